@@ -0,0 +1,177 @@
+//! The stable entry points for using gcmake as a library.
+//!
+//! Everything here returns a [`GCMakeError`] on failure instead of exiting the process. The other
+//! public modules are the binary's implementation and can change between any two releases.
+//!
+//! Project loading still prints gcmake's warnings, and respects the warning policy set by
+//! [`deny_all_warnings`] and the root project's `warnings` section. Use [`num_denied_warnings`]
+//! to find out whether any denied warnings were emitted.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{file_writers, project_info::{dep_graph_loader::load_graph, final_project_data::FinalProjectLoadContext, raw_data_in::dependencies::all_raw_supported_dependency_configs, validators::is_valid_target_name, GivenConstraintSpecParseContext, platform_spec_parser::parse_leading_constraint_spec}};
+
+pub use crate::{
+  gcmake_error::GCMakeError,
+  logger::{deny_all_warnings, num_denied_warnings, WarningId},
+  file_writers::{GeneratedFileSet, StaleGeneratedFile, CMakeDataSchemaKind},
+  project_info::{
+    LinkSpecifier,
+    SystemSpecifierWrapper,
+    final_project_data::{FinalProjectData, UseableFinalProjectDataGroup},
+    dependency_graph_mod::dependency_graph::{DependencyGraph, DependencyGraphInfoWrapper, DependencyGraphWarningMode, TargetNode, ProjectWrapper},
+    raw_data_in::{RawProject, RawSubproject, RawTestProject, dependencies::RawPredefinedDependencyMap}
+  }
+};
+
+/// Loads every predefined dependency configuration from the configured dependency config
+/// repositories (by default, `~/.gcmake/gcmake-dependency-configs`).
+pub fn load_dependency_configs() -> Result<RawPredefinedDependencyMap, GCMakeError> {
+  return all_raw_supported_dependency_configs()
+    .map_err(GCMakeError::Config);
+}
+
+/// Loads the whole project tree which `project_dir` belongs to. `project_dir` can be the root
+/// project or any subproject or test project inside it, which is then available as
+/// `operating_on` in the returned group.
+///
+/// Returns [`GCMakeError::Config`] when any `cmake_data.yaml` in the tree is invalid.
+pub fn load_project(
+  project_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) -> Result<UseableFinalProjectDataGroup, GCMakeError> {
+  return FinalProjectData::new(project_dir, dep_config, FinalProjectLoadContext::default())
+    .map_err(GCMakeError::from);
+}
+
+/// Resolves every link in a loaded project tree into a dependency graph.
+///
+/// Returns [`GCMakeError::Graph`] when a link is invalid or targets depend on each other in a cycle.
+pub fn load_dependency_graph<'a>(
+  project_group: &'a UseableFinalProjectDataGroup,
+  warning_mode: DependencyGraphWarningMode
+) -> Result<DependencyGraphInfoWrapper<'a>, GCMakeError> {
+  return load_graph(project_group, warning_mode);
+}
+
+/// Finds the graph of an already loaded project, such as the group's `operating_on` project.
+pub fn find_project_graph<'a>(
+  graph_info: &DependencyGraphInfoWrapper<'a>,
+  project: &Rc<FinalProjectData>
+) -> Option<Rc<RefCell<DependencyGraph<'a>>>> {
+  return graph_info.root_dep_graph
+    .as_ref()
+    .borrow()
+    .find_using_project_data(project);
+}
+
+/// Finds targets the same way the `target-info` command does. `selector` is either a target name
+/// in `project_graph`, or a link specifier such as `some-dep::{ first, second }`.
+///
+/// Returns [`GCMakeError::Config`] if the selector can't be parsed, and [`GCMakeError::Graph`]
+/// if any selected target doesn't exist.
+pub fn find_targets<'a>(
+  project_graph: &DependencyGraph<'a>,
+  selector: &str
+) -> Result<Vec<Rc<RefCell<TargetNode<'a>>>>, GCMakeError> {
+  let search_results = if is_valid_target_name(selector) {
+    vec![project_graph.find_single_target_by_name(selector)]
+  }
+  else {
+    project_graph.find_targets_using_link_spec(false, &parse_link_specifier(selector)?)
+      .map_err(GCMakeError::Graph)?
+  };
+
+  return search_results.into_iter()
+    .map(|search_result| match search_result.target {
+      Some(target) => Ok(target),
+      None => Err(GCMakeError::Graph(format!(
+        "Unable to find '{}' in project [{}]",
+        search_result.searched_with,
+        search_result.searched_project.as_ref().borrow().project_debug_name()
+      )))
+    })
+    .collect();
+}
+
+/// Parses a link specifier like the ones written in an output item's `link` section.
+pub fn parse_link_specifier(link_spec: &str) -> Result<LinkSpecifier, GCMakeError> {
+  return LinkSpecifier::parse_with_full_permissions(link_spec, None)
+    .map_err(GCMakeError::Config);
+}
+
+/// Parses a standalone system specifier such as `((windows or linux))`.
+/// An empty string specifies all systems.
+pub fn parse_system_specifier(system_spec: &str) -> Result<SystemSpecifierWrapper, GCMakeError> {
+  let parse_context = GivenConstraintSpecParseContext {
+    maybe_valid_feature_list: None,
+    is_before_output_name: false
+  };
+
+  return match parse_leading_constraint_spec(system_spec, parse_context).map_err(GCMakeError::Config)? {
+    None if system_spec.trim().is_empty() => Ok(SystemSpecifierWrapper::default_include_all()),
+    Some(parse_success) if parse_success.rest.trim().is_empty() => Ok(parse_success.value),
+    _ => Err(GCMakeError::Config(format!(
+      "'{}' is not a system specifier. System specifiers look like this: ((windows or linux))",
+      system_spec
+    )))
+  }
+}
+
+/// Renders every file gcmake would generate for the project tree, without writing anything.
+pub fn render_project_configs<'a>(
+  graph_info: &'a DependencyGraphInfoWrapper<'a>
+) -> Result<GeneratedFileSet, GCMakeError> {
+  return file_writers::render_configurations(graph_info)
+    .map_err(GCMakeError::from);
+}
+
+/// Generates and writes every gcmake-generated file (CMakeLists.txt, install scripts and package
+/// manifests) for the project tree. Every configuration is still written when an earlier one
+/// fails, but the first failure is returned.
+pub fn write_project_configs<'a>(
+  graph_info: &'a DependencyGraphInfoWrapper<'a>
+) -> Result<(), GCMakeError> {
+  let first_failure: RefCell<Option<GCMakeError>> = RefCell::new(None);
+
+  file_writers::write_configurations(
+    graph_info,
+    |_| (),
+    |(config_name, config_result)| if let Err(err) = config_result {
+      first_failure.borrow_mut().get_or_insert(GCMakeError::Io(format!(
+        "Writing {} configuration failed with error: {}",
+        config_name,
+        err
+      )));
+    }
+  )?;
+
+  return match first_failure.into_inner() {
+    Some(failure) => Err(failure),
+    None => Ok(())
+  }
+}
+
+/// Generates a JSON Schema (draft 7) describing one kind of `cmake_data.yaml` file. When
+/// `predefined_dep_names` isn't empty, a root project's `predefined_dependencies` can only use
+/// those names.
+pub fn cmake_data_schema(
+  schema_kind: CMakeDataSchemaKind,
+  predefined_dep_names: &[String]
+) -> Result<String, GCMakeError> {
+  return file_writers::export_cmake_data_schema(schema_kind, predefined_dep_names)
+    .map_err(GCMakeError::Config);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_system_specifier_rejects_trailing_text() {
+    assert!(parse_system_specifier("").unwrap().includes_all());
+    assert!(!parse_system_specifier("((windows or linux))").unwrap().includes_all());
+    assert!(parse_system_specifier("((windows)) some-target").is_err());
+    assert!(parse_system_specifier("windows").is_err());
+  }
+}
@@ -1,5 +1,6 @@
 use clap::{Subcommand, Args, Parser, ValueEnum};
 use clap::builder::styling::{AnsiColor, Styles};
+use serde::Deserialize;
 
 const SKY: &'static str = "Skylar Cupit";
 
@@ -13,7 +14,8 @@ fn help_styling() -> Styles {
     .placeholder(AnsiColor::Yellow.on_default())
 }
 
-#[derive(ValueEnum, Clone)]
+#[derive(ValueEnum, Clone, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum CLIProjectOutputTypeIn {
   Exe,
   StaticLib,
@@ -28,6 +30,19 @@ pub struct Opts {
   #[arg(default_value = ".")]
   pub project_root: String,
 
+  /// Print a diff of what would change in the generated files instead of writing them.
+  #[arg(long = "dry-run")]
+  pub dry_run: bool,
+
+  /// Fail if any generated files are out of date with the project configuration.
+  /// Nothing is written.
+  #[arg(long = "check", conflicts_with = "dry_run")]
+  pub check_up_to_date: bool,
+
+  /// Treat every warning as an error, except for warnings the root project explicitly allows.
+  #[arg(long = "deny-warnings", global = true)]
+  pub deny_warnings: bool,
+
   #[command(subcommand)]
   pub subcommand: Option<SubCommandStruct>
 }
@@ -41,10 +56,30 @@ pub enum SubCommandStruct {
   /// Generate code files in-tree.
   GenFile(CreateFilesCommand),
 
+  /// Move or rename a header/source/template-impl set, updating every include of the moved
+  /// files in the project tree.
+  MvFile(MoveFilesCommand),
+
   /// Subcommand for working with the 'external dependency configuration repository'.
   #[command(subcommand)]
   DepConfig(DepConfigSubCommand),
 
+  /// Inspect and clean up the dependency cache (~/.gcmake/dep-cache).
+  #[command(subcommand)]
+  DepCache(DepCacheSubCommand),
+
+  /// Add a predefined dependency to the root project's cmake_data.yaml, then regenerate the project.
+  AddDep(AddPredefinedDependencyCommand),
+
+  /// Pin the exact revision of every downloaded dependency in gcmake.lock, then regenerate the project.
+  Lock(LockDependenciesCommand),
+
+  /// Add links to a project output's cmake_data.yaml link section, then regenerate the project.
+  AddLink(AddLinkCommand),
+
+  /// Remove links from a project output's cmake_data.yaml link section, then regenerate the project.
+  RemoveLink(RemoveLinkCommand),
+
   /// Copy a default file from ~/.gcmake into the project root.
   UseFile(UseFilesCommand),
 
@@ -58,11 +93,29 @@ pub enum SubCommandStruct {
   /// Select and print information about projects. Dependency print information is limited.
   ProjectInfo(ProjectInfoCommand),
 
+  /// Print the dependency tree of the selected targets, or of every target in the current project.
+  DepGraph(DepGraphCommand),
+
+  /// Export the fully resolved project model (projects, outputs, links, build configs, and
+  /// dependencies) in a machine-readable format.
+  DumpModel(DumpModelCommand),
+
+  /// Show the effective build settings of a target.
+  #[command(subcommand)]
+  Show(ShowSubcommand),
+
   /// Select and print information about predefined dependencies
   PredepInfo(PredepInfoCommand),
 
   /// Print information about the GCMake tool itself
-  ToolInfo(ToolInfoCommand)
+  ToolInfo(ToolInfoCommand),
+
+  /// Validate the project tree without writing any files. Reports the problems in every
+  /// cmake_data.yaml at once, and exits with a non-zero status code if any are found.
+  Check(CheckCommand),
+
+  /// Print a JSON Schema for cmake_data.yaml files, for use with editor completion and validation.
+  Schema(SchemaCommand)
 }
 
 #[derive(Subcommand)]
@@ -106,7 +159,23 @@ pub struct NewRootProjectCommand {
   pub no_emscripten: bool,
 
   #[arg(long)]
-  pub with_cuda: bool
+  pub with_cuda: bool,
+
+  /// Vendor name (your name or organization). Skips the vendor prompt.
+  #[arg(long)]
+  pub vendor: Option<String>,
+
+  /// Name of the first header/source pair created for compiled library projects.
+  /// Skips the initial file pair name prompt.
+  #[arg(long = "initial-file")]
+  pub initial_file_name: Option<String>,
+
+  /// Name of a project template in ~/.gcmake/templates to create the project from.
+  #[arg(long)]
+  pub template: Option<String>,
+
+  #[command(flatten)]
+  pub answers: NewProjectAnswerArgs
 }
 
 /// Generate a new subproject
@@ -130,7 +199,15 @@ pub struct NewSubprojectCommand {
   pub cpp2: bool,
 
   #[arg(value_enum, short, long, name = "type")]
-  pub subproject_type: Option<CLIProjectOutputTypeIn>
+  pub subproject_type: Option<CLIProjectOutputTypeIn>,
+
+  /// Name of the first header/source pair created for compiled library projects.
+  /// Skips the initial file pair name prompt.
+  #[arg(long = "initial-file")]
+  pub initial_file_name: Option<String>,
+
+  #[command(flatten)]
+  pub answers: NewProjectAnswerArgs
 }
 
 /// Generate a new test.
@@ -141,6 +218,41 @@ pub struct NewTestProjectCommand {
   /// Project name, no whitespace
   #[arg(required = true)]
   pub new_project_name: String,
+
+  /// The test provides its own main function instead of using the one provided by the
+  /// test framework. Skips the custom main prompt.
+  #[arg(long)]
+  pub custom_main: bool,
+
+  #[command(flatten)]
+  pub answers: NewProjectAnswerArgs
+}
+
+/// Prompt answers shared by every kind of new project. Any prompt without an answer is
+/// still shown, unless stdin is not a terminal. In that case the command fails instead.
+#[derive(Args)]
+pub struct NewProjectAnswerArgs {
+  /// YAML file containing answers to the project creation prompts. Answers given as
+  /// flags take precedence over the ones in this file.
+  #[arg(long = "answers", value_name = "FILE")]
+  pub answers_file: Option<String>,
+
+  /// Skips the include prefix prompt.
+  #[arg(long)]
+  pub include_prefix: Option<String>,
+
+  /// Project description. Skips the description prompt.
+  #[arg(long)]
+  pub description: Option<String>,
+
+  /// Overwrite the project directory if it already exists, instead of asking first.
+  #[arg(long, conflicts_with = "no_overwrite")]
+  pub overwrite: bool,
+
+  /// Keep the project directory if it already exists, instead of asking first. No project
+  /// is created in that case.
+  #[arg(long = "no-overwrite")]
+  pub no_overwrite: bool
 }
 
 #[derive(ValueEnum, Clone, Copy)]
@@ -174,10 +286,27 @@ pub struct CreateFilesCommand {
   pub should_files_be_private: bool
 }
 
+#[derive(Args)]
+pub struct MoveFilesCommand {
+  /// Path to any file in the set being moved. Example: include/MY-LIB/foo/bar.hpp
+  #[arg(required = true)]
+  pub from: String,
+
+  /// New path for the set, which can be in another project in the tree. Extensions are optional.
+  /// When this is a directory (or ends with '/'), the files keep their current name.
+  /// Example: subprojects/util/include/MY-LIB/UTIL/bar
+  #[arg(required = true)]
+  pub to: String
+}
+
 #[derive(Subcommand)]
 pub enum DepConfigSubCommand {
-  /// Update the dependency configuration repo. Downloads the repo if it is not already present.
-  Update(UpdateDependencyConfigsCommand)
+  /// Update the dependency configuration repos. Downloads each repo if it is not already present.
+  Update(UpdateDependencyConfigsCommand),
+
+  /// Load and validate predefined dependency configurations. Checks every configuration
+  /// when no names are given.
+  Check(CheckDependencyConfigsCommand)
 }
 
 #[derive(Args)]
@@ -187,6 +316,135 @@ pub struct UpdateDependencyConfigsCommand {
   /// updated or the repo is cloned into the 'develop' branch.
   #[arg(long = "to-branch", short = 'b')]
   pub branch: Option<String>,
+
+  /// Only update the dependency configuration repo with this name. Required when selecting a
+  /// branch while multiple repos are configured.
+  #[arg(long = "repo", short = 'r')]
+  pub repo_name: Option<String>
+}
+
+#[derive(Args)]
+pub struct CheckDependencyConfigsCommand {
+  /// Names of the predefined dependencies to check
+  #[arg(required = false)]
+  pub dep_names: Vec<String>
+}
+
+#[derive(Subcommand)]
+pub enum DepCacheSubCommand {
+  /// List every cached dependency version along with its size.
+  List(ListDepCacheCommand),
+
+  /// Remove cached dependencies. Removes the whole cache when no dependency is given.
+  Clean(CleanDepCacheCommand),
+
+  /// Remove every cached dependency version which isn't used by any of the given projects.
+  Prune(PruneDepCacheCommand)
+}
+
+#[derive(Args)]
+pub struct ListDepCacheCommand {
+  /// Only list cache entries of this dependency
+  #[arg(required = false)]
+  pub dep_name: Option<String>
+}
+
+#[derive(Args)]
+pub struct CleanDepCacheCommand {
+  /// Name of the dependency whose cached versions should be removed
+  #[arg(required = false)]
+  pub dep_name: Option<String>,
+
+  /// Don't ask for confirmation before removing anything.
+  #[arg(long = "yes", short = 'y')]
+  pub skip_confirmation: bool
+}
+
+#[derive(Args)]
+pub struct PruneDepCacheCommand {
+  /// Root directories of the projects whose dependencies should be kept
+  #[arg(required = true)]
+  pub project_roots: Vec<String>,
+
+  /// Only print which cache entries would be removed.
+  #[arg(long = "dry-run")]
+  pub dry_run: bool,
+
+  /// Don't ask for confirmation before removing anything.
+  #[arg(long = "yes", short = 'y')]
+  pub skip_confirmation: bool
+}
+
+fn parse_key_value_pair(given_pair: &str) -> Result<(String, String), String> {
+  return match given_pair.split_once('=') {
+    Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+    _ => Err(format!("'{}' should be formatted like KEY=VALUE", given_pair))
+  }
+}
+
+#[derive(Args)]
+pub struct LockDependenciesCommand {
+  /// Re-resolve locked revisions even if their entries are up to date. Without this, only
+  /// dependencies which are missing from gcmake.lock or whose configuration changed are resolved.
+  #[arg(long = "update")]
+  pub update: bool,
+
+  /// Dependencies to update. Updates all dependencies when none are given.
+  #[arg(requires = "update")]
+  pub dep_names: Vec<String>
+}
+
+#[derive(Args)]
+pub struct AddPredefinedDependencyCommand {
+  /// Name of the predefined dependency to add
+  #[arg(required = true)]
+  pub dep_name: String,
+
+  /// Git tag to download. Only valid for dependencies which can be downloaded using git.
+  #[arg(long = "git-tag", conflicts_with_all = ["commit_hash", "file_version"])]
+  pub git_tag: Option<String>,
+
+  /// Git commit hash to download. Only valid for dependencies which can be downloaded using git.
+  #[arg(long = "commit", conflicts_with = "file_version")]
+  pub commit_hash: Option<String>,
+
+  /// Archive version to download. Only valid for dependencies which can be downloaded from a URL.
+  #[arg(long = "file-version")]
+  pub file_version: Option<String>,
+
+  /// Set one of the dependency's configuration options. Formatted like KEY=VALUE, and can be
+  /// given multiple times.
+  #[arg(long = "option", value_parser = parse_key_value_pair)]
+  pub options: Vec<(String, String)>
+}
+
+#[derive(Args)]
+pub struct AddLinkCommand {
+  /// The project output to add the links to. Uses the same selector format as target-info,
+  /// but must select exactly one output from the project tree.
+  #[arg(required = true)]
+  pub target: String,
+
+  /// Links to add, in the same format used by an output's 'link' section.
+  /// Example: 'fmt::fmt' or '((windows)) self::{ first-lib, second-lib }'
+  #[arg(required = true)]
+  pub link_specs: Vec<String>,
+
+  /// Add the links to the output's 'public' list. Only valid for compiled libraries, which
+  /// otherwise have their links added to the 'private' list.
+  #[arg(long = "public")]
+  pub is_public: bool
+}
+
+#[derive(Args)]
+pub struct RemoveLinkCommand {
+  /// The project output to remove the links from. Uses the same selector format as add-link.
+  #[arg(required = true)]
+  pub target: String,
+
+  /// Links to remove, exactly as written in the output's 'link' section (whitespace is ignored).
+  #[arg(required = true)]
+  pub link_specs: Vec<String>
 }
 
 #[derive(ValueEnum, Clone, Copy)]
@@ -257,6 +515,105 @@ pub struct TargetInfoCommand {
   pub item_type: bool
 }
 
+#[derive(Args)]
+pub struct DepGraphCommand {
+  /// Select which targets to print dependency trees for. Uses the same selector format
+  /// as target-info. When omitted, prints the tree for every target in the current project.
+  #[arg(required = false)]
+  pub selectors: Vec<String>,
+
+  /// Output format. 'dot' and 'mermaid' export the graph for use in other tools.
+  #[arg(value_enum, short = 'f', long = "format", default_value = "tree")]
+  pub format: DepGraphFormat,
+
+  /// Write the exported graph to this file instead of printing it. Can only be used with
+  /// the 'dot' and 'mermaid' formats.
+  #[arg(short = 'o', long = "output")]
+  pub output_file: Option<String>,
+
+  /// Draw each predefined dependency as a single node instead of one node per target.
+  #[arg(long = "collapse-predefined")]
+  pub collapse_predefined_deps: bool
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum DepGraphFormat {
+  #[value(name = "tree")]
+  Tree,
+  #[value(name = "dot")]
+  Dot,
+  #[value(name = "mermaid")]
+  Mermaid
+}
+
+#[derive(Args)]
+pub struct DumpModelCommand {
+  /// Output format
+  #[arg(value_enum, short = 'f', long = "format", default_value = "json")]
+  pub format: ModelDumpFormat,
+
+  /// Write the model to this file instead of printing it.
+  #[arg(short = 'o', long = "output")]
+  pub output_file: Option<String>
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum ModelDumpFormat {
+  #[value(name = "json")]
+  Json
+}
+
+#[derive(Subcommand)]
+pub enum ShowSubcommand {
+  /// Print the compiler flags, link-time flags and linker flags a target is built with
+  /// for the given build configuration and compiler.
+  Flags(ShowBuildConfigCommand),
+
+  /// Print the defines a target is built with for the given build configuration and compiler.
+  Defines(ShowBuildConfigCommand)
+}
+
+#[derive(Args)]
+pub struct ShowBuildConfigCommand {
+  /// Select which targets to print settings for. Uses the same selector format as target-info.
+  #[arg(required = true)]
+  pub selectors: Vec<String>,
+
+  /// Build configuration. Defaults to the project's default_build_type.
+  #[arg(value_enum, short = 'c', long = "config", ignore_case = true)]
+  pub build_type: Option<CLIBuildType>,
+
+  /// Compiler to show the settings for
+  #[arg(value_enum, long = "compiler", ignore_case = true, required = true)]
+  pub compiler: CLICompilerSpecifier
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum CLIBuildType {
+  #[value(name = "Debug")]
+  Debug,
+  #[value(name = "Release")]
+  Release,
+  #[value(name = "MinSizeRel")]
+  MinSizeRel,
+  #[value(name = "RelWithDebInfo")]
+  RelWithDebInfo
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum CLICompilerSpecifier {
+  #[value(name = "gcc")]
+  GCC,
+  #[value(name = "clang")]
+  Clang,
+  #[value(name = "msvc")]
+  MSVC,
+  #[value(name = "cuda")]
+  CUDA,
+  #[value(name = "emscripten")]
+  Emscripten
+}
+
 #[derive(Args)]
 pub struct ProjectInfoCommand {
   /// Select which projects to print info for 
@@ -326,6 +683,10 @@ pub struct PredepInfoCommand {
   /// Prints whether the dependency supports can be compiled in a project which supports Emscripten
   #[arg(long = "supports-emscripten")]
   pub show_supports_emscripten: bool,
+
+  /// Print the directory the dependency's configuration is loaded from
+  #[arg(long = "config-dir")]
+  pub show_config_dir: bool,
 }
 
 #[derive(ValueEnum, Clone, Copy)]
@@ -371,10 +732,39 @@ pub struct ToolInfoCommand {
   #[arg(long = "dep-cache")]
   pub show_dep_cache_dir: bool,
 
-  /// Print the dependency config dir
+  /// Print the dependency config dirs, in order of precedence
   #[arg(long = "dep-config")]
   pub show_dep_config_dir: bool,
 
   #[command(subcommand)]
   pub part: Option<SpecificToolPartSubcommand>
 }
+
+#[derive(Args)]
+pub struct CheckCommand { }
+
+#[derive(Args)]
+pub struct SchemaCommand {
+  /// Which kind of project's cmake_data.yaml the schema describes
+  #[arg(value_enum, default_value = "root")]
+  pub project_type: SchemaProjectType,
+
+  /// Don't restrict predefined_dependencies to the dependencies currently available in the
+  /// dependency config repositories.
+  #[arg(long = "any-dep-names")]
+  pub allow_any_dep_names: bool,
+
+  /// Write the schema to this file instead of printing it.
+  #[arg(short = 'o', long = "output")]
+  pub output_file: Option<String>
+}
+
+#[derive(ValueEnum, Clone, Copy)]
+pub enum SchemaProjectType {
+  #[value(name = "root")]
+  Root,
+  #[value(name = "subproject")]
+  Subproject,
+  #[value(name = "test")]
+  Test
+}
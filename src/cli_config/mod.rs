@@ -2,8 +2,11 @@ use std::collections::HashSet;
 
 use crate::{project_generator::configuration::{MainFileLanguage, CreationProjectOutputType, OutputLibType}, project_info::raw_data_in::SpecificCompilerSpecifier};
 
-use self::clap_cli_config::{CLIProjectOutputTypeIn, NewProjectSubcommand};
+use self::clap_cli_config::{CLIProjectOutputTypeIn, NewProjectSubcommand, NewProjectAnswerArgs};
 pub mod clap_cli_config;
+mod new_project_answers;
+
+pub use new_project_answers::*;
 
 pub enum CLIProjectTypeGenerating {
   RootProject,
@@ -12,12 +15,29 @@ pub enum CLIProjectTypeGenerating {
 }
 
 pub struct CLIProjectGenerationInfo {
-  pub language: Option<MainFileLanguage>,
   pub project_name: String,
   pub project_type: CLIProjectTypeGenerating,
-  pub project_output_type: Option<CreationProjectOutputType>,
   pub supported_compilers: HashSet<SpecificCompilerSpecifier>,
-  pub should_use_cpp2_main_if_possible: bool
+  pub should_use_cpp2_main_if_possible: bool,
+  // Answers given as flags. Language and output type are included here.
+  pub answers: NewProjectAnswers,
+  pub answers_file: Option<String>,
+  pub template_name: Option<String>
+}
+
+fn answers_from_args(answer_args: NewProjectAnswerArgs) -> (NewProjectAnswers, Option<String>) {
+  let answers = NewProjectAnswers {
+    overwrite: match (answer_args.overwrite, answer_args.no_overwrite) {
+      (true, _) => Some(true),
+      (_, true) => Some(false),
+      _ => None
+    },
+    include_prefix: answer_args.include_prefix,
+    description: answer_args.description,
+    ..NewProjectAnswers::default()
+  };
+
+  return (answers, answer_args.answers_file);
 }
 
 impl From<NewProjectSubcommand> for CLIProjectGenerationInfo {
@@ -44,13 +64,22 @@ impl From<NewProjectSubcommand> for CLIProjectGenerationInfo {
           supported_compilers.insert(SpecificCompilerSpecifier::CUDA);
         }
 
+        let (common_answers, answers_file) = answers_from_args(project_info.answers);
+
         return CLIProjectGenerationInfo {
           project_name: project_info.new_project_name,
-          language,
           project_type: CLIProjectTypeGenerating::RootProject,
-          project_output_type: convert_given_project_type(&project_info.project_type),
           supported_compilers,
-          should_use_cpp2_main_if_possible: project_info.cpp2
+          should_use_cpp2_main_if_possible: project_info.cpp2,
+          answers: NewProjectAnswers {
+            language,
+            project_output_type: convert_given_project_type(&project_info.project_type),
+            vendor: project_info.vendor,
+            initial_file_name: project_info.initial_file_name,
+            ..common_answers
+          },
+          answers_file,
+          template_name: project_info.template
         }
       },
       NewProjectSubcommand::Subproject(subproject_info) => {
@@ -60,25 +89,41 @@ impl From<NewProjectSubcommand> for CLIProjectGenerationInfo {
           else if subproject_info.c     { Some(MainFileLanguage::C) }
           else                          { None };
         
+        let (common_answers, answers_file) = answers_from_args(subproject_info.answers);
+
         return CLIProjectGenerationInfo {
           project_name: subproject_info.new_project_name,
-          language,
           project_type: CLIProjectTypeGenerating::Subproject,
-          project_output_type: convert_given_project_type(&subproject_info.subproject_type),
           // This will be ignored for subprojects
           supported_compilers: HashSet::new(),
-          should_use_cpp2_main_if_possible: subproject_info.cpp2
+          should_use_cpp2_main_if_possible: subproject_info.cpp2,
+          answers: NewProjectAnswers {
+            language,
+            project_output_type: convert_given_project_type(&subproject_info.subproject_type),
+            initial_file_name: subproject_info.initial_file_name,
+            ..common_answers
+          },
+          answers_file,
+          template_name: None
         }
       },
       NewProjectSubcommand::Test(test_project_info) =>  {
+        let (common_answers, answers_file) = answers_from_args(test_project_info.answers);
+
         return CLIProjectGenerationInfo {
           project_name: test_project_info.new_project_name,
-          language: Some(MainFileLanguage::Cpp),
           project_type: CLIProjectTypeGenerating::Test,
-          project_output_type: Some(CreationProjectOutputType::Executable),
           // This will be ignored for test projects
           supported_compilers: HashSet::new(),
-          should_use_cpp2_main_if_possible: false
+          should_use_cpp2_main_if_possible: false,
+          answers: NewProjectAnswers {
+            language: Some(MainFileLanguage::Cpp),
+            project_output_type: Some(CreationProjectOutputType::Executable),
+            requires_custom_main: if test_project_info.custom_main { Some(true) } else { None },
+            ..common_answers
+          },
+          answers_file,
+          template_name: None
         }
       }
     }
@@ -0,0 +1,117 @@
+use std::fs;
+
+use colored::*;
+use serde::Deserialize;
+
+use crate::{project_generator::configuration::{MainFileLanguage, CreationProjectOutputType}, project_info::validators::{is_valid_base_include_prefix, is_valid_relative_code_file_path}};
+
+use super::{clap_cli_config::CLIProjectOutputTypeIn, convert_given_project_type};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RawAnsweredLanguage {
+  C,
+  Cpp,
+  Cpp2
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawNewProjectAnswers {
+  overwrite: Option<bool>,
+  include_prefix: Option<String>,
+  language: Option<RawAnsweredLanguage>,
+  #[serde(rename = "type")]
+  project_type: Option<CLIProjectOutputTypeIn>,
+  vendor: Option<String>,
+  description: Option<String>,
+  requires_custom_main: Option<bool>,
+  initial_file_name: Option<String>
+}
+
+// Answers to the project creation prompts which were given up front, either as flags or in
+// an --answers file. Prompts are only shown for answers which are missing.
+#[derive(Default, Clone)]
+pub struct NewProjectAnswers {
+  pub overwrite: Option<bool>,
+  pub include_prefix: Option<String>,
+  pub language: Option<MainFileLanguage>,
+  pub project_output_type: Option<CreationProjectOutputType>,
+  pub vendor: Option<String>,
+  pub description: Option<String>,
+  pub requires_custom_main: Option<bool>,
+  pub initial_file_name: Option<String>
+}
+
+impl NewProjectAnswers {
+  pub fn from_file(file_path: &str) -> Result<Self, String> {
+    let file_contents: String = fs::read_to_string(file_path)
+      .map_err(|err| format!("Failed to read answers file '{}': {}", file_path, err.to_string()))?;
+
+    let raw_answers: RawNewProjectAnswers = serde_yaml::from_str(&file_contents)
+      .map_err(|err| format!("Failed to parse answers file '{}': {}", file_path, err.to_string()))?;
+
+    return Ok(Self {
+      overwrite: raw_answers.overwrite,
+      include_prefix: raw_answers.include_prefix,
+      language: raw_answers.language.map(|language| match language {
+        RawAnsweredLanguage::C => MainFileLanguage::C,
+        RawAnsweredLanguage::Cpp => MainFileLanguage::Cpp,
+        RawAnsweredLanguage::Cpp2 => MainFileLanguage::Cpp2
+      }),
+      project_output_type: convert_given_project_type(&raw_answers.project_type),
+      vendor: raw_answers.vendor,
+      description: raw_answers.description,
+      requires_custom_main: raw_answers.requires_custom_main,
+      initial_file_name: raw_answers.initial_file_name
+    });
+  }
+
+  // Fills in any answers not given here using the fallback answers. Used so that answers given
+  // as flags take precedence over those in the answers file.
+  pub fn or(self, fallback: Self) -> Self {
+    return Self {
+      overwrite: self.overwrite.or(fallback.overwrite),
+      include_prefix: self.include_prefix.or(fallback.include_prefix),
+      language: self.language.or(fallback.language),
+      project_output_type: self.project_output_type.or(fallback.project_output_type),
+      vendor: self.vendor.or(fallback.vendor),
+      description: self.description.or(fallback.description),
+      requires_custom_main: self.requires_custom_main.or(fallback.requires_custom_main),
+      initial_file_name: self.initial_file_name.or(fallback.initial_file_name)
+    }
+  }
+
+  // Given answers skip the prompts, so they need the same validation the prompts would have done.
+  pub fn validate(&self) -> Result<(), String> {
+    if let Some(include_prefix) = &self.include_prefix {
+      if !is_valid_base_include_prefix(include_prefix) {
+        return Err(format!("'{}' is not a valid include prefix.", include_prefix.yellow()));
+      }
+    }
+
+    if let Some(file_name) = &self.initial_file_name {
+      if !is_valid_relative_code_file_path(file_name) {
+        return Err(format!("'{}' is not a valid initial file name.", file_name.yellow()));
+      }
+    }
+
+    for (answer_name, maybe_answer) in [("vendor", &self.vendor), ("description", &self.description)] {
+      if maybe_answer.as_ref().map_or(false, |answer| answer.trim().is_empty()) {
+        return Err(format!("The {} must not be empty.", answer_name));
+      }
+    }
+
+    Ok(())
+  }
+}
+
+// Explains how to give an answer up front, for when it can't be prompted for.
+pub fn how_to_answer(answer_description: &str, flag: &str, answers_file_key: &str) -> String {
+  return format!(
+    "Pass {} or set '{}' in the --answers file to give the {}.",
+    flag.yellow(),
+    answers_file_key.yellow(),
+    answer_description
+  );
+}
@@ -1,4 +1,5 @@
 pub mod prompt;
+pub mod yaml_editing;
 
 use base64ct::{Base64Url, Encoding};
 
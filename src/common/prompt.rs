@@ -1,9 +1,19 @@
-use std::{io::{self, stdin, Write}, fmt::Display};
+use std::{io::{self, stdin, BufRead, Write, IsTerminal}, fmt::Display};
 
 use colored::Colorize;
 
 pub fn prompt_until_custom<F, T>(prompt: impl Display, transforming_predicate: F) -> io::Result<T>
   where F: Fn(&str) -> Option<T>
+{
+  return prompt_until_custom_reading(&mut stdin().lock(), prompt, transforming_predicate);
+}
+
+fn prompt_until_custom_reading<F, T>(
+  input: &mut impl BufRead,
+  prompt: impl Display,
+  transforming_predicate: F
+) -> io::Result<T>
+  where F: Fn(&str) -> Option<T>
 {
   let mut buffer = String::new();
 
@@ -12,7 +22,14 @@ pub fn prompt_until_custom<F, T>(prompt: impl Display, transforming_predicate: F
 
     print!("{}: ", prompt);
     io::stdout().flush()?;
-    stdin().read_line(&mut buffer)?;
+
+    if input.read_line(&mut buffer)? == 0 {
+      // Nothing more can be read, so prompting again would loop forever.
+      return Err(io::Error::new(
+        io::ErrorKind::UnexpectedEof,
+        format!("Reached the end of input while waiting for an answer to '{}'", prompt)
+      ));
+    }
 
     if let Some(success_value) = (transforming_predicate)(buffer.trim()) {
       return Ok(success_value);
@@ -20,6 +37,55 @@ pub fn prompt_until_custom<F, T>(prompt: impl Display, transforming_predicate: F
   }
 }
 
+// Prompts are only answerable when a user is typing into a terminal. Piped or redirected
+// input can't be relied on to contain answers.
+pub fn is_interactive() -> bool {
+  stdin().is_terminal()
+}
+
+// Uses the answer if one was already given, otherwise prompts for it. Fails with the given help
+// message instead of prompting when not running interactively.
+pub fn answer_or_prompt<T>(
+  given_answer: Option<T>,
+  missing_answer_help: impl Display,
+  prompt: impl FnOnce() -> io::Result<T>
+) -> io::Result<T> {
+  return answer_or_prompt_if_interactive(given_answer, is_interactive(), missing_answer_help, prompt);
+}
+
+fn answer_or_prompt_if_interactive<T>(
+  given_answer: Option<T>,
+  can_prompt: bool,
+  missing_answer_help: impl Display,
+  prompt: impl FnOnce() -> io::Result<T>
+) -> io::Result<T> {
+  if let Some(answer) = given_answer {
+    return Ok(answer);
+  }
+
+  if !can_prompt {
+    return Err(io::Error::new(
+      io::ErrorKind::Other,
+      format!("Unable to prompt for an answer because stdin is not a terminal. {}", missing_answer_help)
+    ));
+  }
+
+  return prompt();
+}
+
+// Same as answer_or_prompt, except the prompt's default value is used when not running interactively.
+pub fn answer_or_prompt_with_default<T>(
+  given_answer: Option<T>,
+  default_value: T,
+  prompt: impl FnOnce() -> io::Result<T>
+) -> io::Result<T> {
+  return match given_answer {
+    Some(answer) => Ok(answer),
+    None if !is_interactive() => Ok(default_value),
+    None => prompt()
+  }
+}
+
 pub fn prompt_until_custom_or_default<F, T: Clone>(
   prompt: impl Display,
   transforming_predicate: F,
@@ -161,3 +227,51 @@ pub fn prompt_with_choices<T>(
     }
   )?;
 }
+
+#[cfg(test)]
+mod tests {
+  use std::io::Cursor;
+
+  use super::*;
+
+  fn prompt_from(input: &str) -> impl FnOnce() -> io::Result<bool> + '_ {
+    return move || prompt_until_custom_reading(&mut Cursor::new(input), "Overwrite it?", resolve_boolean_from_str);
+  }
+
+  #[test]
+  fn test_given_answers_are_used_without_prompting() {
+    let answer = answer_or_prompt_if_interactive(
+      Some(false),
+      true,
+      "help",
+      || -> io::Result<bool> { panic!("Prompted even though an answer was given") }
+    );
+
+    assert_eq!(answer.unwrap(), false);
+  }
+
+  #[test]
+  fn test_missing_answers_are_prompted_for_until_valid() {
+    assert_eq!(answer_or_prompt_if_interactive(None, true, "help", prompt_from("maybe\ny\n")).unwrap(), true);
+  }
+
+  #[test]
+  fn test_missing_answers_fail_with_help_when_not_interactive() {
+    let err = answer_or_prompt_if_interactive(
+      None,
+      false,
+      "Pass --overwrite",
+      || -> io::Result<bool> { panic!("Prompted without a terminal") }
+    ).unwrap_err();
+
+    assert!(err.to_string().contains("Pass --overwrite"));
+  }
+
+  #[test]
+  fn test_reaching_end_of_input_is_an_error() {
+    for input in ["", "maybe\n"] {
+      let err = answer_or_prompt_if_interactive(None, true, "help", prompt_from(input)).unwrap_err();
+      assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+  }
+}
@@ -0,0 +1,556 @@
+// Minimal line-based editing of block style YAML documents, used by commands which modify
+// cmake_data.yaml. Round-tripping through serde_yaml would lose comments, key order and formatting,
+// so instead only the lines which need to change are touched.
+//
+// Only block mappings can be traversed. Flow style collections ({ } and [ ]) are only handled
+// when they are empty.
+
+use std::ops::Range;
+
+use colored::*;
+
+const DEFAULT_INDENT_WIDTH: usize = 2;
+
+pub struct YamlDocument {
+  lines: Vec<String>,
+  line_ending: &'static str,
+  has_trailing_newline: bool
+}
+
+fn indent_of(line: &str) -> usize {
+  line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_ignorable_line(line: &str) -> bool {
+  let trimmed: &str = line.trim();
+  trimmed.is_empty() || trimmed.starts_with('#')
+}
+
+fn is_sequence_item(content: &str) -> bool {
+  content == "-" || content.starts_with("- ")
+}
+
+pub fn without_comment(value: &str) -> &str {
+  let mut in_single_quotes: bool = false;
+  let mut in_double_quotes: bool = false;
+  let mut previous_char: char = ' ';
+
+  for (index, c) in value.char_indices() {
+    match c {
+      '\'' if !in_double_quotes => in_single_quotes = !in_single_quotes,
+      '"' if !in_single_quotes => in_double_quotes = !in_double_quotes,
+      '#' if !in_single_quotes && !in_double_quotes && previous_char.is_whitespace() => {
+        return value[..index].trim_end();
+      },
+      _ => ()
+    }
+    previous_char = c;
+  }
+
+  return value.trim_end();
+}
+
+fn unquoted(value: &str) -> &str {
+  let is_quoted: bool = value.len() >= 2 && (
+    (value.starts_with('"') && value.ends_with('"'))
+      || (value.starts_with('\'') && value.ends_with('\''))
+  );
+
+  return if is_quoted
+    { &value[1..value.len() - 1] }
+    else { value };
+}
+
+// Index of the colon which separates a mapping key from its value.
+fn mapping_colon_index(content: &str) -> Option<usize> {
+  if is_sequence_item(content) || content.starts_with('#') {
+    return None;
+  }
+
+  let mut search_start: usize = 0;
+
+  while let Some(relative_index) = content[search_start..].find(':') {
+    let colon_index: usize = search_start + relative_index;
+    let rest: &str = &content[colon_index + 1..];
+
+    if rest.is_empty() || rest.starts_with(' ') {
+      return Some(colon_index);
+    }
+
+    search_start = colon_index + 1;
+  }
+
+  return None;
+}
+
+// Splits a "key: value" line into its key and (comment stripped) value.
+fn split_mapping_entry(content: &str) -> Option<(&str, &str)> {
+  let colon_index: usize = mapping_colon_index(content)?;
+
+  return Some((
+    unquoted(content[..colon_index].trim()),
+    without_comment(content[colon_index + 1..].trim_start())
+  ));
+}
+
+fn is_empty_value(value: &str) -> bool {
+  matches!(value, "" | "~" | "null" | "Null" | "NULL" | "{}" | "[]")
+}
+
+// Quotes a string so that it is always read back as exactly that string.
+pub fn yaml_quoted_string(value: &str) -> String {
+  return format!(
+    "\"{}\"",
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+  );
+}
+
+// Writes the string unquoted when it would be read back unchanged, otherwise quotes it.
+pub fn yaml_string_scalar(value: &str) -> String {
+  let reads_back_unchanged: bool = !value.contains(" #") && match serde_yaml::from_str::<serde_yaml::Value>(value) {
+    Ok(serde_yaml::Value::String(parsed_value)) => parsed_value == value,
+    _ => false
+  };
+
+  return if reads_back_unchanged
+    { value.to_string() }
+    else { yaml_quoted_string(value) };
+}
+
+impl YamlDocument {
+  pub fn new(contents: &str) -> Self {
+    let line_ending: &'static str = if contents.contains("\r\n")
+      { "\r\n" }
+      else { "\n" };
+
+    return Self {
+      lines: contents.lines().map(String::from).collect(),
+      has_trailing_newline: contents.ends_with('\n'),
+      line_ending
+    }
+  }
+
+  pub fn to_string(&self) -> String {
+    let mut result: String = self.lines.join(self.line_ending);
+
+    if self.has_trailing_newline {
+      result.push_str(self.line_ending);
+    }
+
+    return result;
+  }
+
+  fn value_at(&self, line_index: usize) -> &str {
+    let content: &str = self.lines[line_index].trim_start();
+    return split_mapping_entry(content).map_or("", |(_, value)| value);
+  }
+
+  // Exclusive end of the block nested under the key at the given line. Trailing blank lines and
+  // comments are not part of the block.
+  fn block_end(&self, key_line: usize) -> usize {
+    let key_indent: usize = indent_of(&self.lines[key_line]);
+    let mut end: usize = key_line + 1;
+
+    for line_index in key_line + 1 .. self.lines.len() {
+      let line: &str = &self.lines[line_index];
+
+      if is_ignorable_line(line) {
+        continue;
+      }
+
+      let line_indent: usize = indent_of(line);
+
+      // YAML allows sequences nested in a mapping to have the same indentation as their key.
+      if line_indent > key_indent || (line_indent == key_indent && is_sequence_item(line.trim_start())) {
+        end = line_index + 1;
+      }
+      else {
+        break;
+      }
+    }
+
+    return end;
+  }
+
+  // Indentation used by the direct children of the key at the given line, if it has any.
+  fn child_indent(&self, key_line: usize) -> Option<usize> {
+    return (key_line + 1 .. self.block_end(key_line))
+      .map(|line_index| &self.lines[line_index])
+      .find(|line| !is_ignorable_line(line))
+      .map(|line| indent_of(line));
+  }
+
+  fn find_key_in_range(&self, key: &str, indent: usize, start: usize, end: usize) -> Option<usize> {
+    for line_index in start..end {
+      let line: &str = &self.lines[line_index];
+
+      if is_ignorable_line(line) || indent_of(line) != indent {
+        continue;
+      }
+
+      if let Some((line_key, _)) = split_mapping_entry(line.trim_start()) {
+        if line_key == key {
+          return Some(line_index);
+        }
+      }
+    }
+
+    return None;
+  }
+
+  // Replaces the inline value of the key at the given line, keeping any trailing comment.
+  fn set_inline_value(&mut self, key_line: usize, new_value: &str) {
+    let line: &str = &self.lines[key_line];
+    let indent: usize = indent_of(line);
+    let colon_index: usize = indent + mapping_colon_index(line.trim_start()).unwrap();
+    let old_value: &str = without_comment(line[colon_index + 1..].trim_start());
+    let comment: &str = line[colon_index + 1..].trim_start()[old_value.len()..].trim();
+
+    let mut new_line: String = line[..=colon_index].to_string();
+
+    for part in [new_value, comment] {
+      if !part.is_empty() {
+        new_line.push(' ');
+        new_line.push_str(part);
+      }
+    }
+
+    self.lines[key_line] = new_line;
+  }
+
+  // Returns the line index of the key at the end of the given path.
+  pub fn find_key(&self, key_path: &[&str]) -> Option<usize> {
+    let mut search_start: usize = 0;
+    let mut search_end: usize = self.lines.len();
+    let mut indent: usize = 0;
+    let mut found_line: Option<usize> = None;
+
+    for key in key_path {
+      if let Some(parent_line) = found_line {
+        indent = self.child_indent(parent_line)?;
+      }
+
+      let key_line: usize = self.find_key_in_range(key, indent, search_start, search_end)?;
+      search_start = key_line + 1;
+      search_end = self.block_end(key_line);
+      found_line = Some(key_line);
+    }
+
+    return found_line;
+  }
+
+  // Line index of the key at the end of the given path, and the byte range of the key within that line.
+  pub fn key_position(&self, key_path: &[&str]) -> Option<(usize, Range<usize>)> {
+    let key_line: usize = self.find_key(key_path)?;
+    let line: &str = &self.lines[key_line];
+    let indent: usize = indent_of(line);
+    let key_end: usize = indent + line[indent..indent + mapping_colon_index(line.trim_start())?].trim_end().len();
+
+    return Some((key_line, indent..key_end));
+  }
+
+  // Line indices of the key at the end of the given path and every line nested under it.
+  pub fn key_block_lines(&self, key_path: &[&str]) -> Option<Range<usize>> {
+    let key_line: usize = self.find_key(key_path)?;
+    return Some(key_line..self.block_end(key_line));
+  }
+
+  pub fn line(&self, line_index: usize) -> Option<&str> {
+    return self.lines.get(line_index).map(String::as_str);
+  }
+
+  pub fn num_lines(&self) -> usize {
+    return self.lines.len();
+  }
+
+  // Returns the (unquoted) keys of the block mapping at the given path, in document order.
+  pub fn mapping_keys(&self, key_path: &[&str]) -> Vec<String> {
+    let key_line: usize = match self.find_key(key_path) {
+      Some(line_index) => line_index,
+      None => return Vec::new()
+    };
+
+    let child_indent: Option<usize> = self.child_indent(key_line);
+
+    return (key_line + 1 .. self.block_end(key_line))
+      .map(|line_index| &self.lines[line_index])
+      .filter(|line| !is_ignorable_line(line) && Some(indent_of(line)) == child_indent)
+      .filter_map(|line| split_mapping_entry(line.trim_start()))
+      .map(|(key, _)| key.to_string())
+      .collect();
+  }
+
+  fn flow_style_error(&self, key_path: &[&str], line_index: usize) -> String {
+    return format!(
+      "Unable to edit '{}' (line {}) because it uses flow style. Rewrite it in block style, then try again.",
+      key_path.join(".").yellow(),
+      line_index + 1
+    );
+  }
+
+  // Makes sure every key in the path exists, and that the last key holds a block (or nothing).
+  // Returns the line index of the last key.
+  fn ensure_key(&mut self, key_path: &[&str]) -> Result<usize, String> {
+    if let Some(key_line) = self.find_key(key_path) {
+      let value: &str = self.value_at(key_line);
+
+      if !value.is_empty() && self.block_end(key_line) > key_line + 1 {
+        return Err(format!(
+          "'{}' (line {}) has both an inline value and a nested block.",
+          key_path.join(".").yellow(),
+          key_line + 1
+        ));
+      }
+
+      if is_empty_value(value) {
+        // Remove the placeholder value, but keep any comment following it.
+        self.set_inline_value(key_line, "");
+        return Ok(key_line);
+      }
+
+      return Err(self.flow_style_error(key_path, key_line));
+    }
+
+    let (key, parent_path) = key_path.split_last().unwrap();
+
+    let (insert_at, indent) = if parent_path.is_empty() {
+      let mut insert_at: usize = self.lines.len();
+
+      while insert_at > 0 && self.lines[insert_at - 1].trim().is_empty() {
+        insert_at -= 1;
+      }
+
+      (insert_at, 0)
+    }
+    else {
+      let parent_line: usize = self.ensure_key(parent_path)?;
+      let indent: usize = self.child_indent(parent_line)
+        .unwrap_or(indent_of(&self.lines[parent_line]) + DEFAULT_INDENT_WIDTH);
+
+      if self.lines.get(parent_line + 1).map_or(false, |line| !is_ignorable_line(line) && is_sequence_item(line.trim_start())) {
+        return Err(format!(
+          "Expected '{}' to be a mapping, but it is a sequence.",
+          parent_path.join(".").yellow()
+        ));
+      }
+
+      (self.block_end(parent_line), indent)
+    };
+
+    self.lines.insert(insert_at, format!("{}{}:", " ".repeat(indent), key));
+    return Ok(insert_at);
+  }
+
+  // Adds a new entry to the mapping at the given path, creating the mapping if needed.
+  // The entry body is given relative to the entry's key, using two-space indentation.
+  pub fn insert_mapping_entry(
+    &mut self,
+    mapping_path: &[&str],
+    entry_key: &str,
+    entry_body: &[String]
+  ) -> Result<(), String> {
+    let mut entry_path: Vec<&str> = mapping_path.to_vec();
+    entry_path.push(entry_key);
+
+    if self.find_key(&entry_path).is_some() {
+      return Err(format!(
+        "'{}' already exists.",
+        entry_path.join(".").yellow()
+      ));
+    }
+
+    let entry_line: usize = self.ensure_key(&entry_path)?;
+    let body_indent: String = " ".repeat(indent_of(&self.lines[entry_line]) + DEFAULT_INDENT_WIDTH);
+
+    if entry_body.is_empty() {
+      self.lines[entry_line].push_str(" {}");
+    }
+
+    for (offset, body_line) in entry_body.iter().enumerate() {
+      self.lines.insert(entry_line + 1 + offset, format!("{}{}", body_indent, body_line));
+    }
+
+    Ok(())
+  }
+
+  // Returns the (unquoted) items of the block sequence at the given path.
+  pub fn sequence_items(&self, key_path: &[&str]) -> Vec<String> {
+    return match self.find_key(key_path) {
+      None => Vec::new(),
+      Some(key_line) => self.sequence_item_lines(key_line)
+        .into_iter()
+        .map(|(_, item)| item)
+        .collect()
+    }
+  }
+
+  fn sequence_item_lines(&self, key_line: usize) -> Vec<(usize, String)> {
+    let mut items: Vec<(usize, String)> = Vec::new();
+    let item_indent: Option<usize> = self.child_indent(key_line);
+
+    for line_index in key_line + 1 .. self.block_end(key_line) {
+      let line: &str = &self.lines[line_index];
+
+      if !is_ignorable_line(line) && Some(indent_of(line)) == item_indent && is_sequence_item(line.trim_start()) {
+        let item: &str = without_comment(line.trim_start()[1..].trim_start());
+        items.push((line_index, unquoted(item).to_string()));
+      }
+    }
+
+    return items;
+  }
+
+  // Appends an item to the block sequence at the given path, creating the sequence if needed.
+  pub fn append_sequence_item(&mut self, key_path: &[&str], item: &str) -> Result<(), String> {
+    let key_line: usize = self.ensure_key(key_path)?;
+
+    let item_indent: usize = match self.child_indent(key_line) {
+      Some(indent) => {
+        if !self.sequence_item_lines(key_line).is_empty() || self.block_end(key_line) == key_line + 1 {
+          indent
+        }
+        else {
+          return Err(format!(
+            "Expected '{}' to be a sequence, but it is a mapping.",
+            key_path.join(".").yellow()
+          ));
+        }
+      },
+      None => indent_of(&self.lines[key_line]) + DEFAULT_INDENT_WIDTH
+    };
+
+    let insert_at: usize = self.block_end(key_line);
+    self.lines.insert(insert_at, format!("{}- {}", " ".repeat(item_indent), item));
+    Ok(())
+  }
+
+  // Removes every item of the sequence at the given path which matches the predicate.
+  // When the sequence becomes empty, its value is set to null. Returns the number of items removed.
+  pub fn remove_sequence_items(
+    &mut self,
+    key_path: &[&str],
+    should_remove: impl Fn(&str) -> bool
+  ) -> usize {
+    let key_line: usize = match self.find_key(key_path) {
+      Some(line_index) => line_index,
+      None => return 0
+    };
+
+    let all_items: Vec<(usize, String)> = self.sequence_item_lines(key_line);
+    let removed_lines: Vec<usize> = all_items.iter()
+      .filter(|(_, item)| should_remove(item))
+      .map(|(line_index, _)| *line_index)
+      .collect();
+
+    for line_index in removed_lines.iter().rev() {
+      self.lines.remove(*line_index);
+    }
+
+    if !removed_lines.is_empty() && removed_lines.len() == all_items.len() {
+      self.set_inline_value(key_line, "null");
+    }
+
+    return removed_lines.len();
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{YamlDocument, yaml_string_scalar};
+
+  const PROJECT_YAML: &'static str = "name: demo
+# Outputs
+output:
+  demo:
+    output_type: Executable
+    link:
+    - mylib::mylib   # trailing comment
+    - \"other::thing\"
+
+predefined_dependencies: null # none yet
+build_configs:
+  Debug: {}
+";
+
+  #[test]
+  fn test_find_nested_key() {
+    let document = YamlDocument::new(PROJECT_YAML);
+    assert_eq!(document.find_key(&["output", "demo", "link"]), Some(5));
+    assert_eq!(document.find_key(&["build_configs", "Debug"]), Some(11));
+    assert_eq!(document.find_key(&["output", "link"]), None);
+  }
+
+  #[test]
+  fn test_string_scalars_are_only_quoted_when_needed() {
+    assert_eq!(yaml_string_scalar("((windows)) mylib::mylib"), "((windows)) mylib::mylib");
+    assert_eq!(yaml_string_scalar("self::{ a, b }"), "self::{ a, b }");
+    assert_eq!(yaml_string_scalar("{ a, b }"), "\"{ a, b }\"");
+    assert_eq!(yaml_string_scalar("true"), "\"true\"");
+    assert_eq!(yaml_string_scalar("a #b"), "\"a #b\"");
+  }
+
+  #[test]
+  fn test_mapping_keys() {
+    let document = YamlDocument::new(PROJECT_YAML);
+    assert_eq!(document.mapping_keys(&["output", "demo"]), vec!["output_type", "link"]);
+    assert_eq!(document.mapping_keys(&["build_configs"]), vec!["Debug"]);
+    assert!(document.mapping_keys(&["predefined_dependencies"]).is_empty());
+  }
+
+  #[test]
+  fn test_insert_into_null_mapping() {
+    let mut document = YamlDocument::new(PROJECT_YAML);
+    document.insert_mapping_entry(&["predefined_dependencies"], "fmt", &[String::from("git_tag: \"9.1.0\"")]).unwrap();
+
+    assert!(document.to_string().contains(
+      "predefined_dependencies: # none yet\n  fmt:\n    git_tag: \"9.1.0\"\nbuild_configs:"
+    ));
+  }
+
+  #[test]
+  fn test_insert_into_missing_mapping() {
+    let mut document = YamlDocument::new("name: demo\n");
+    document.insert_mapping_entry(&["predefined_dependencies"], "fmt", &[]).unwrap();
+    assert_eq!(document.to_string(), "name: demo\npredefined_dependencies:\n  fmt: {}\n");
+  }
+
+  #[test]
+  fn test_flow_style_is_rejected() {
+    let mut document = YamlDocument::new("predefined_dependencies: { fmt: { git_tag: v1 } }\n");
+    assert!(document.insert_mapping_entry(&["predefined_dependencies"], "other", &[]).is_err());
+  }
+
+  #[test]
+  fn test_sequence_items_are_unquoted() {
+    let document = YamlDocument::new(PROJECT_YAML);
+    assert_eq!(
+      document.sequence_items(&["output", "demo", "link"]),
+      vec!["mylib::mylib", "other::thing"]
+    );
+  }
+
+  #[test]
+  fn test_append_and_remove_sequence_items() {
+    let mut document = YamlDocument::new(PROJECT_YAML);
+    document.append_sequence_item(&["output", "demo", "link"], "fmt::fmt").unwrap();
+    assert!(document.to_string().contains("    - \"other::thing\"\n    - fmt::fmt\n\npredefined_dependencies"));
+
+    assert_eq!(document.remove_sequence_items(&["output", "demo", "link"], |item| item.contains("::")), 3);
+    assert!(document.to_string().contains("    link: null\n\npredefined_dependencies"));
+  }
+
+  #[test]
+  fn test_append_creates_sequence() {
+    let mut document = YamlDocument::new(PROJECT_YAML);
+    document.append_sequence_item(&["output", "demo", "link_extra", "public"], "a::b").unwrap();
+    assert!(document.to_string().contains(
+      "    - \"other::thing\"\n    link_extra:\n      public:\n        - a::b\n"
+    ));
+  }
+
+  #[test]
+  fn test_flow_style_sequence_is_rejected() {
+    let mut document = YamlDocument::new("link: [a, b]\n");
+    assert!(document.append_sequence_item(&["link"], "c").is_err());
+  }
+}
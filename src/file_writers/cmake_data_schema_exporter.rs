@@ -0,0 +1,94 @@
+use schemars::{gen::{SchemaGenerator, SchemaSettings}, schema::{InstanceType, RootSchema, Schema, SchemaObject}};
+use serde_json::Value;
+
+use crate::project_info::raw_data_in::{RawProject, RawSubproject, RawTestProject};
+
+#[derive(Clone, Copy)]
+pub enum CMakeDataSchemaKind {
+  RootProject,
+  Subproject,
+  TestProject
+}
+
+impl CMakeDataSchemaKind {
+  fn title(&self) -> &'static str {
+    return match self {
+      Self::RootProject => "GCMake root project cmake_data.yaml",
+      Self::Subproject => "GCMake subproject cmake_data.yaml",
+      Self::TestProject => "GCMake test project cmake_data.yaml"
+    }
+  }
+}
+
+// Restricts the keys of the root project's predefined_dependencies section to the
+// dependencies which currently have a configuration.
+fn restrict_predefined_dependency_names(root_schema: &mut RootSchema, predefined_dep_names: &[String]) {
+  let maybe_predef_section = root_schema.schema.object().properties.get_mut("predefined_dependencies");
+
+  if let Some(Schema::Object(predef_section_schema)) = maybe_predef_section {
+    predef_section_schema.object().property_names = Some(Box::new(
+      SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(
+          predefined_dep_names.iter()
+            .map(|dep_name| Value::String(dep_name.clone()))
+            .collect()
+        ),
+        ..Default::default()
+      }.into()
+    ));
+  }
+}
+
+// Generates a JSON Schema (draft 7) for one kind of cmake_data.yaml file. predefined_dep_names
+// only affects root project schemas, and is ignored when empty.
+pub fn export_cmake_data_schema(
+  schema_kind: CMakeDataSchemaKind,
+  predefined_dep_names: &[String]
+) -> Result<String, String> {
+  let generator: SchemaGenerator = SchemaSettings::draft07().into_generator();
+
+  let mut root_schema: RootSchema = match schema_kind {
+    CMakeDataSchemaKind::RootProject => generator.into_root_schema_for::<RawProject>(),
+    CMakeDataSchemaKind::Subproject => generator.into_root_schema_for::<RawSubproject>(),
+    CMakeDataSchemaKind::TestProject => generator.into_root_schema_for::<RawTestProject>()
+  };
+
+  root_schema.schema.metadata().title = Some(schema_kind.title().to_string());
+
+  if let CMakeDataSchemaKind::RootProject = schema_kind {
+    if !predefined_dep_names.is_empty() {
+      restrict_predefined_dependency_names(&mut root_schema, predefined_dep_names);
+    }
+  }
+
+  return serde_json::to_string_pretty(&root_schema)
+    .map_err(|err| format!("Failed to serialize the cmake_data.yaml schema: {}", err.to_string()));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_root_schema_lists_enum_values_and_dependency_names() {
+    let schema_json: String = export_cmake_data_schema(
+      CMakeDataSchemaKind::RootProject,
+      &[String::from("fmt"), String::from("nlohmann_json")]
+    ).unwrap();
+
+    let schema: Value = serde_json::from_str(&schema_json).unwrap();
+    let properties: &Value = &schema["properties"];
+
+    assert_eq!(properties["build_configs"]["propertyNames"]["$ref"], "#/definitions/BuildType");
+    assert_eq!(properties["predefined_dependencies"]["propertyNames"]["enum"], serde_json::json!(["fmt", "nlohmann_json"]));
+    assert_eq!(schema["additionalProperties"], false);
+    assert!(schema["required"].as_array().unwrap().contains(&Value::from("build_configs")));
+    assert!(!schema["required"].as_array().unwrap().contains(&Value::from("warnings")));
+
+    let definitions: &Value = &schema["definitions"];
+    assert_eq!(definitions["OutputItemType"]["enum"].as_array().unwrap().len(), 5);
+    assert!(definitions["BuildConfigCompilerSpecifier"]["enum"].as_array().unwrap().contains(&Value::from("AllCompilers")));
+    assert!(definitions["WarningId"]["enum"].as_array().unwrap().contains(&Value::from("unused-dependency")));
+  }
+}
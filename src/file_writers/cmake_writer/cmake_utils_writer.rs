@@ -1,4 +1,6 @@
-use std::{fs::{self}, io, path::{PathBuf, Path}};
+use std::{fs::{self}, io, path::{PathBuf, Path}, cell::RefCell};
+
+use crate::file_writers::GeneratedFileSet;
 
 use super::ordered_utils;
 
@@ -18,7 +20,8 @@ impl CMakeUtilFile {
 pub struct CMakeUtilWriter {
   cmake_utils_path: PathBuf,
   custom_find_modules_path: PathBuf,
-  utils: Vec<CMakeUtilFile>
+  utils: Vec<CMakeUtilFile>,
+  custom_find_files: RefCell<Vec<PathBuf>>
 }
 
 impl CMakeUtilWriter {
@@ -26,19 +29,12 @@ impl CMakeUtilWriter {
     return Self {
       custom_find_modules_path: cmake_utils_path.join(CMAKE_FIND_MODULES_DIRNAME),
       cmake_utils_path,
-      utils: ordered_utils::ordered_utils_vec()
+      utils: ordered_utils::ordered_utils_vec(),
+      custom_find_files: RefCell::new(Vec::new())
     }
   }
 
-  pub fn write_cmake_utils(&self) -> io::Result<()> {
-    if !self.cmake_utils_path.is_dir() {
-      fs::create_dir(&self.cmake_utils_path)?;
-    }
-
-    if !self.custom_find_modules_path.is_dir() {
-      fs::create_dir(&self.custom_find_modules_path)?;
-    }
-
+  pub fn write_cmake_utils(&self, generated_files: &mut GeneratedFileSet) -> io::Result<()> {
     for util_config in &self.utils {
       let mut util_file_path: PathBuf = self.cmake_utils_path.clone();
 
@@ -49,26 +45,29 @@ impl CMakeUtilWriter {
       util_file_path.push(util_config.util_name);
       util_file_path.set_extension("cmake");
 
-      fs::write(
+      generated_files.add_file(
         util_file_path,
         util_config.util_contents
-      )?;
+      );
     }
 
-    Ok(())
-  }
-
-  pub fn copy_custom_find_file(&self, file_path: impl AsRef<Path>) -> io::Result<()> {
-    let file_name: &str = file_path.as_ref().file_name().unwrap().to_str().unwrap();
+    for custom_find_file_path in self.custom_find_files.borrow().iter() {
+      let file_name: &str = custom_find_file_path.file_name().unwrap().to_str().unwrap();
 
-    fs::copy(
-      file_path.as_ref(),
-      self.custom_find_modules_path.join(file_name)
-    )?;
+      generated_files.add_file(
+        self.custom_find_modules_path.join(file_name),
+        fs::read(custom_find_file_path)?
+      );
+    }
 
     Ok(())
   }
 
+  // Custom find modules are copied into the cmake/modules dir when the utils are written.
+  pub fn copy_custom_find_file(&self, file_path: impl AsRef<Path>) {
+    self.custom_find_files.borrow_mut().push(file_path.as_ref().to_path_buf());
+  }
+
   pub fn get_utils(&self) -> &Vec<CMakeUtilFile> {
     &self.utils
   }
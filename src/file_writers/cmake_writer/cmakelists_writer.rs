@@ -1,9 +1,8 @@
-use std::{collections::{HashSet, BTreeMap, BTreeSet }, fs::File, io::{self, Write, ErrorKind}, path::{PathBuf, Path}, rc::Rc, cell::{RefCell, Ref}, iter::FromIterator};
+use std::{collections::{HashSet, BTreeMap, BTreeSet }, io::{self, Write, ErrorKind}, path::{PathBuf, Path}, rc::Rc, cell::{RefCell, Ref}, iter::FromIterator};
 
-use crate::{project_info::{final_project_data::{FinalProjectData, CppFileGrammar}, path_manipulation::{cleaned_pathbuf, file_relative_to_dir, unix_style}, final_dependencies::{GitRevisionSpecifier, PredefinedCMakeComponentsModuleDep, PredefinedSubdirDep, PredefinedCMakeModuleDep, FinalPredepInfo, GCMakeDependencyStatus, FinalPredefinedDependencyConfig, PredefinedDepFunctionality, FinalDownloadMethod, FinalDebianPackagesConfig, GCMakeDepIDHash}, raw_data_in::{BuildType, BuildConfigCompilerSpecifier, SpecificCompilerSpecifier, OutputItemType, TargetSpecificBuildType, dependencies::internal_dep_config::CMakeModuleType, DefaultCompiledLibType}, FinalProjectType, CompiledOutputItem, LinkMode, FinalTestFramework, dependency_graph_mod::dependency_graph::{DependencyGraph, OrderedTargetInfo, ProjectWrapper, TargetNode, SimpleNodeOutputType, Link, EmscriptenLinkFlagInfo, ContainedItem}, SystemSpecifierWrapper, CompilerDefine, FinalBuildConfig, CompilerFlag, LinkerFlag, gcmake_constants::{SRC_DIR_NAME, INCLUDE_DIR_NAME}, platform_spec_parser::parse_leading_constraint_spec, CodeFileInfo, RetrievedCodeFileType, PreBuildScriptType, CodeFileLang, GivenConstraintSpecParseContext, SystemSpecFeatureType, SystemSpecExpressionTree, SingleSystemSpec}, file_writers::cmake_writer::cmake_writer_helpers::system_constraint_generator_expression};
+use crate::{project_info::{dependency_lock::{LockedDependency, LockedUrlArchive}, final_project_data::{FinalProjectData, CppFileGrammar}, path_manipulation::{cleaned_pathbuf, file_relative_to_dir, unix_style}, final_dependencies::{GitRevisionSpecifier, PredefinedCMakeComponentsModuleDep, PredefinedSubdirDep, PredefinedCMakeModuleDep, FinalPredepInfo, GCMakeDependencyStatus, GCMakeDependencySource, FinalGCMakeDependency, FinalPredefinedDependencyConfig, PredefinedDepFunctionality, FinalDownloadMethod, FinalGitRepoDescriptor, FinalDebianPackagesConfig, GCMakeDepIDHash}, raw_data_in::{BuildType, BuildConfigCompilerSpecifier, SpecificCompilerSpecifier, OutputItemType, TargetSpecificBuildType, dependencies::internal_dep_config::CMakeModuleType, DefaultCompiledLibType}, FinalProjectType, CompiledOutputItem, LinkMode, FinalTestFramework, dependency_graph_mod::dependency_graph::{DependencyGraph, OrderedTargetInfo, ProjectWrapper, TargetNode, SimpleNodeOutputType, Link, EmscriptenLinkFlagInfo, ContainedItem}, SystemSpecifierWrapper, CompilerDefine, FinalBuildConfig, CompilerFlag, LinkerFlag, gcmake_constants::{SRC_DIR_NAME, INCLUDE_DIR_NAME}, platform_spec_parser::parse_leading_constraint_spec, CodeFileInfo, RetrievedCodeFileType, PreBuildScriptType, CodeFileLang, GivenConstraintSpecParseContext, SystemSpecFeatureType, SystemSpecExpressionTree, SingleSystemSpec}, file_writers::{cmake_writer::cmake_writer_helpers::system_constraint_generator_expression, GeneratedFileSet, FileBuffer}, logger::{self, WarningId}};
 
 use super::{cmake_utils_writer::CMakeUtilWriter, cmake_writer_helpers::{system_contstraint_conditional_expression, language_feature_name}};
-use colored::*;
 
 lazy_static! {
   static ref CUDA_CONSTRAINT: SystemSpecifierWrapper = parse_leading_constraint_spec(
@@ -110,7 +109,8 @@ enum DownloadMethodInfo {
   },
   UrlMethod {
     windows_url: String,
-    unix_url: String
+    unix_url: String,
+    locked_archive: Option<LockedUrlArchive>
   }
 }
 
@@ -121,26 +121,28 @@ enum FullCMakeDownloadMethodInfo {
   },
   UrlMethod {
     _windows_url: String,
-    _unix_url: String
+    _unix_url: String,
+    is_hash_locked: bool
   }
 }
 
 pub fn configure_cmake_helper<'a>(
   dep_graph: &Rc<RefCell<DependencyGraph<'a>>>,
-  sorted_target_info: &'a OrderedTargetInfo<'a>
+  sorted_target_info: &'a OrderedTargetInfo<'a>,
+  generated_files: &mut GeneratedFileSet
 ) -> io::Result<()> {
   let borrowed_graph = dep_graph.as_ref().borrow();
 
   for(_, gcmake_dep) in borrowed_graph.get_gcmake_dependencies() {
-    configure_cmake_helper(gcmake_dep, sorted_target_info)?;
+    configure_cmake_helper(gcmake_dep, sorted_target_info, generated_files)?;
   }
 
   for (_, test_project_graph) in borrowed_graph.get_test_projects() {
-    configure_cmake_helper(test_project_graph, sorted_target_info)?;
+    configure_cmake_helper(test_project_graph, sorted_target_info, generated_files)?;
   }
 
   for (_, subproject_graph) in borrowed_graph.get_subprojects() {
-    configure_cmake_helper(subproject_graph, sorted_target_info)?;
+    configure_cmake_helper(subproject_graph, sorted_target_info, generated_files)?;
   }
 
   if let Some(project_data) = borrowed_graph.project_wrapper().maybe_normal_project() {
@@ -149,10 +151,6 @@ pub fn configure_cmake_helper<'a>(
       { Some(CMakeUtilWriter::new(cmake_util_path)) }
       else { None };
 
-    if let Some(util_writer) = &maybe_util_writer {
-      util_writer.write_cmake_utils()?;
-    }
-
     let mut cmake_configurer = CMakeListsWriter::new(
       Rc::clone(dep_graph),
       sorted_target_info,
@@ -160,6 +158,17 @@ pub fn configure_cmake_helper<'a>(
     )?;
 
     cmake_configurer.write_cmakelists()?;
+
+    // Utils are written after the CMakeLists because custom find modules are
+    // collected while writing dependency configuration.
+    if let Some(util_writer) = &cmake_configurer.util_writer {
+      util_writer.write_cmake_utils(generated_files)?;
+    }
+
+    generated_files.add_file(
+      cmake_configurer.cmakelists_file_path,
+      cmake_configurer.cmakelists_file.into_contents()
+    );
   }
 
   Ok(())
@@ -284,7 +293,8 @@ struct CMakeListsWriter<'a> {
   sorted_target_info: &'a OrderedTargetInfo<'a>,
   project_data: Rc<FinalProjectData>,
   util_writer: Option<CMakeUtilWriter>,
-  cmakelists_file: File,
+  cmakelists_file_path: PathBuf,
+  cmakelists_file: FileBuffer,
 
   public_include_dir_var: String,
   private_include_dir_var: String,
@@ -350,7 +360,8 @@ impl<'a> CMakeListsWriter<'a> {
       sorted_target_info: sorted_target_info,
       project_data,
       util_writer,
-      cmakelists_file: File::create(cmakelists_file_name)?
+      cmakelists_file_path: cmakelists_file_name,
+      cmakelists_file: FileBuffer::new()
     })
   }
 
@@ -1237,12 +1248,11 @@ impl<'a> CMakeListsWriter<'a> {
           let usage_conditional: UsageConditionalGroup = self.get_usage_conditional_for_dependency(&wrapped_graph.0);
 
           if !usage_conditional.was_used() {
-            println!(
-              "{} loading project [{}]: No targets from predefined dependency '{}' are ever actually linked to an output.",
-              "Warning".yellow(),
+            logger::warn(WarningId::UnusedDependency, format!(
+              "Project [{}]: No targets from predefined dependency '{}' are ever actually linked to an output.",
               self.project_data.get_name_for_error_messages(),
               borrowed_graph.project_debug_name()
-            );
+            ));
           }
 
           // Usage conditional
@@ -1281,7 +1291,7 @@ impl<'a> CMakeListsWriter<'a> {
               self.project_data.get_name_for_error_messages()
             );
 
-            self.util_writer.as_ref().unwrap().copy_custom_find_file(&custom_find_module.file_path)?;
+            self.util_writer.as_ref().unwrap().copy_custom_find_file(&custom_find_module.file_path);
 
             writeln!(&self.cmakelists_file,
               "add_to_custom_find_modules_list( {} )",
@@ -1548,10 +1558,11 @@ impl<'a> CMakeListsWriter<'a> {
     project_hash_to_write: Option<&GCMakeDepIDHash>
   ) -> io::Result<()> {
     let download_url_var: String = format!("{}_DOWNLOAD_URL", dep_name);
+    let download_hash_var: String = format!("{}_DOWNLOAD_URL_HASH", dep_name);
 
     match &download_method {
       DownloadMethodInfo::GitMethod { .. } => (),
-      DownloadMethodInfo::UrlMethod { windows_url, unix_url } => {
+      DownloadMethodInfo::UrlMethod { windows_url, unix_url, locked_archive } => {
         writeln!(&self.cmakelists_file,
           "if( CURRENT_SYSTEM_IS_WINDOWS )"
         )?;
@@ -1562,6 +1573,14 @@ impl<'a> CMakeListsWriter<'a> {
           &format!("\"{}\"", windows_url)
         )?;
 
+        if let Some(locked) = locked_archive {
+          self.set_basic_var(
+            "\t",
+            &download_hash_var,
+            &format!("\"SHA256={}\"", locked.windows_sha256)
+          )?;
+        }
+
         writeln!(&self.cmakelists_file, "else()")?;
 
         self.set_basic_var(
@@ -1570,6 +1589,14 @@ impl<'a> CMakeListsWriter<'a> {
           &format!("\"{}\"", unix_url)
         )?;
 
+        if let Some(locked) = locked_archive {
+          self.set_basic_var(
+            "\t",
+            &download_hash_var,
+            &format!("\"SHA256={}\"", locked.unix_sha256)
+          )?;
+        }
+
         writeln!(&self.cmakelists_file, "endif()")?;
       }
     }
@@ -1582,10 +1609,11 @@ impl<'a> CMakeListsWriter<'a> {
           GitRevisionSpecifier::Tag(tag) => format!("GIT_TAG \"{}\"", tag)
         }
       },
-      DownloadMethodInfo::UrlMethod { windows_url, unix_url } => {
+      DownloadMethodInfo::UrlMethod { windows_url, unix_url, locked_archive } => {
         FullCMakeDownloadMethodInfo::UrlMethod {
           _windows_url: windows_url,
-          _unix_url: unix_url
+          _unix_url: unix_url,
+          is_hash_locked: locked_archive.is_some()
         }
       }
     };
@@ -1606,12 +1634,17 @@ impl<'a> CMakeListsWriter<'a> {
           revision_spec_str
         )?;
       },
-      FullCMakeDownloadMethodInfo::UrlMethod { .. } => {
+      FullCMakeDownloadMethodInfo::UrlMethod { is_hash_locked, .. } => {
+        let url_hash_str: String = if *is_hash_locked
+          { format!("\n\tURL_HASH \"${{{}}}\"", download_hash_var) }
+          else { String::new() };
+
         writeln!(&self.cmakelists_file,
-          "CPMAddPackage(\n\tNAME {}\n\tDOWNLOAD_ONLY {}\n\tURL \"${{{}}}\"\n\tSYSTEM\n)",
+          "CPMAddPackage(\n\tNAME {}\n\tDOWNLOAD_ONLY {}\n\tURL \"${{{}}}\"{}\n\tSYSTEM\n)",
           dep_name,
           on_or_off_str(requires_custom_populate),
-          download_url_var
+          download_url_var,
+          url_hash_str
         )?;
       }
     }
@@ -1641,6 +1674,87 @@ impl<'a> CMakeListsWriter<'a> {
     Ok(())
   }
 
+  // Only projects which have a gcmake.lock warn about unpinned dependencies. Projects without one
+  // keep downloading whatever their configured tags currently point to.
+  fn warn_about_unlocked_dependency(&self, dep_name: &str, has_stale_entry: bool) {
+    if self.project_data.get_dependency_lock().is_none() {
+      return;
+    }
+
+    logger::warn(WarningId::UnlockedDependency, format!(
+      "Project [{}]: gcmake.lock {} dependency '{}', so its revision isn't pinned. Run 'gcmake lock' to pin it.",
+      self.project_data.get_name_for_error_messages(),
+      if has_stale_entry { "has an outdated entry for" } else { "has no entry for" },
+      dep_name
+    ));
+  }
+
+  fn pinned_revision(
+    &self,
+    dep_name: &str,
+    maybe_locked: Option<&LockedDependency>,
+    git_info: &FinalGitRepoDescriptor
+  ) -> GitRevisionSpecifier {
+    return match maybe_locked.and_then(|locked| locked.locked_commit_for(git_info)) {
+      Some(locked_commit) => GitRevisionSpecifier::CommitHash(locked_commit.to_string()),
+      None => {
+        self.warn_about_unlocked_dependency(dep_name, maybe_locked.is_some());
+        git_info.revision_specifier.clone()
+      }
+    }
+  }
+
+  // Local GCMake dependencies are used straight from their checkout instead of being cloned.
+  // Only the project which declares the dependency writes this, so CMAKE_CURRENT_SOURCE_DIR is
+  // the directory its relative path is resolved from. The global property makes sure the
+  // dependency is only added once, even if another project in the build already added it.
+  fn write_local_dep_code(
+    &self,
+    dep_name: &str,
+    local_path: &Path
+  ) -> io::Result<()> {
+    let source_dir: String = if local_path.is_absolute()
+      { unix_style(local_path) }
+      else { format!("${{CMAKE_CURRENT_SOURCE_DIR}}/{}", unix_style(local_path)) };
+
+    let added_property: String = format!("GCMAKE_LOCAL_DEP_{}_ADDED", dep_name);
+    let added_var: String = format!("{}_ADDED", dep_name);
+
+    writeln!(&self.cmakelists_file,
+      "get_property( {} GLOBAL PROPERTY {} )",
+      added_var,
+      added_property
+    )?;
+
+    writeln!(&self.cmakelists_file, "if( NOT {} )", added_var)?;
+
+    writeln!(&self.cmakelists_file,
+      "\tset_property( GLOBAL PROPERTY {} TRUE )",
+      added_property
+    )?;
+
+    self.set_basic_var(
+      "\t",
+      &format!("{}_SOURCE_DIR", dep_name),
+      &format!("\"{}\"", source_dir)
+    )?;
+
+    writeln!(&self.cmakelists_file,
+      "\tadd_subdirectory( \"${{{}_SOURCE_DIR}}\" \"${{CMAKE_BINARY_DIR}}/_deps/{}-build\" SYSTEM )",
+      dep_name,
+      dep_name.to_lowercase()
+    )?;
+
+    writeln!(&self.cmakelists_file, "endif()")?;
+
+    writeln!(&self.cmakelists_file,
+      "append_to_actual_dep_list( {} )",
+      dep_name
+    )?;
+
+    Ok(())
+  }
+
   fn write_predefined_subdirectory_dependency(
     &self,
     dep_name: &str,
@@ -1750,14 +1864,24 @@ impl<'a> CMakeListsWriter<'a> {
       }
     }
 
+    let maybe_locked: Option<&LockedDependency> = self.project_data.get_dependency_lock()
+      .and_then(|lockfile| lockfile.predefined_dependencies.get(dep_name));
+
     let download_method: DownloadMethodInfo = match dep_info.download_method() {
       FinalDownloadMethod::GitMode(git_info) => DownloadMethodInfo::GitMethod {
         repo_url: git_info.repo_url.clone(),
-        revision: git_info.revision_specifier.clone()
+        revision: self.pinned_revision(dep_name, maybe_locked, git_info)
       },
       FinalDownloadMethod::UrlMode(url_info) => DownloadMethodInfo::UrlMethod {
         windows_url: url_info.windows_url(),
-        unix_url: url_info.unix_url()
+        unix_url: url_info.unix_url(),
+        locked_archive: match maybe_locked.and_then(|locked| locked.locked_archive_for(url_info)) {
+          Some(locked_archive) => Some(locked_archive.clone()),
+          None => {
+            self.warn_about_unlocked_dependency(dep_name, maybe_locked.is_some());
+            None
+          }
+        }
       }
     };
 
@@ -1771,21 +1895,32 @@ impl<'a> CMakeListsWriter<'a> {
     Ok(())
   }
 
+  fn declares_gcmake_dependency(&self, dep_info: &Rc<FinalGCMakeDependency>) -> bool {
+    return self.project_data.get_gcmake_dependencies()
+      .values()
+      .any(|own_dep| Rc::ptr_eq(own_dep, dep_info));
+  }
+
   fn write_gcmake_dependencies(&self) -> io::Result<()> {
     for wrapped_graph in &self.sorted_target_info.project_order {
       let borrowed_graph = wrapped_graph.as_ref().borrow();
 
       if let Some(dep_info) = borrowed_graph.project_wrapper().maybe_gcmake_dep() {
+        // Local paths are relative to the project which declares them, so local dependencies
+        // are only written by that project.
+        if dep_info.local_path().is_some() && !self.declares_gcmake_dependency(dep_info) {
+          continue;
+        }
+
         let dep_name: &str = borrowed_graph.project_identifier_name();
         let usage_conditional: UsageConditionalGroup = self.get_usage_conditional_for_dependency(&wrapped_graph.0);
 
         if !usage_conditional.was_used() {
-          println!(
-            "{} loading project [{}]: No targets from gcmake dependency '{}' are ever actually linked to an output.",
-            "Warning".yellow(),
+          logger::warn(WarningId::UnusedDependency, format!(
+            "Project [{}]: No targets from gcmake dependency '{}' are ever actually linked to an output.",
             self.project_data.get_name_for_error_messages(),
             borrowed_graph.project_debug_name()
-          );
+          ));
         }
 
         // Usage conditional
@@ -1818,19 +1953,28 @@ impl<'a> CMakeListsWriter<'a> {
           )?;
         }
 
-        self.write_dep_clone_code(
-          dep_name,
-          // GCMake projects just link using their targets as usual, since Emscripten
-          // doesn't explicitly specify support for projects we just made ourselves. Makes sense.
-          false,
-          // GCMake projects currently only support downloading each other using the Git method.
-          DownloadMethodInfo::GitMethod {
-            repo_url: dep_info.repo_url().to_string(),
-            revision: dep_info.revision().clone(),
+        match dep_info.source() {
+          GCMakeDependencySource::GitRepo(git_repo) => {
+            let maybe_locked: Option<&LockedDependency> = self.project_data.get_dependency_lock()
+              .and_then(|lockfile| lockfile.gcmake_dependencies.get(dep_name));
+
+            self.write_dep_clone_code(
+              dep_name,
+              // GCMake projects just link using their targets as usual, since Emscripten
+              // doesn't explicitly specify support for projects we just made ourselves. Makes sense.
+              false,
+              DownloadMethodInfo::GitMethod {
+                repo_url: git_repo.repo_url.clone(),
+                revision: self.pinned_revision(dep_name, maybe_locked, git_repo),
+              },
+              false, // All GCMake projects are FetchContent-ready, so they don't require custom population.
+              dep_info.get_hash_info()
+            )?;
           },
-          false, // All GCMake projects are FetchContent-ready, so they don't require custom population.
-          Some(dep_info.get_hash_info())
-        )?;
+          GCMakeDependencySource::LocalPath(local_path) => {
+            self.write_local_dep_code(dep_name, local_path)?;
+          }
+        }
 
         writeln!(&self.cmakelists_file,
           "gcmake_config_file_add_contents( \"find_dependency( {} \n\tPATHS\n\t\t\\\"${{CMAKE_CURRENT_LIST_DIR}}/../{}\\\"\n)\" )",
@@ -1859,12 +2003,11 @@ impl<'a> CMakeListsWriter<'a> {
         let usage_conditional: UsageConditionalGroup = self.get_usage_conditional_for_dependency(&wrapped_graph.0);
 
         if !usage_conditional.was_used() {
-          println!(
-            "{} loading project [{}]: No targets from predefined dependency '{}' are ever actually linked to an output.",
-            "Warning".yellow(),
+          logger::warn(WarningId::UnusedDependency, format!(
+            "Project [{}]: No targets from predefined dependency '{}' are ever actually linked to an output.",
             self.project_data.get_name_for_error_messages(),
             borrowed_graph.project_debug_name()
-          );
+          ));
         }
 
         // Usage conditional
@@ -4005,4 +4148,38 @@ fn get_link_inheritance_method(
       LinkMode::Interface => "INTERFACE",
     }
   }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+  use crate::{api::{self, DependencyGraphWarningMode}, test_projects::{TestProjectDir, no_dep_configs}};
+
+  #[test]
+  fn test_local_gcmake_dependencies_are_added_by_the_project_which_declares_them() {
+    let test_dir = TestProjectDir::new("local-dep-cmake");
+
+    test_dir.write_root_project("top", "top", &[], "gcmake_dependencies:\n  mid:\n    path: ../mid\n");
+    test_dir.write_root_project("mid", "mid", &[], "gcmake_dependencies:\n  shared:\n    path: libs/shared\n");
+    test_dir.write_root_project("mid/libs/shared", "shared", &[], "");
+
+    let project_group = api::load_project(&test_dir.path_str("top"), &no_dep_configs()).unwrap();
+    let graph_info = api::load_dependency_graph(&project_group, DependencyGraphWarningMode::Off).unwrap();
+    let generated_files = api::render_project_configs(&graph_info).unwrap();
+
+    let cmakelists_of = |project_dir: &str| -> String {
+      let contents: &[u8] = generated_files.contents_of(test_dir.path().join(project_dir).join("CMakeLists.txt"))
+        .unwrap_or_else(|| panic!("No CMakeLists.txt was generated for '{}'", project_dir));
+
+      return String::from_utf8(contents.to_vec()).unwrap();
+    };
+
+    let top_cmakelists: String = cmakelists_of("top");
+    let mid_cmakelists: String = cmakelists_of("mid");
+
+    assert!(top_cmakelists.contains("set( mid_SOURCE_DIR \"${CMAKE_CURRENT_SOURCE_DIR}/../mid\" )"), "{}", top_cmakelists);
+    assert!(!top_cmakelists.contains("shared_SOURCE_DIR"), "{}", top_cmakelists);
+    assert!(mid_cmakelists.contains("set( shared_SOURCE_DIR \"${CMAKE_CURRENT_SOURCE_DIR}/libs/shared\" )"), "{}", mid_cmakelists);
+    assert!(mid_cmakelists.contains("if( NOT shared_ADDED )"), "{}", mid_cmakelists);
+    assert!(!mid_cmakelists.contains("mid_SOURCE_DIR"), "{}", mid_cmakelists);
+    assert!(cmakelists_of("mid/libs/shared").contains("project("));
+  }
+}
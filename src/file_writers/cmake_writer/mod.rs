@@ -5,11 +5,14 @@ mod ordered_utils;
 
 use std::{io};
 
-use crate::project_info::{dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper}};
+use crate::{project_info::{dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper}}, file_writers::GeneratedFileSet};
 
 use self::cmakelists_writer::configure_cmake_helper;
 
-pub fn configure_cmake<'a>(root_graph_info: &'a DependencyGraphInfoWrapper<'a>) -> io::Result<()> {
-  configure_cmake_helper(&root_graph_info.root_dep_graph, &root_graph_info.sorted_info)?;
+pub fn configure_cmake<'a>(
+  root_graph_info: &'a DependencyGraphInfoWrapper<'a>,
+  generated_files: &mut GeneratedFileSet
+) -> io::Result<()> {
+  configure_cmake_helper(&root_graph_info.root_dep_graph, &root_graph_info.sorted_info, generated_files)?;
   Ok(())
 }
@@ -1,12 +1,13 @@
-use std::{io, collections::BTreeSet, rc::Rc, cell::RefCell, fs::File, path::{PathBuf, Path}};
+use std::{io, collections::BTreeSet, rc::Rc, cell::RefCell, path::{PathBuf, Path}};
 use std::io::Write;
 
-use crate::project_info::{dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper, DependencyGraph}, final_dependencies::FinalDebianPackagesConfig};
+use crate::{project_info::{dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper, DependencyGraph}, final_dependencies::FinalDebianPackagesConfig}, file_writers::{GeneratedFileSet, FileBuffer}};
 
 const DEBIAN_DEP_INSTALL_SH_FILE_NAME: &'static str = "install-deb-development-packages.sh";
 
 pub fn write_debian_dep_install_sh<'a>(
-  dep_graph_wrapper: &'a DependencyGraphInfoWrapper<'a>
+  dep_graph_wrapper: &'a DependencyGraphInfoWrapper<'a>,
+  generated_files: &mut GeneratedFileSet
 ) -> io::Result<()> {
   let borrowed_project = dep_graph_wrapper.root_dep_graph.as_ref().borrow();
 
@@ -19,7 +20,7 @@ pub fn write_debian_dep_install_sh<'a>(
     );
 
     let file_path: PathBuf = Path::new(project_data.get_project_root_relative_to_cwd()).join(DEBIAN_DEP_INSTALL_SH_FILE_NAME);
-    let deb_dep_installer_file: File = File::create(file_path.as_path())?;
+    let deb_dep_installer_file: FileBuffer = FileBuffer::new();
 
     if accumulated_deps.is_empty() {
       write!(&deb_dep_installer_file,
@@ -33,6 +34,8 @@ pub fn write_debian_dep_install_sh<'a>(
         accumulated_deps.into_iter().collect::<Vec<String>>().join(" ")
       )?;
     }
+
+    generated_files.add_file(file_path, deb_dep_installer_file.into_contents());
   }
   Ok(())
 }
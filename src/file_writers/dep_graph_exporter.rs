@@ -0,0 +1,315 @@
+use std::{rc::Rc, cell::RefCell, collections::{BTreeMap, BTreeSet}};
+
+use crate::project_info::{dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper, TargetNode, SimpleNodeOutputType, ProjectGroupId, ProjectWrapper, DependencyGraph}, LinkMode, SystemSpecifierWrapper, dependency_graph_mod::hash_wrapper::RcRefcHashWrapper};
+
+#[derive(Clone, Copy)]
+pub enum DepGraphExportFormat {
+  Dot,
+  Mermaid
+}
+
+pub struct DepGraphExportOptions {
+  pub format: DepGraphExportFormat,
+  // When true, all targets from a predefined dependency are drawn as a single node.
+  pub collapse_predefined_deps: bool
+}
+
+struct ExportNode {
+  id: String,
+  label: String,
+  output_type: SimpleNodeOutputType
+}
+
+struct ExportEdge {
+  link_mode: LinkMode,
+  system_spec: SystemSpecifierWrapper
+}
+
+#[derive(Default)]
+struct ExportGraph {
+  clusters: BTreeMap<ProjectGroupId, (String, Vec<ExportNode>)>,
+  unclustered_nodes: Vec<ExportNode>,
+  edges: BTreeMap<(String, String), ExportEdge>
+}
+
+// Walks up the project tree to find the project which "owns" the target's project group.
+// Test projects share a group ID with their parent, so their targets are clustered with
+// the parent project.
+fn project_group_label(container_project: &Rc<RefCell<DependencyGraph>>) -> String {
+  let mut group_head: Rc<RefCell<DependencyGraph>> = Rc::clone(container_project);
+
+  loop {
+    let maybe_parent = group_head.as_ref().borrow().parent_project();
+
+    match maybe_parent {
+      Some(parent) if parent.as_ref().borrow().project_group_id() == group_head.as_ref().borrow().project_group_id() => {
+        group_head = parent;
+      },
+      _ => break
+    }
+  }
+
+  let label: String = group_head.as_ref().borrow().project_debug_name().to_string();
+  return label;
+}
+
+fn collapsed_predef_dep_name(target: &TargetNode) -> Option<String> {
+  let container_project = target.container_project();
+  let borrowed_container = container_project.as_ref().borrow();
+
+  return match borrowed_container.project_wrapper() {
+    ProjectWrapper::PredefinedDependency(predef_dep) => Some(predef_dep.get_name().to_string()),
+    _ => None
+  }
+}
+
+fn node_id(target: &TargetNode, options: &DepGraphExportOptions) -> String {
+  if options.collapse_predefined_deps {
+    if let Some(dep_name) = collapsed_predef_dep_name(target) {
+      return format!("predep_{}", dep_name.replace(|c: char| !c.is_ascii_alphanumeric(), "_"));
+    }
+  }
+
+  return format!("target_{}", target.unique_target_id());
+}
+
+// Only targets reachable from the given root targets are exported.
+fn reachable_targets<'a>(
+  graph_info: &DependencyGraphInfoWrapper<'a>,
+  root_targets: &Vec<Rc<RefCell<TargetNode<'a>>>>
+) -> Vec<Rc<RefCell<TargetNode<'a>>>> {
+  let mut reachable: BTreeSet<RcRefcHashWrapper<TargetNode<'a>>> = BTreeSet::new();
+  let mut stack: Vec<Rc<RefCell<TargetNode<'a>>>> = root_targets.clone();
+
+  while let Some(target_rc) = stack.pop() {
+    if reachable.insert(RcRefcHashWrapper(Rc::clone(&target_rc))) {
+      for (_, link) in target_rc.as_ref().borrow().get_depends_on() {
+        stack.push(link.linked_target());
+      }
+    }
+  }
+
+  return graph_info.sorted_info.targets_in_build_order
+    .iter()
+    .filter(|target| reachable.contains(target))
+    .map(|target| Rc::clone(&target.0))
+    .collect();
+}
+
+fn build_export_graph<'a>(
+  targets: &Vec<Rc<RefCell<TargetNode<'a>>>>,
+  options: &DepGraphExportOptions
+) -> ExportGraph {
+  let mut export_graph: ExportGraph = ExportGraph::default();
+  let mut added_node_ids: BTreeSet<String> = BTreeSet::new();
+
+  for target_rc in targets {
+    let target: &TargetNode = &target_rc.as_ref().borrow();
+    let target_node_id: String = node_id(target, options);
+
+    if added_node_ids.insert(target_node_id.clone()) {
+      let collapsed_name: Option<String> = if options.collapse_predefined_deps
+        { collapsed_predef_dep_name(target) }
+        else { None };
+
+      match collapsed_name {
+        Some(dep_name) => {
+          export_graph.unclustered_nodes.push(ExportNode {
+            id: target_node_id.clone(),
+            label: dep_name,
+            output_type: SimpleNodeOutputType::Library
+          });
+        },
+        None => {
+          let container_project = target.container_project();
+
+          export_graph.clusters.entry(target.container_project_group_id())
+            .or_insert_with(|| (project_group_label(&container_project), Vec::new()))
+            .1.push(ExportNode {
+              id: target_node_id.clone(),
+              label: target.get_yaml_namespaced_target_name().to_string(),
+              output_type: target.simple_output_type()
+            });
+        }
+      }
+    }
+
+    for (_, link) in target.get_depends_on() {
+      let linked_node_id: String = node_id(&link.linked_target().as_ref().borrow(), options);
+
+      // Links between targets in the same collapsed predefined dependency.
+      if linked_node_id == target_node_id {
+        continue;
+      }
+
+      export_graph.edges.entry((target_node_id.clone(), linked_node_id))
+        .or_insert_with(|| ExportEdge {
+          link_mode: link.get_link_mode(),
+          system_spec: link.get_system_spec_info().clone()
+        });
+    }
+  }
+
+  return export_graph;
+}
+
+fn edge_label(edge: &ExportEdge) -> Option<String> {
+  return match &edge.system_spec {
+    SystemSpecifierWrapper::All => None,
+    SystemSpecifierWrapper::Specific(spec_tree) => Some(spec_tree.to_string())
+  }
+}
+
+// Backslashes have to be escaped first, otherwise the backslashes added for quotes would be
+// escaped again.
+fn dot_escaped(some_str: &str) -> String {
+  some_str
+    .replace('\\', "\\\\")
+    .replace('"', "\\\"")
+}
+
+fn dot_node_line(node: &ExportNode, indent: &str) -> String {
+  let shape: &str = match node.output_type {
+    SimpleNodeOutputType::Executable => "box",
+    SimpleNodeOutputType::Library => "ellipse"
+  };
+
+  return format!(
+    "{}{} [label=\"{}\", shape={}];\n",
+    indent,
+    node.id,
+    dot_escaped(&node.label),
+    shape
+  );
+}
+
+fn to_dot(export_graph: &ExportGraph) -> String {
+  let mut result: String = String::from("digraph dependencies {\n  rankdir=LR;\n");
+
+  for (cluster_index, (_, (cluster_label, nodes))) in export_graph.clusters.iter().enumerate() {
+    result.push_str(&format!("\n  subgraph cluster_{} {{\n", cluster_index));
+    result.push_str(&format!("    label=\"{}\";\n", dot_escaped(cluster_label)));
+
+    for node in nodes {
+      result.push_str(&dot_node_line(node, "    "));
+    }
+
+    result.push_str("  }\n");
+  }
+
+  if !export_graph.unclustered_nodes.is_empty() {
+    result.push('\n');
+  }
+
+  for node in &export_graph.unclustered_nodes {
+    result.push_str(&dot_node_line(node, "  "));
+  }
+
+  result.push('\n');
+
+  for ((from_id, to_id), edge) in &export_graph.edges {
+    // Public links are drawn thickest, interface links are dotted.
+    let style: &str = match edge.link_mode {
+      LinkMode::Public => "bold",
+      LinkMode::Private => "solid",
+      LinkMode::Interface => "dotted"
+    };
+
+    let label_attr: String = match edge_label(edge) {
+      Some(label) => format!(", label=\"{}\"", dot_escaped(&label)),
+      None => String::new()
+    };
+
+    result.push_str(&format!(
+      "  {} -> {} [style={}{}];\n",
+      from_id,
+      to_id,
+      style,
+      label_attr
+    ));
+  }
+
+  result.push_str("}\n");
+  return result;
+}
+
+fn mermaid_escaped(some_str: &str) -> String {
+  some_str.replace('"', "#quot;")
+}
+
+fn mermaid_node_line(node: &ExportNode, indent: &str) -> String {
+  let escaped_label: String = mermaid_escaped(&node.label);
+
+  return match node.output_type {
+    SimpleNodeOutputType::Executable => format!("{}{}[\"{}\"]\n", indent, node.id, escaped_label),
+    SimpleNodeOutputType::Library => format!("{}{}([\"{}\"])\n", indent, node.id, escaped_label)
+  }
+}
+
+fn to_mermaid(export_graph: &ExportGraph) -> String {
+  let mut result: String = String::from("flowchart LR\n");
+
+  for (cluster_index, (_, (cluster_label, nodes))) in export_graph.clusters.iter().enumerate() {
+    result.push_str(&format!(
+      "  subgraph cluster_{} [\"{}\"]\n",
+      cluster_index,
+      mermaid_escaped(cluster_label)
+    ));
+
+    for node in nodes {
+      result.push_str(&mermaid_node_line(node, "    "));
+    }
+
+    result.push_str("  end\n");
+  }
+
+  for node in &export_graph.unclustered_nodes {
+    result.push_str(&mermaid_node_line(node, "  "));
+  }
+
+  for ((from_id, to_id), edge) in &export_graph.edges {
+    // Same convention as the DOT output: public is thick, interface is dotted.
+    let arrow: &str = match edge.link_mode {
+      LinkMode::Public => "==>",
+      LinkMode::Private => "-->",
+      LinkMode::Interface => "-.->"
+    };
+
+    let label_str: String = match edge_label(edge) {
+      Some(label) => format!("|\"{}\"|", mermaid_escaped(&label)),
+      None => String::new()
+    };
+
+    result.push_str(&format!("  {} {}{} {}\n", from_id, arrow, label_str, to_id));
+  }
+
+  return result;
+}
+
+pub fn export_dep_graph<'a>(
+  graph_info: &DependencyGraphInfoWrapper<'a>,
+  root_targets: &Vec<Rc<RefCell<TargetNode<'a>>>>,
+  options: &DepGraphExportOptions
+) -> String {
+  let export_graph: ExportGraph = build_export_graph(
+    &reachable_targets(graph_info, root_targets),
+    options
+  );
+
+  return match options.format {
+    DepGraphExportFormat::Dot => to_dot(&export_graph),
+    DepGraphExportFormat::Mermaid => to_mermaid(&export_graph)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_dot_labels_escape_backslashes_and_quotes() {
+    assert_eq!(dot_escaped(r#"say "hi""#), r#"say \"hi\""#);
+    assert_eq!(dot_escaped(r#"C:\deps\"#), r#"C:\\deps\\"#);
+    assert_eq!(dot_escaped(r#"ends with \""#), r#"ends with \\\""#);
+  }
+}
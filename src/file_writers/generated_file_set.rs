@@ -0,0 +1,167 @@
+use std::{collections::BTreeMap, path::{PathBuf, Path}, io::{self, Write}, fs, cell::RefCell};
+
+use similar::TextDiff;
+
+// Every writer renders into one of these instead of writing to disk directly. That way
+// the generated files can either be written out or compared against what's currently
+// on disk (for --dry-run and --check).
+pub struct GeneratedFileSet {
+  files: BTreeMap<PathBuf, Vec<u8>>
+}
+
+impl GeneratedFileSet {
+  pub fn new() -> Self {
+    return Self {
+      files: BTreeMap::new()
+    }
+  }
+
+  pub fn add_file(
+    &mut self,
+    file_path: impl AsRef<Path>,
+    contents: impl Into<Vec<u8>>
+  ) {
+    self.files.insert(file_path.as_ref().to_path_buf(), contents.into());
+  }
+
+  pub fn append(&mut self, other: GeneratedFileSet) {
+    self.files.extend(other.files);
+  }
+
+  pub fn contents_of(&self, file_path: impl AsRef<Path>) -> Option<&[u8]> {
+    return self.files.get(file_path.as_ref())
+      .map(|contents| contents.as_slice());
+  }
+
+  pub fn write_all(&self) -> io::Result<()> {
+    for (file_path, contents) in &self.files {
+      if let Some(parent_dir) = file_path.parent() {
+        if !parent_dir.as_os_str().is_empty() && !parent_dir.is_dir() {
+          fs::create_dir_all(parent_dir)?;
+        }
+      }
+
+      fs::write(file_path, contents)?;
+    }
+
+    Ok(())
+  }
+
+  // Returns every generated file whose contents don't match the file currently on disk,
+  // including files which don't exist yet.
+  pub fn stale_files(&self) -> io::Result<Vec<StaleGeneratedFile<'_>>> {
+    let mut stale_files: Vec<StaleGeneratedFile> = Vec::new();
+
+    for (file_path, contents) in &self.files {
+      let existing_contents: Option<Vec<u8>> = if file_path.is_file()
+        { Some(fs::read(file_path)?) }
+        else { None };
+
+      if existing_contents.as_ref() != Some(contents) {
+        stale_files.push(StaleGeneratedFile {
+          file_path,
+          existing_contents,
+          generated_contents: contents
+        });
+      }
+    }
+
+    return Ok(stale_files);
+  }
+}
+
+pub struct StaleGeneratedFile<'a> {
+  pub file_path: &'a Path,
+  existing_contents: Option<Vec<u8>>,
+  generated_contents: &'a [u8]
+}
+
+impl<'a> StaleGeneratedFile<'a> {
+  pub fn is_new_file(&self) -> bool {
+    self.existing_contents.is_none()
+  }
+
+  pub fn unified_diff(&self) -> String {
+    let old_text = String::from_utf8_lossy(self.existing_contents.as_deref().unwrap_or(&[]));
+    let new_text = String::from_utf8_lossy(self.generated_contents);
+    let unix_path_str: String = self.file_path.to_str().unwrap().replace('\\', "/");
+    let path_str: &str = unix_path_str.trim_start_matches("./");
+
+    let old_header: String = if self.is_new_file()
+      { String::from("/dev/null") }
+      else { format!("a/{}", path_str) };
+
+    return TextDiff::from_lines(old_text.as_ref(), new_text.as_ref())
+      .unified_diff()
+      .context_radius(3)
+      .header(&old_header, &format!("b/{}", path_str))
+      .to_string();
+  }
+}
+
+// In-memory stand-in for a File. Writing goes through '&FileBuffer' just like '&File',
+// so existing write!(&self.some_file, ...) code works unchanged.
+pub struct FileBuffer {
+  contents: RefCell<Vec<u8>>
+}
+
+impl FileBuffer {
+  pub fn new() -> Self {
+    return Self {
+      contents: RefCell::new(Vec::new())
+    }
+  }
+
+  pub fn into_contents(self) -> Vec<u8> {
+    self.contents.into_inner()
+  }
+}
+
+impl Write for &FileBuffer {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    self.contents.borrow_mut().extend_from_slice(buf);
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::test_projects::TestProjectDir;
+
+  use super::GeneratedFileSet;
+
+  #[test]
+  fn test_stale_files_are_diffed_against_the_files_on_disk() {
+    let test_dir = TestProjectDir::new("stale-files");
+
+    test_dir.write_file("unchanged.txt", "same\n");
+    test_dir.write_file("changed.txt", "first\nold line\nlast\n");
+
+    let mut generated_files = GeneratedFileSet::new();
+    generated_files.add_file(test_dir.path().join("unchanged.txt"), "same\n");
+    generated_files.add_file(test_dir.path().join("changed.txt"), "first\nnew line\nlast\n");
+    generated_files.add_file(test_dir.path().join("missing.txt"), "created\n");
+
+    let stale_files = generated_files.stale_files().unwrap();
+    let stale_file_names: Vec<&str> = stale_files.iter()
+      .map(|stale_file| stale_file.file_path.file_name().unwrap().to_str().unwrap())
+      .collect();
+
+    // Files are stored in path order.
+    assert_eq!(stale_file_names, vec!["changed.txt", "missing.txt"]);
+
+    let changed_diff: String = stale_files[0].unified_diff();
+    assert!(!stale_files[0].is_new_file());
+    assert!(changed_diff.contains("changed.txt\n+++ b/"), "{}", changed_diff);
+    assert!(changed_diff.contains("\n first\n-old line\n+new line\n last\n"), "{}", changed_diff);
+
+    let missing_diff: String = stale_files[1].unified_diff();
+    assert!(stale_files[1].is_new_file());
+    assert!(missing_diff.starts_with("--- /dev/null\n+++ b/"), "{}", missing_diff);
+    assert!(missing_diff.contains("\n+created\n"), "{}", missing_diff);
+  }
+}
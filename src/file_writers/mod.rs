@@ -1,24 +1,26 @@
 mod cmake_writer;
 mod debian_sh_install_writer;
+mod package_manifest_writer;
+mod generated_file_set;
+mod dep_graph_exporter;
+mod model_json_exporter;
+mod cmake_data_schema_exporter;
+
+pub use generated_file_set::*;
+pub use dep_graph_exporter::*;
+pub use model_json_exporter::*;
+pub use cmake_data_schema_exporter::*;
 
 use std::{io::{self}};
 use crate::project_info::{dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper}};
 
 pub struct ProjectWriteConfiguration<'a> {
   name: String,
-  config_func: fn(&'a DependencyGraphInfoWrapper<'a>) -> io::Result<()>,
+  config_func: fn(&'a DependencyGraphInfoWrapper<'a>, &mut GeneratedFileSet) -> io::Result<()>,
 }
 
-pub fn write_configurations<'a, FBefore, FAfter>(
-  root_graph_info: &'a DependencyGraphInfoWrapper<'a>,
-  before_write: FBefore,
-  after_write: FAfter
-) -> io::Result<()>
-  where
-    FBefore: Fn(&str),
-    FAfter: Fn((&str, io::Result<()>))
-{
-  let project_configurers = [
+fn project_configurers<'a>() -> [ProjectWriteConfiguration<'a>; 3] {
+  return [
     ProjectWriteConfiguration {
       name: String::from("CMake"),
       config_func: cmake_writer::configure_cmake
@@ -26,16 +28,49 @@ pub fn write_configurations<'a, FBefore, FAfter>(
     ProjectWriteConfiguration {
       name: String::from("Debian dev dependency install sh"),
       config_func: debian_sh_install_writer::write_debian_dep_install_sh
+    },
+    ProjectWriteConfiguration {
+      name: String::from("GCMake package manifest"),
+      config_func: package_manifest_writer::write_package_manifest
     }
   ];
+}
 
-  for config in project_configurers {
+pub fn write_configurations<'a, FBefore, FAfter>(
+  root_graph_info: &'a DependencyGraphInfoWrapper<'a>,
+  before_write: FBefore,
+  after_write: FAfter
+) -> io::Result<()>
+  where
+    FBefore: Fn(&str),
+    FAfter: Fn((&str, io::Result<()>))
+{
+  for config in project_configurers() {
     let config_name_str = config.name.as_str();
     before_write(config_name_str);
 
-    let write_result = (config.config_func)(root_graph_info);
+    let mut generated_files: GeneratedFileSet = GeneratedFileSet::new();
+
+    let write_result = (config.config_func)(root_graph_info, &mut generated_files)
+      .and_then(|_| generated_files.write_all());
+
     after_write((config_name_str, write_result));
   }
 
   Ok(())
 }
+
+// Renders every configuration in memory without writing anything to disk.
+pub fn render_configurations<'a>(
+  root_graph_info: &'a DependencyGraphInfoWrapper<'a>
+) -> io::Result<GeneratedFileSet> {
+  let mut all_generated_files: GeneratedFileSet = GeneratedFileSet::new();
+
+  for config in project_configurers() {
+    let mut generated_files: GeneratedFileSet = GeneratedFileSet::new();
+    (config.config_func)(root_graph_info, &mut generated_files)?;
+    all_generated_files.append(generated_files);
+  }
+
+  return Ok(all_generated_files);
+}
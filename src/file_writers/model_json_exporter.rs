@@ -0,0 +1,470 @@
+use std::{rc::Rc, cell::RefCell, collections::BTreeMap};
+
+use serde::Serialize;
+
+use crate::project_info::{dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper, DependencyGraph, TargetNode, ProjectWrapper}, final_project_data::FinalProjectData, final_dependencies::{FinalPredefinedDependencyConfig, FinalPredepInfo, FinalDownloadMethod, GitRevisionSpecifier, FinalGCMakeDependency}, path_manipulation::unix_style, FinalBuildConfigMap, FinalTargetBuildConfigMap, FinalBuildTypeOptionMap, FinalBuildConfig, CompilerFlag, CompilerDefine, SystemSpecifierWrapper, FinalFeatureEnabler};
+
+// These structs mirror the resolved project model, but only contain plain data so the
+// output format stays stable even when the internal representation changes.
+
+#[derive(Serialize)]
+pub struct ModelDump {
+  gcmake_version: String,
+  root_project: ProjectModel,
+  predefined_dependencies: BTreeMap<String, PredefinedDependencyModel>,
+  gcmake_dependencies: BTreeMap<String, GCMakeDependencyModel>
+}
+
+#[derive(Serialize)]
+struct ProjectModel {
+  name: String,
+  namespaced_name: String,
+  version: String,
+  description: String,
+  vendor: String,
+  absolute_root: String,
+  base_include_prefix: String,
+  full_include_prefix: String,
+  default_build_config: String,
+  features: BTreeMap<String, FeatureModel>,
+  global_defines: Vec<ConstrainedItemModel>,
+  build_configs: BTreeMap<String, BTreeMap<String, BuildConfigModel>>,
+  pre_build: Option<TargetModel>,
+  outputs: BTreeMap<String, TargetModel>,
+  subprojects: BTreeMap<String, ProjectModel>,
+  tests: BTreeMap<String, ProjectModel>
+}
+
+#[derive(Serialize)]
+struct FeatureModel {
+  enabled_by_default: bool,
+  enables: Vec<String>
+}
+
+// A flag or define along with the system spec it is constrained to. A missing
+// system_spec means the item applies to all systems.
+#[derive(Serialize)]
+struct ConstrainedItemModel {
+  value: String,
+  system_spec: Option<String>
+}
+
+#[derive(Serialize)]
+struct BuildConfigModel {
+  compiler_flags: Vec<ConstrainedItemModel>,
+  link_time_flags: Vec<ConstrainedItemModel>,
+  linker_flags: Vec<ConstrainedItemModel>,
+  defines: Vec<ConstrainedItemModel>
+}
+
+#[derive(Serialize)]
+struct TargetModel {
+  namespaced_name: String,
+  output_type: Option<String>,
+  entry_file: Option<String>,
+  system_spec: Option<String>,
+  build_configs: BTreeMap<String, BTreeMap<String, BuildConfigModel>>,
+  links: Vec<LinkModel>
+}
+
+#[derive(Serialize)]
+struct LinkModel {
+  target: String,
+  link_mode: String,
+  system_spec: Option<String>,
+  source: LinkSourceModel
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum LinkSourceModel {
+  RootProject { project: String },
+  Subproject { project: String },
+  PredefinedDependency { dependency: String },
+  GCMakeDependency { dependency: String }
+}
+
+#[derive(Serialize)]
+struct PredefinedDependencyModel {
+  dependency_type: String,
+  // The file_version, git tag or commit hash the dependency is retrieved at. CMake module
+  // dependencies use whichever version is installed on the system, so they don't have one.
+  version: Option<String>,
+  download_method: Option<DownloadMethodModel>,
+  options: BTreeMap<String, DependencyOptionModel>,
+  targets: Vec<String>
+}
+
+#[derive(Serialize)]
+struct DependencyOptionModel {
+  cmake_var: String,
+  value: String
+}
+
+#[derive(Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum DownloadMethodModel {
+  Git {
+    repo_url: String,
+    revision: RevisionModel
+  },
+  Url {
+    windows_url: String,
+    unix_url: String
+  }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+enum RevisionModel {
+  Tag(String),
+  CommitHash(String)
+}
+
+#[derive(Serialize)]
+struct GCMakeDependencyModel {
+  // Git dependencies have a repo_url and revision, while local dependencies only have a path.
+  repo_url: Option<String>,
+  revision: Option<RevisionModel>,
+  path: Option<String>,
+  is_downloaded: bool,
+  use_default_features: bool,
+  features: Vec<String>,
+  // Only present once the dependency has been cloned, since the project can't be
+  // loaded before then.
+  project: Option<ProjectModel>
+}
+
+fn system_spec_model(system_spec: &SystemSpecifierWrapper) -> Option<String> {
+  return match system_spec {
+    SystemSpecifierWrapper::All => None,
+    SystemSpecifierWrapper::Specific(spec_tree) => Some(spec_tree.to_string())
+  }
+}
+
+fn flag_list_model(flags: &Vec<CompilerFlag>) -> Vec<ConstrainedItemModel> {
+  return flags.iter()
+    .map(|flag| ConstrainedItemModel {
+      value: flag.flag_string.clone(),
+      system_spec: system_spec_model(&flag.system_spec)
+    })
+    .collect();
+}
+
+fn define_list_model(defines: &Vec<CompilerDefine>) -> Vec<ConstrainedItemModel> {
+  return defines.iter()
+    .map(|define| ConstrainedItemModel {
+      value: define.def_string.clone(),
+      system_spec: system_spec_model(&define.system_spec)
+    })
+    .collect();
+}
+
+fn compiler_config_model(option_map: &FinalBuildTypeOptionMap) -> BTreeMap<String, BuildConfigModel> {
+  return option_map.iter()
+    .map(|(compiler, build_config): (_, &FinalBuildConfig)| (
+      format!("{:?}", compiler),
+      BuildConfigModel {
+        compiler_flags: flag_list_model(&build_config.compiler_flags),
+        link_time_flags: flag_list_model(&build_config.link_time_flags),
+        linker_flags: flag_list_model(&build_config.linker_flags),
+        defines: define_list_model(&build_config.defines)
+      }
+    ))
+    .collect();
+}
+
+fn project_build_configs_model(build_configs: &FinalBuildConfigMap) -> BTreeMap<String, BTreeMap<String, BuildConfigModel>> {
+  return build_configs.iter()
+    .map(|(build_type, option_map)| (build_type.name_str().to_string(), compiler_config_model(option_map)))
+    .collect();
+}
+
+fn target_build_configs_model(build_configs: &FinalTargetBuildConfigMap) -> BTreeMap<String, BTreeMap<String, BuildConfigModel>> {
+  return build_configs.iter()
+    .map(|(build_type, option_map)| (build_type.name_string().to_string(), compiler_config_model(option_map)))
+    .collect();
+}
+
+fn feature_enabler_str(enabler: &FinalFeatureEnabler) -> String {
+  return match &enabler.dep_name {
+    Some(dep_name) => format!("{}/{}", dep_name, enabler.feature_name),
+    None => enabler.feature_name.clone()
+  }
+}
+
+fn revision_model(revision: &GitRevisionSpecifier) -> RevisionModel {
+  return match revision {
+    GitRevisionSpecifier::Tag(tag) => RevisionModel::Tag(tag.clone()),
+    GitRevisionSpecifier::CommitHash(hash) => RevisionModel::CommitHash(hash.clone())
+  }
+}
+
+fn link_source_model(target: &TargetNode) -> LinkSourceModel {
+  let container_project = target.container_project();
+  let borrowed_container = container_project.as_ref().borrow();
+
+  if let ProjectWrapper::PredefinedDependency(predef_dep) = borrowed_container.project_wrapper() {
+    return LinkSourceModel::PredefinedDependency { dependency: predef_dep.get_name().to_string() };
+  }
+
+  let root_project = borrowed_container.root_project();
+  let borrowed_root = root_project.as_ref().borrow();
+
+  if let ProjectWrapper::GCMakeDependencyRoot(_) = borrowed_root.project_wrapper() {
+    return LinkSourceModel::GCMakeDependency { dependency: borrowed_root.project_identifier_name().to_string() };
+  }
+
+  let project: String = borrowed_container.project_debug_name().to_string();
+
+  return match borrowed_container.parent_project() {
+    Some(_) => LinkSourceModel::Subproject { project },
+    None => LinkSourceModel::RootProject { project }
+  }
+}
+
+fn target_model(target_rc: &Rc<RefCell<TargetNode>>) -> TargetModel {
+  let target: &TargetNode = &target_rc.as_ref().borrow();
+
+  let links: Vec<LinkModel> = target.get_depends_on()
+    .iter()
+    .map(|(_, link)| {
+      let linked_target_rc = link.linked_target();
+      let linked_target: &TargetNode = &linked_target_rc.as_ref().borrow();
+
+      LinkModel {
+        target: linked_target.get_yaml_namespaced_target_name().to_string(),
+        link_mode: link.get_link_mode().to_str().to_string(),
+        system_spec: system_spec_model(link.get_system_spec_info()),
+        source: link_source_model(linked_target)
+      }
+    })
+    .collect();
+
+  let maybe_output = target.maybe_regular_output();
+
+  return TargetModel {
+    namespaced_name: target.get_yaml_namespaced_target_name().to_string(),
+    output_type: maybe_output.map(|output| output.output_type.name_string().to_string()),
+    entry_file: maybe_output.map(|output| output.entry_file.get_file_path().to_str().unwrap().to_string()),
+    system_spec: system_spec_model(target.get_system_spec_info()),
+    build_configs: maybe_output
+      .and_then(|output| output.build_config.as_ref())
+      .map_or(BTreeMap::new(), target_build_configs_model),
+    links
+  };
+}
+
+fn project_model(
+  graph: &Rc<RefCell<DependencyGraph>>,
+  project_data: &FinalProjectData
+) -> ProjectModel {
+  let borrowed_graph = graph.as_ref().borrow();
+
+  let nested_projects = |project_map: &BTreeMap<String, Rc<RefCell<DependencyGraph>>>| -> BTreeMap<String, ProjectModel> {
+    project_map.iter()
+      .filter_map(|(name, nested_graph)| {
+        let nested_project_data: Rc<FinalProjectData> = Rc::clone(nested_graph.as_ref().borrow().project_wrapper().maybe_normal_project()?);
+        Some((name.clone(), project_model(nested_graph, &nested_project_data)))
+      })
+      .collect()
+  };
+
+  return ProjectModel {
+    name: project_data.get_project_base_name().to_string(),
+    namespaced_name: project_data.get_full_namespaced_project_name().to_string(),
+    version: project_data.version.to_string(),
+    description: project_data.get_description().to_string(),
+    vendor: project_data.get_vendor().to_string(),
+    absolute_root: project_data.get_absolute_project_root().to_str().unwrap().to_string(),
+    base_include_prefix: project_data.get_base_include_prefix().to_string(),
+    full_include_prefix: project_data.get_full_include_prefix().to_string(),
+    default_build_config: project_data.get_default_build_config().name_str().to_string(),
+    features: project_data.get_features()
+      .iter()
+      .map(|(feature_name, feature_config)| (
+        feature_name.clone(),
+        FeatureModel {
+          enabled_by_default: feature_config.is_enabled_by_default,
+          enables: feature_config.enables.iter().map(feature_enabler_str).collect()
+        }
+      ))
+      .collect(),
+    global_defines: define_list_model(project_data.get_global_defines()),
+    build_configs: project_build_configs_model(project_data.get_build_configs()),
+    pre_build: borrowed_graph.get_pre_build_node().as_ref().map(target_model),
+    outputs: borrowed_graph.get_this_target_map()
+      .borrow()
+      .iter()
+      .map(|(output_name, target_rc)| (output_name.clone(), target_model(target_rc)))
+      .collect(),
+    subprojects: nested_projects(borrowed_graph.get_subprojects()),
+    tests: nested_projects(borrowed_graph.get_test_projects())
+  };
+}
+
+fn predefined_dependency_model(predef_dep: &FinalPredefinedDependencyConfig) -> PredefinedDependencyModel {
+  let (dependency_type, version, download_method): (&str, Option<String>, Option<DownloadMethodModel>) = match predef_dep.predefined_dep_info() {
+    FinalPredepInfo::CMakeModule(_) => ("cmake_module", None, None),
+    FinalPredepInfo::CMakeComponentsModule(_) => ("cmake_components_module", None, None),
+    FinalPredepInfo::Subdirectory(subdir_dep) => {
+      let (version, download_method): (String, DownloadMethodModel) = match subdir_dep.download_method() {
+        FinalDownloadMethod::GitMode(git_details) => (
+          match &git_details.revision_specifier {
+            GitRevisionSpecifier::Tag(tag) => tag.clone(),
+            GitRevisionSpecifier::CommitHash(commit_hash) => commit_hash.clone()
+          },
+          DownloadMethodModel::Git {
+            repo_url: git_details.repo_url.clone(),
+            revision: revision_model(&git_details.revision_specifier)
+          }
+        ),
+        FinalDownloadMethod::UrlMode(url_details) => (
+          url_details.version.to_string(),
+          DownloadMethodModel::Url {
+            windows_url: url_details.windows_url(),
+            unix_url: url_details.unix_url()
+          }
+        )
+      };
+
+      ("subdirectory", Some(version), Some(download_method))
+    }
+  };
+
+  let options: BTreeMap<String, DependencyOptionModel> = predef_dep.as_common()
+    .config_options_map()
+    .iter()
+    .map(|(option_name, option)| (
+      option_name.clone(),
+      DependencyOptionModel {
+        cmake_var: option.cmake_var.clone(),
+        value: option.value.clone()
+      }
+    ))
+    .collect();
+
+  let mut targets: Vec<String> = predef_dep.get_target_config_map()
+    .keys()
+    .filter_map(|target_name| predef_dep.get_yaml_namespaced_target_name(target_name))
+    .collect();
+
+  targets.sort();
+
+  return PredefinedDependencyModel {
+    dependency_type: dependency_type.to_string(),
+    version,
+    download_method,
+    options,
+    targets
+  };
+}
+
+fn gcmake_dependency_model(
+  dep_graph: &Rc<RefCell<DependencyGraph>>,
+  gcmake_dep: &FinalGCMakeDependency
+) -> GCMakeDependencyModel {
+  let maybe_dep_project: Option<Rc<FinalProjectData>> = dep_graph.as_ref().borrow()
+    .project_wrapper()
+    .maybe_normal_project()
+    .map(Rc::clone);
+
+  return GCMakeDependencyModel {
+    repo_url: gcmake_dep.repo_url().map(str::to_string),
+    revision: gcmake_dep.revision().map(revision_model),
+    path: gcmake_dep.local_path().map(unix_style),
+    is_downloaded: gcmake_dep.is_available(),
+    use_default_features: gcmake_dep.is_using_default_features(),
+    features: gcmake_dep.specified_features().iter().cloned().collect(),
+    project: maybe_dep_project.map(|dep_project| project_model(dep_graph, &dep_project))
+  };
+}
+
+pub fn make_model_dump<'a>(
+  graph_info: &DependencyGraphInfoWrapper<'a>
+) -> ModelDump {
+  let root_graph = &graph_info.root_dep_graph;
+  let borrowed_root = root_graph.as_ref().borrow();
+
+  let root_project_data: Rc<FinalProjectData> = Rc::clone(borrowed_root.project_wrapper().maybe_normal_project().unwrap());
+
+  let predefined_dependencies: BTreeMap<String, PredefinedDependencyModel> = borrowed_root.get_predefined_dependencies()
+    .iter()
+    .map(|(dep_name, dep_graph)| {
+      let predef_dep: Rc<FinalPredefinedDependencyConfig> = Rc::clone(dep_graph.as_ref().borrow().project_wrapper().maybe_predef_dep().unwrap());
+      (dep_name.clone(), predefined_dependency_model(&predef_dep))
+    })
+    .collect();
+
+  let gcmake_dependencies: BTreeMap<String, GCMakeDependencyModel> = borrowed_root.get_gcmake_dependencies()
+    .iter()
+    .map(|(dep_name, dep_graph)| {
+      let gcmake_dep: Rc<FinalGCMakeDependency> = Rc::clone(dep_graph.as_ref().borrow().project_wrapper().maybe_gcmake_dep().unwrap());
+      (dep_name.clone(), gcmake_dependency_model(dep_graph, &gcmake_dep))
+    })
+    .collect();
+
+  return ModelDump {
+    gcmake_version: env!("CARGO_PKG_VERSION").to_string(),
+    root_project: project_model(root_graph, &root_project_data),
+    predefined_dependencies,
+    gcmake_dependencies
+  };
+}
+
+pub fn export_model_json<'a>(
+  graph_info: &DependencyGraphInfoWrapper<'a>
+) -> Result<String, String> {
+  return serde_json::to_string_pretty(&make_model_dump(graph_info))
+    .map_err(|err| format!("Failed to serialize the project model: {}", err.to_string()));
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::Value;
+
+  use crate::{api::{self, DependencyGraphWarningMode}, test_projects::{TestProjectDir, git_dep_config_yaml}};
+
+  use super::export_model_json;
+
+  #[test]
+  fn test_model_dump_contains_resolved_links_and_dependency_versions() {
+    let test_dir = TestProjectDir::new("model-dump");
+
+    test_dir.write_file("repo/good/dep_config.yaml", &git_dep_config_yaml("good", "https://example.com/good.git"));
+    test_dir.write_root_project(
+      "app",
+      "app",
+      &["((windows)) good::lib"],
+      "predefined_dependencies:\n  good:\n    git_tag: v1.2.3\n"
+    );
+
+    let project_group = api::load_project(&test_dir.path_str("app"), &test_dir.dep_config_repo("repo")).unwrap();
+    let graph_info = api::load_dependency_graph(&project_group, DependencyGraphWarningMode::Off).unwrap();
+    let model: Value = serde_json::from_str(&export_model_json(&graph_info).unwrap()).unwrap();
+
+    let root_project: &Value = &model["root_project"];
+    assert_eq!(root_project["name"], "app");
+    assert_eq!(root_project["full_include_prefix"], "APP");
+    assert!(root_project["build_configs"]["Debug"].is_object(), "{}", root_project);
+
+    let exe: &Value = &root_project["outputs"]["app-exe"];
+    assert_eq!(exe["output_type"], "Executable");
+    assert_eq!(exe["links"].as_array().unwrap().len(), 1, "{}", exe);
+
+    let link: &Value = &exe["links"][0];
+    assert_eq!(link["target"], "good::lib");
+    assert_eq!(link["link_mode"], "private");
+    assert_eq!(link["system_spec"], "((windows))");
+    assert_eq!(link["source"]["kind"], "predefined_dependency");
+    assert_eq!(link["source"]["dependency"], "good");
+
+    let good_dep: &Value = &model["predefined_dependencies"]["good"];
+    assert_eq!(good_dep["dependency_type"], "subdirectory");
+    assert_eq!(good_dep["version"], "v1.2.3");
+    assert_eq!(good_dep["download_method"]["method"], "git");
+    assert_eq!(good_dep["download_method"]["repo_url"], "https://example.com/good.git");
+    assert_eq!(good_dep["download_method"]["revision"]["tag"], "v1.2.3");
+    assert_eq!(good_dep["targets"], serde_json::json!(["good::lib"]));
+  }
+}
@@ -0,0 +1,21 @@
+use std::{io, path::{Path, PathBuf}};
+
+use crate::{project_info::{dependency_graph_mod::dependency_graph::DependencyGraphInfoWrapper, gcmake_constants::PACKAGE_MANIFEST_FILE_NAME, package_manifest::PackageManifest}, file_writers::GeneratedFileSet};
+
+pub fn write_package_manifest<'a>(
+  dep_graph_wrapper: &'a DependencyGraphInfoWrapper<'a>,
+  generated_files: &mut GeneratedFileSet
+) -> io::Result<()> {
+  let borrowed_project = dep_graph_wrapper.root_dep_graph.as_ref().borrow();
+
+  if let Some(project_data) = borrowed_project.project_wrapper().maybe_normal_project() {
+    let manifest_contents: String = PackageManifest::from_project(project_data)
+      .to_file_contents()
+      .map_err(|err_msg| io::Error::new(io::ErrorKind::Other, err_msg))?;
+
+    let file_path: PathBuf = Path::new(project_data.get_project_root_relative_to_cwd()).join(PACKAGE_MANIFEST_FILE_NAME);
+    generated_files.add_file(file_path, manifest_contents);
+  }
+
+  Ok(())
+}
@@ -0,0 +1,132 @@
+use std::{fmt, io};
+
+use crate::project_info::final_project_data::ProjectLoadFailureReason;
+
+// Exit code 1 is used for general failures, such as invalid command usage or out of date generated files.
+pub const CONFIG_ERROR_EXIT_CODE: i32 = 3;
+pub const GRAPH_ERROR_EXIT_CODE: i32 = 4;
+pub const IO_ERROR_EXIT_CODE: i32 = 5;
+pub const SUBPROCESS_ERROR_EXIT_CODE: i32 = 6;
+pub const DENIED_WARNINGS_EXIT_CODE: i32 = 7;
+
+#[derive(Debug)]
+pub enum GCMakeError {
+  // A cmake_data.yaml, dependency config, lockfile or other configuration file is invalid.
+  Config(String),
+  // The project tree loaded successfully, but its dependency graph is invalid. For example,
+  // a link points to a nonexistent target or targets depend on each other in a cycle.
+  Graph(String),
+  // Reading or writing a file failed.
+  Io(String),
+  // An external program such as git or cmake failed or couldn't be run.
+  Subprocess(String),
+  // Everything else succeeded, but at least one warning was escalated to an error by the
+  // project's warning policy or '--deny-warnings'.
+  DeniedWarnings(String)
+}
+
+impl GCMakeError {
+  pub fn exit_code(&self) -> i32 {
+    return match self {
+      Self::Config(_) => CONFIG_ERROR_EXIT_CODE,
+      Self::Graph(_) => GRAPH_ERROR_EXIT_CODE,
+      Self::Io(_) => IO_ERROR_EXIT_CODE,
+      Self::Subprocess(_) => SUBPROCESS_ERROR_EXIT_CODE,
+      Self::DeniedWarnings(_) => DENIED_WARNINGS_EXIT_CODE
+    }
+  }
+
+  pub fn message(&self) -> &str {
+    return match self {
+      Self::Config(msg)
+        | Self::Graph(msg)
+        | Self::Io(msg)
+        | Self::Subprocess(msg)
+        | Self::DeniedWarnings(msg) => msg
+    }
+  }
+
+  pub fn map_message(
+    self,
+    mapper: impl FnOnce(String) -> String
+  ) -> Self {
+    return match self {
+      Self::Config(msg) => Self::Config(mapper(msg)),
+      Self::Graph(msg) => Self::Graph(mapper(msg)),
+      Self::Io(msg) => Self::Io(mapper(msg)),
+      Self::Subprocess(msg) => Self::Subprocess(mapper(msg)),
+      Self::DeniedWarnings(msg) => Self::DeniedWarnings(mapper(msg))
+    }
+  }
+}
+
+impl fmt::Display for GCMakeError {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    write!(f, "{}", self.message())
+  }
+}
+
+impl From<ProjectLoadFailureReason> for GCMakeError {
+  fn from(failure_reason: ProjectLoadFailureReason) -> Self {
+    return match failure_reason {
+      ProjectLoadFailureReason::MissingYaml(msg)
+        | ProjectLoadFailureReason::MissingRequiredTestFramework(msg)
+        | ProjectLoadFailureReason::Other(msg) => Self::Config(msg),
+      ProjectLoadFailureReason::Io(msg) => Self::Io(msg),
+      ProjectLoadFailureReason::InvalidYaml(diagnostic) => Self::Config(diagnostic.to_string()),
+      ProjectLoadFailureReason::Multiple(failures) => {
+        let errors: Vec<GCMakeError> = failures.into_iter()
+          .map(Self::from)
+          .collect();
+
+        let joined_message: String = errors.iter()
+          .map(|err| err.message())
+          .collect::<Vec<&str>>()
+          .join("\n\n");
+
+        // Configuration problems are what the user needs to fix, so they take precedence.
+        if errors.iter().all(|err| matches!(err, Self::Io(_)))
+          { Self::Io(joined_message) }
+          else { Self::Config(joined_message) }
+      }
+    }
+  }
+}
+
+impl From<io::Error> for GCMakeError {
+  fn from(err: io::Error) -> Self {
+    return Self::Io(err.to_string());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_error_categories_have_distinct_nonzero_exit_codes() {
+    let exit_codes: Vec<i32> = vec![
+      GCMakeError::Config(String::new()).exit_code(),
+      GCMakeError::Graph(String::new()).exit_code(),
+      GCMakeError::Io(String::new()).exit_code(),
+      GCMakeError::Subprocess(String::new()).exit_code(),
+      GCMakeError::DeniedWarnings(String::new()).exit_code()
+    ];
+
+    for (index, exit_code) in exit_codes.iter().enumerate() {
+      // 0 is success, 1 is a general failure and 2 is used by clap for invalid arguments.
+      assert!(*exit_code > 2);
+      assert!(!exit_codes[index + 1..].contains(exit_code));
+    }
+  }
+
+  #[test]
+  fn test_project_load_failures_keep_their_category() {
+    let io_failure: GCMakeError = ProjectLoadFailureReason::Io(String::from("permission denied")).into();
+    let config_failure: GCMakeError = ProjectLoadFailureReason::MissingYaml(String::from("no cmake_data.yaml")).into();
+
+    assert_eq!(io_failure.exit_code(), IO_ERROR_EXIT_CODE);
+    assert_eq!(io_failure.message(), "permission denied");
+    assert_eq!(config_failure.exit_code(), CONFIG_ERROR_EXIT_CODE);
+  }
+}
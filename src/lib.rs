@@ -0,0 +1,42 @@
+//! gcmake loads a tree of `cmake_data.yaml` files, resolves the links between their targets and
+//! generates CMake configurations from them.
+//!
+//! Tools which reuse gcmake's project loading should go through the [`api`] module. For example:
+//!
+//! ``` no_run
+//! use gcmake_rust::api::{self, DependencyGraphWarningMode, GCMakeError};
+//!
+//! fn print_targets(project_dir: &str) -> Result<(), GCMakeError> {
+//!   let dep_config = api::load_dependency_configs()?;
+//!   let project_group = api::load_project(project_dir, &dep_config)?;
+//!   let graph_info = api::load_dependency_graph(&project_group, DependencyGraphWarningMode::Off)?;
+//!
+//!   for target in graph_info.sorted_info.targets_in_link_order() {
+//!     println!("{}", target.as_ref().borrow().get_yaml_namespaced_target_name());
+//!   }
+//!
+//!   return Ok(());
+//! }
+//! ```
+
+#[macro_use]
+extern crate lazy_static;
+
+pub mod api;
+pub mod common;
+pub mod gcmake_error;
+pub mod project_info;
+pub mod logger;
+pub mod file_writers;
+
+#[cfg(test)]
+mod test_projects;
+
+// The command line interface. These exit the process on failure, so they aren't part of the
+// library API.
+#[doc(hidden)]
+pub mod cli_config;
+#[doc(hidden)]
+pub mod project_generator;
+#[doc(hidden)]
+pub mod program_actions;
@@ -1,12 +1,55 @@
+mod warning_policy;
+
 use std::process::exit;
 use colored::Colorize;
 
-pub fn warn(message: impl AsRef<str>) {
-  println!(
-    "{}: {}",
-    "Warning".yellow(),
-    message.as_ref()
-  );
+use crate::gcmake_error::GCMakeError;
+
+pub use warning_policy::{WarningId, WarningLevel, deny_all_warnings, set_project_warning_levels, reset_project_warning_levels, warning_level, num_denied_warnings};
+
+// Allowed warnings aren't printed at all. Denied warnings are printed as errors and make
+// gcmake fail once check_warnings_denied or exit_if_warnings_denied is called. Warnings go to stderr so they never end
+// up in a command's machine-readable output, such as 'dump-model'.
+pub fn warn(warning_id: WarningId, message: impl AsRef<str>) {
+  match warning_policy::warning_level(warning_id) {
+    WarningLevel::Allow => (),
+    WarningLevel::Warn => eprintln!(
+      "{} [{}]: {}",
+      "Warning".yellow(),
+      warning_id.to_str(),
+      message.as_ref()
+    ),
+    WarningLevel::Deny => {
+      warning_policy::record_denied_warning();
+
+      eprintln!(
+        "{} [{}]: {}",
+        "Error".red(),
+        warning_id.to_str(),
+        message.as_ref()
+      );
+    }
+  }
+}
+
+pub fn check_warnings_denied() -> Result<(), GCMakeError> {
+  let denied_count: usize = num_denied_warnings();
+
+  if denied_count > 0 {
+    return Err(GCMakeError::DeniedWarnings(format!(
+      "{} {} denied warning(s) were emitted. Fix them, or add their IDs to 'warnings: {{ allow: [...] }}' in the root project's cmake_data.yaml.",
+      "Error:".red(),
+      denied_count
+    )));
+  }
+
+  Ok(())
+}
+
+pub fn exit_if_warnings_denied() {
+  if let Err(err) = check_warnings_denied() {
+    exit_with_error(err);
+  }
 }
 
 pub fn block(closure: impl FnOnce()) {
@@ -14,9 +57,18 @@ pub fn block(closure: impl FnOnce()) {
   println!("----------------------------------------");
 }
 
+// Exits with the general failure code. Prefer exit_with_error when the failure fits one of
+// the GCMakeError categories, so scripts can tell what went wrong.
 pub fn exit_error_log(error_message: impl AsRef<str>) -> ! {
   block(|| {
     eprintln!("{}", error_message.as_ref());
   });
-  exit(0);
-}
\ No newline at end of file
+  exit(1);
+}
+
+pub fn exit_with_error(error: GCMakeError) -> ! {
+  block(|| {
+    eprintln!("{}", error);
+  });
+  exit(error.exit_code());
+}
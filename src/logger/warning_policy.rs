@@ -0,0 +1,134 @@
+use std::{collections::BTreeSet, sync::{Mutex, atomic::{AtomicUsize, Ordering}}};
+
+use enum_iterator::Sequence;
+use schemars::JsonSchema;
+use serde::{Serialize, Deserialize};
+
+// Every warning gcmake can emit. The kebab-case names are what users write in the root project's
+// 'warnings' section, so they must never change once released.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Sequence)]
+#[serde(rename_all = "kebab-case")]
+pub enum WarningId {
+  UnusedDependency,
+  FileOutsideIncludePrefix,
+  ExactLanguageStandard,
+  Cpp2StandardTooLow,
+  UnusedDocConfigFile,
+  InvalidInclude,
+  UnlockedDependency,
+  UnvalidatedPlatformSpecifier
+}
+
+impl WarningId {
+  pub fn to_str(&self) -> &'static str {
+    return match self {
+      Self::UnusedDependency => "unused-dependency",
+      Self::FileOutsideIncludePrefix => "file-outside-include-prefix",
+      Self::ExactLanguageStandard => "exact-language-standard",
+      Self::Cpp2StandardTooLow => "cpp2-standard-too-low",
+      Self::UnusedDocConfigFile => "unused-doc-config-file",
+      Self::InvalidInclude => "invalid-include",
+      Self::UnlockedDependency => "unlocked-dependency",
+      Self::UnvalidatedPlatformSpecifier => "unvalidated-platform-specifier"
+    }
+  }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningLevel {
+  Allow,
+  Warn,
+  Deny
+}
+
+pub struct WarningPolicy {
+  deny_all: bool,
+  denied: BTreeSet<WarningId>,
+  allowed: BTreeSet<WarningId>
+}
+
+impl WarningPolicy {
+  pub fn new() -> Self {
+    return Self {
+      deny_all: false,
+      denied: BTreeSet::new(),
+      allowed: BTreeSet::new()
+    };
+  }
+
+  // Explicitly allowed warnings stay silent even when all warnings are denied. That way
+  // '--deny-warnings' can be used in CI on projects which have accepted specific warnings.
+  pub fn level_of(&self, warning_id: WarningId) -> WarningLevel {
+    if self.allowed.contains(&warning_id) {
+      return WarningLevel::Allow;
+    }
+    else if self.deny_all || self.denied.contains(&warning_id) {
+      return WarningLevel::Deny;
+    }
+
+    return WarningLevel::Warn;
+  }
+}
+
+lazy_static! {
+  static ref WARNING_POLICY: Mutex<WarningPolicy> = Mutex::new(WarningPolicy::new());
+}
+
+static NUM_DENIED_WARNINGS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn deny_all_warnings() {
+  WARNING_POLICY.lock().unwrap().deny_all = true;
+}
+
+pub fn set_project_warning_levels(
+  denied: BTreeSet<WarningId>,
+  allowed: BTreeSet<WarningId>
+) {
+  let mut policy = WARNING_POLICY.lock().unwrap();
+  policy.denied = denied;
+  policy.allowed = allowed;
+}
+
+// Clears the project-specified levels, but keeps '--deny-warnings'.
+pub fn reset_project_warning_levels() {
+  set_project_warning_levels(BTreeSet::new(), BTreeSet::new());
+}
+
+pub fn warning_level(warning_id: WarningId) -> WarningLevel {
+  return WARNING_POLICY.lock().unwrap().level_of(warning_id);
+}
+
+pub fn record_denied_warning() {
+  NUM_DENIED_WARNINGS.fetch_add(1, Ordering::SeqCst);
+}
+
+pub fn num_denied_warnings() -> usize {
+  return NUM_DENIED_WARNINGS.load(Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_warning_ids_match_their_config_names() {
+    for warning_id in enum_iterator::all::<WarningId>() {
+      let config_name: String = serde_yaml::to_string(&warning_id).unwrap();
+      assert_eq!(config_name.trim(), warning_id.to_str());
+      assert_eq!(serde_yaml::from_str::<WarningId>(warning_id.to_str()).unwrap(), warning_id);
+    }
+  }
+
+  #[test]
+  fn test_allowed_warnings_take_precedence_over_deny_all() {
+    let policy = WarningPolicy {
+      deny_all: true,
+      denied: BTreeSet::new(),
+      allowed: BTreeSet::from([WarningId::UnusedDependency])
+    };
+
+    assert_eq!(policy.level_of(WarningId::UnusedDependency), WarningLevel::Allow);
+    assert_eq!(policy.level_of(WarningId::InvalidInclude), WarningLevel::Deny);
+    assert_eq!(WarningPolicy::new().level_of(WarningId::InvalidInclude), WarningLevel::Warn);
+  }
+}
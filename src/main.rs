@@ -1,23 +1,15 @@
-#[macro_use]
-extern crate lazy_static;
-
-mod common;
-mod project_info;
-mod logger;
-mod file_writers;
-mod cli_config;
-mod project_generator;
-mod program_actions;
+use std::process::exit;
 
 use clap::Parser;
 
-use cli_config::{CLIProjectGenerationInfo};
-use logger::exit_error_log;
-
-use program_actions::*;
-use project_generator::{DefaultProjectInfo};
-
-use crate::{project_info::{raw_data_in::dependencies::{all_raw_supported_dependency_configs, RawPredefinedDependencyMap}}, project_generator::configuration::{MainFileLanguage, CreationProjectOutputType}, cli_config::clap_cli_config::{Opts, SubCommandStruct, DepConfigSubCommand, CreateFilesCommand, FileCreationLang}};
+use gcmake_rust::{
+  api::{self, RawPredefinedDependencyMap},
+  cli_config::{CLIProjectGenerationInfo, clap_cli_config::{Opts, SubCommandStruct, DepConfigSubCommand, DepCacheSubCommand, ShowSubcommand, CreateFilesCommand, FileCreationLang}},
+  common::prompt::answer_or_prompt_with_default,
+  logger::{self, exit_with_error},
+  program_actions::*,
+  project_generator::{DefaultProjectInfo, configuration::{MainFileLanguage, CreationProjectOutputType}}
+};
 
 // fn print_project_info(project_data_group: UseableFinalProjectDataGroup) {
 //   println!("PROJECT INFORMATION\n----------------------------------------");
@@ -37,21 +29,55 @@ use crate::{project_info::{raw_data_in::dependencies::{all_raw_supported_depende
 fn main() {
   let opts: Opts = Opts::parse();
 
-  if let Some(SubCommandStruct::DepConfig(dep_config_subcommand)) = opts.subcommand {
-    match dep_config_subcommand {
-      DepConfigSubCommand::Update(command_update_deps) => {
-        do_dependency_config_update_subcommand(command_update_deps);
-      }
-    }
+  if opts.deny_warnings {
+    logger::deny_all_warnings();
+  }
 
+  if let Some(SubCommandStruct::DepConfig(DepConfigSubCommand::Update(command_update_deps))) = opts.subcommand {
+    do_dependency_config_update_subcommand(command_update_deps);
     return;
   }
 
-  let dep_config: RawPredefinedDependencyMap = match all_raw_supported_dependency_configs() {
+  match &opts.subcommand {
+    Some(SubCommandStruct::DepCache(DepCacheSubCommand::List(command_list_cache))) => {
+      do_dep_cache_list_subcommand(command_list_cache);
+      return;
+    },
+    Some(SubCommandStruct::DepCache(DepCacheSubCommand::Clean(command_clean_cache))) => {
+      do_dep_cache_clean_subcommand(command_clean_cache);
+      return;
+    },
+    // The schema doesn't need a project, and only needs the dependency configs when listing
+    // the available predefined dependencies. Those include the project's local configs when
+    // run inside a project tree.
+    Some(SubCommandStruct::Schema(command_schema)) => {
+      print_cmake_data_schema(command_schema, &opts.project_root);
+      return;
+    },
+    _ => ()
+  }
+
+  let dep_config: RawPredefinedDependencyMap = match api::load_dependency_configs() {
     Ok(config) => config,
-    Err(error_message) => exit_error_log(&error_message)
+    Err(err) => exit_with_error(err)
   };
 
+  // Includes the configs in the project's .gcmake/dep-configs directory, if there is one. Generation
+  // merges those while loading the project, so commands which only inspect configs use this instead.
+  let maybe_project_dep_config: Option<RawPredefinedDependencyMap> = match dep_config_for_project_tree(&opts.project_root, &dep_config) {
+    Ok(maybe_config) => maybe_config,
+    Err(err) => exit_with_error(err)
+  };
+  let project_tree_dep_config: &RawPredefinedDependencyMap = maybe_project_dep_config.as_ref().unwrap_or(&dep_config);
+
+  if let Some(SubCommandStruct::DepConfig(DepConfigSubCommand::Check(command_check_deps))) = &opts.subcommand {
+    if let Err(err) = do_dependency_config_check_subcommand(command_check_deps, project_tree_dep_config) {
+      exit_with_error(err);
+    }
+
+    return;
+  }
+
   // Project root is only set by the user when using the default command. When using subcommands or unspecified
   // in the main command, uses the current working directory.
   let mut given_root_dir: String = opts.project_root;
@@ -75,8 +101,13 @@ fn main() {
           if let CreationProjectOutputType::Library(lib_type) = new_project_info.project_output_type {
             if lib_type.is_compiled_lib() {
               println!();
-              let new_file_name: String = match prompt_for_initial_compiled_lib_file_pair_name(&new_project_info.project.name) {
-                Err(io_err) => exit_error_log(io_err.to_string()),
+              let project_name: &str = &new_project_info.project.name;
+              let new_file_name: String = match answer_or_prompt_with_default(
+                new_project_info.answers.initial_file_name.clone(),
+                String::from(DEFAULT_INITIAL_FILE_PAIR_NAME),
+                || prompt_for_initial_compiled_lib_file_pair_name(project_name)
+              ) {
+                Err(io_err) => exit_with_error(io_err.into()),
                 Ok(relative_name) => relative_name
               };
 
@@ -112,6 +143,31 @@ fn main() {
         &dep_config,
         None
       ),
+      SubCommandStruct::MvFile(command) => do_move_files_subcommand(
+        &command,
+        &given_root_dir,
+        &dep_config
+      ),
+      SubCommandStruct::AddDep(command) => do_add_dep_subcommand(
+        &command,
+        &given_root_dir,
+        &dep_config
+      ),
+      SubCommandStruct::Lock(command) => do_lock_subcommand(
+        &command,
+        &given_root_dir,
+        &dep_config
+      ),
+      SubCommandStruct::AddLink(command) => do_add_link_subcommand(
+        &command,
+        &given_root_dir,
+        &dep_config
+      ),
+      SubCommandStruct::RemoveLink(command) => do_remove_link_subcommand(
+        &command,
+        &given_root_dir,
+        &dep_config
+      ),
       SubCommandStruct::UseFile(command) => {
         should_generate_cmakelists = false;
 
@@ -122,7 +178,7 @@ fn main() {
         );
 
         if let Err(err) = file_copy_result {
-          exit_error_log(err.to_string());
+          exit_with_error(err.into());
         }
       },
       SubCommandStruct::GenDefault(command) => {
@@ -133,7 +189,7 @@ fn main() {
         );
 
         if let Err(err) = file_copy_result {
-          exit_error_log(err.to_string());
+          exit_with_error(err.into());
         }
       },
       SubCommandStruct::TargetInfo(command) => {
@@ -145,6 +201,24 @@ fn main() {
           &dep_config
         );
       },
+      SubCommandStruct::DepGraph(command) => {
+        should_generate_cmakelists = false;
+
+        print_dep_graph(
+          &command,
+          &given_root_dir,
+          &dep_config
+        );
+      },
+      SubCommandStruct::DumpModel(command) => {
+        should_generate_cmakelists = false;
+
+        dump_project_model(
+          &command,
+          &given_root_dir,
+          &dep_config
+        );
+      },
       SubCommandStruct::ProjectInfo(command) => {
         should_generate_cmakelists = false;
 
@@ -154,12 +228,27 @@ fn main() {
           &dep_config
         );
       },
+      SubCommandStruct::Show(show_subcommand) => {
+        should_generate_cmakelists = false;
+
+        let (command, shown_settings) = match show_subcommand {
+          ShowSubcommand::Flags(command) => (command, ShownBuildSettings::Flags),
+          ShowSubcommand::Defines(command) => (command, ShownBuildSettings::Defines)
+        };
+
+        print_effective_build_settings(
+          &command,
+          shown_settings,
+          &given_root_dir,
+          &dep_config
+        );
+      },
       SubCommandStruct::PredepInfo(command) => {
         should_generate_cmakelists = false;
 
         print_predep_info(
           &command,
-          &dep_config
+          project_tree_dep_config
         );
       },
       SubCommandStruct::ToolInfo(command) => {
@@ -167,18 +256,45 @@ fn main() {
 
         print_tool_info(command);
       },
-      SubCommandStruct::DepConfig(_) => {
+
+      SubCommandStruct::Check(_) => {
+        should_generate_cmakelists = false;
+
+        if let Err(err) = do_check_project(&given_root_dir, &dep_config) {
+          exit_with_error(err);
+        }
+      },
+      SubCommandStruct::DepCache(DepCacheSubCommand::Prune(command)) => {
+        should_generate_cmakelists = false;
+
+        do_dep_cache_prune_subcommand(&command, &dep_config);
+      },
+      SubCommandStruct::DepConfig(_)
+        | SubCommandStruct::DepCache(_)
+        | SubCommandStruct::Schema(_) => {
         unreachable!();
       }
     }
   }
 
   if should_generate_cmakelists {
-    do_generate_project_configs(
+    let generation_mode: ConfigGenerationMode = if opts.dry_run
+      { ConfigGenerationMode::DryRun }
+      else if opts.check_up_to_date
+      { ConfigGenerationMode::CheckUpToDate }
+      else { ConfigGenerationMode::Write };
+
+    let are_generated_files_current: bool = do_generate_project_configs(
       &given_root_dir,
-      &dep_config
+      &dep_config,
+      generation_mode
     );
+
+    if !are_generated_files_current {
+      exit(1);
+    }
   }
 
+  logger::exit_if_warnings_denied();
   println!("");
 }
@@ -0,0 +1,123 @@
+use std::{fs, io, path::PathBuf};
+
+use crate::{common::{basic_configure_replace, make_c_identifier}, project_info::{final_project_data::FinalProjectData, gcmake_constants::PROJECT_LOCAL_FILE_TEMPLATES_DIR}, program_actions::gcmake_file_templates_dir};
+
+use super::code_file_writer::{CodeFileType, extension_for};
+
+fn template_file_name(code_file_type: CodeFileType, is_private: bool) -> String {
+  let kind_name: &str = match &code_file_type {
+    CodeFileType::Header(_) => "header",
+    CodeFileType::Source(_) => "source",
+    CodeFileType::TemplateImpl(_) => "template-impl"
+  };
+
+  return format!("{}{}", kind_name, extension_for(code_file_type, is_private));
+}
+
+// Turns an include prefix such as "MY-LIB/utils" into a C++ namespace like "my_lib::utils".
+pub fn namespace_from_include_prefix(full_include_prefix: &str) -> String {
+  return full_include_prefix
+    .split('/')
+    .filter(|segment| !segment.is_empty())
+    .map(|segment| make_c_identifier(segment).to_lowercase())
+    .collect::<Vec<String>>()
+    .join("::");
+}
+
+// User-provided skeletons for files created by gen-file. Templates are looked up in the root
+// project's .gcmake/file-templates/ first, then in ~/.gcmake/file-templates/. Files without
+// a template get the built-in placeholder code.
+pub struct CodeFileTemplates {
+  search_dirs: Vec<PathBuf>
+}
+
+impl CodeFileTemplates {
+  pub fn for_root_project(root_project: &FinalProjectData) -> Self {
+    Self {
+      search_dirs: vec![
+        root_project.get_absolute_project_root().join(PROJECT_LOCAL_FILE_TEMPLATES_DIR),
+        gcmake_file_templates_dir()
+      ]
+    }
+  }
+
+  // Templates are named by file kind plus the extension of the file being created, for example
+  // header.hpp, source.c or template-impl.tpp. Private files use header.private.hpp if it exists,
+  // otherwise they share the public file's template.
+  pub fn find(&self, code_file_type: &CodeFileType, is_private: bool) -> io::Result<Option<String>> {
+    let mut candidate_names: Vec<String> = vec![template_file_name(code_file_type.clone(), is_private)];
+
+    if is_private {
+      candidate_names.push(template_file_name(code_file_type.clone(), false));
+    }
+
+    for search_dir in &self.search_dirs {
+      for file_name in &candidate_names {
+        let template_path: PathBuf = search_dir.join(file_name);
+
+        if template_path.is_file() {
+          return fs::read_to_string(&template_path).map(Some);
+        }
+      }
+    }
+
+    Ok(None)
+  }
+}
+
+// Values substituted into a code file template.
+pub struct CodeFileTemplateValues {
+  pub file_name: String,
+  pub file_ident: String,
+  pub include_path: String,
+  pub header_include_path: String,
+  pub template_impl_include_path: String,
+  pub guard_name: String,
+  pub guard_begin: String,
+  pub guard_end: String,
+  pub namespace: String,
+  pub export_macro: String,
+  pub export_header_include_path: String
+}
+
+impl CodeFileTemplateValues {
+  pub fn render(&self, template_text: &str) -> String {
+    return basic_configure_replace(
+      template_text,
+      [
+        ("FILE_NAME", self.file_name.clone()),
+        ("FILE_IDENT", self.file_ident.clone()),
+        ("INCLUDE_PATH", self.include_path.clone()),
+        ("HEADER_INCLUDE_PATH", self.header_include_path.clone()),
+        ("TEMPLATE_IMPL_INCLUDE_PATH", self.template_impl_include_path.clone()),
+        ("GUARD_NAME", self.guard_name.clone()),
+        ("GUARD_BEGIN", self.guard_begin.clone()),
+        ("GUARD_END", self.guard_end.clone()),
+        ("NAMESPACE", self.namespace.clone()),
+        ("EXPORT_MACRO", self.export_macro.clone()),
+        ("EXPORT_HEADER_INCLUDE_PATH", self.export_header_include_path.clone())
+      ]
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::cli_config::clap_cli_config::FileCreationLang;
+
+  use super::*;
+
+  #[test]
+  fn test_namespace_from_include_prefix() {
+    assert_eq!(namespace_from_include_prefix("MY-LIB"), "my_lib");
+    assert_eq!(namespace_from_include_prefix("Company/MY-LIB/sub lib"), "company::my_lib::sub_lib");
+  }
+
+  #[test]
+  fn test_template_file_names_use_the_created_file_extension() {
+    assert_eq!(template_file_name(CodeFileType::Header(FileCreationLang::Cpp), false), "header.hpp");
+    assert_eq!(template_file_name(CodeFileType::Header(FileCreationLang::C), true), "header.private.h");
+    assert_eq!(template_file_name(CodeFileType::Source(FileCreationLang::Cpp2), false), "source.cpp2");
+    assert_eq!(template_file_name(CodeFileType::TemplateImpl(FileCreationLang::Cpp), false), "template-impl.tpp");
+  }
+}
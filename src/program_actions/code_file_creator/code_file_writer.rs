@@ -1,8 +1,8 @@
 use colored::Colorize;
 
-use crate::{project_info::{final_project_data::FinalProjectData, path_manipulation::cleaned_pathbuf, ProjectOutputType, CompiledOutputItem}, cli_config::clap_cli_config::FileCreationLang};
+use crate::{project_info::{final_project_data::FinalProjectData, path_manipulation::{cleaned_pathbuf, unix_style}, ProjectOutputType, CompiledOutputItem}, cli_config::clap_cli_config::FileCreationLang};
 
-use super::file_creation_info::{FileTypeGeneratingInfo, SharedFileInfo, FileGuardStyle};
+use super::{file_creation_info::{FileTypeGeneratingInfo, SharedFileInfo, FileGuardStyle, template_impl_guard_name}, code_file_templates::{CodeFileTemplates, CodeFileTemplateValues, namespace_from_include_prefix}};
 use std::{io::{self, Write}, path::{PathBuf, Path}, fs::{self, File}};
 
 pub fn validate_shared_file_info_for_generation(shared_info: &SharedFileInfo) -> Result<(), String> {
@@ -33,6 +33,7 @@ pub fn write_code_files(
   shared_file_info: &SharedFileInfo,
   file_guard: &FileGuardStyle,
   project_info: &FinalProjectData,
+  templates: &CodeFileTemplates,
   language: &FileCreationLang,
   is_private: bool
 ) -> io::Result<Vec<PathBuf>> {
@@ -44,7 +45,8 @@ pub fn write_code_files(
     maybe_template_impl = Some(
       write_template_impl(
         project_info,
-        &file_guard.map_ident(|ident| format!("T_IMPL_{}", ident)),
+        templates,
+        &file_guard.map_ident(template_impl_guard_name),
         shared_file_info,
         language,
         is_private
@@ -56,6 +58,7 @@ pub fn write_code_files(
     maybe_header = Some(
       write_header(
         project_info,
+        templates,
         file_guard,
         shared_file_info,
         language,
@@ -69,6 +72,8 @@ pub fn write_code_files(
     maybe_source = Some(
       write_source(
         project_info,
+        templates,
+        file_guard,
         shared_file_info,
         language,
         &maybe_header,
@@ -148,16 +153,109 @@ pub fn extension_for(file_type: CodeFileType, is_private: bool) -> &'static str
   }
 }
 
+// Files are created in the directories given before their name (e.g. 'sub/my-thing' creates
+// 'include/PREFIX/sub/my-thing.hpp'), so those directories are part of the include path too.
+fn include_path_in_prefix(full_include_prefix: &str, leading_dir_path: &Path, file_name: &str) -> String {
+  return format!(
+    "{}/{}",
+    full_include_prefix,
+    unix_style(cleaned_pathbuf(leading_dir_path.join(file_name)))
+  );
+}
+
 fn to_file_include_path(
   project: &FinalProjectData,
+  file_info: &SharedFileInfo,
   file_including: &PathBuf
 ) -> String {
-  let file_name: &str = file_including.file_name().unwrap().to_str().unwrap();
-  return format!("{}/{}", project.get_full_include_prefix(), file_name);
+  return include_path_in_prefix(
+    project.get_full_include_prefix(),
+    &file_info.leading_dir_path,
+    file_including.file_name().unwrap().to_str().unwrap()
+  );
+}
+
+fn template_values(
+  project_info: &FinalProjectData,
+  file_guard: &FileGuardStyle,
+  file_info: &SharedFileInfo,
+  language: &FileCreationLang,
+  file_path: &PathBuf,
+  is_private: bool
+) -> CodeFileTemplateValues {
+  let include_path_for = |code_file_type: CodeFileType| -> String {
+    let sibling_file_path: PathBuf = PathBuf::from(format!(
+      "{}{}",
+      &file_info.shared_name,
+      extension_for(code_file_type, is_private)
+    ));
+
+    return to_file_include_path(project_info, file_info, &sibling_file_path);
+  };
+
+  let maybe_export_output_name: Option<&String> = match project_info.get_project_output_type() {
+    ProjectOutputType::CompiledLibProject if !is_private => project_info.get_outputs().keys().nth(0),
+    _ => None
+  };
+
+  return CodeFileTemplateValues {
+    file_name: file_info.shared_name.clone(),
+    file_ident: file_info.shared_name_c_ident.clone(),
+    include_path: to_file_include_path(project_info, file_info, file_path),
+    header_include_path: include_path_for(CodeFileType::Header(language.clone())),
+    template_impl_include_path: match language {
+      FileCreationLang::C => String::new(),
+      _ => include_path_for(CodeFileType::TemplateImpl(language.clone()))
+    },
+    guard_name: file_guard.guard_name().to_string(),
+    guard_begin: file_guard.begin_text(),
+    guard_end: file_guard.end_text(),
+    namespace: namespace_from_include_prefix(project_info.get_full_include_prefix()),
+    export_macro: maybe_export_output_name
+      .map(|output_name| CompiledOutputItem::str_export_macro(output_name))
+      .unwrap_or_default(),
+    export_header_include_path: maybe_export_output_name
+      .map(|output_name| CompiledOutputItem::export_macro_header_include_path(
+        project_info.get_full_include_prefix(),
+        output_name
+      ))
+      .unwrap_or_default()
+  };
+}
+
+// Writes the file from a user template if one exists. Returns false when the built-in
+// placeholder code should be written instead.
+fn write_from_template(
+  project_info: &FinalProjectData,
+  templates: &CodeFileTemplates,
+  code_file_type: CodeFileType,
+  file_guard: &FileGuardStyle,
+  file_info: &SharedFileInfo,
+  language: &FileCreationLang,
+  file_path: &PathBuf,
+  is_private: bool
+) -> io::Result<bool> {
+  return match templates.find(&code_file_type, is_private)? {
+    None => Ok(false),
+    Some(template_text) => {
+      let values: CodeFileTemplateValues = template_values(
+        project_info,
+        file_guard,
+        file_info,
+        language,
+        file_path,
+        is_private
+      );
+
+      fs::write(file_path, values.render(&template_text))?;
+      Ok(true)
+    }
+  }
 }
 
 fn write_header(
   project_info: &FinalProjectData,
+  templates: &CodeFileTemplates,
   file_guard: &FileGuardStyle,
   file_info: &SharedFileInfo,
   language: &FileCreationLang,
@@ -175,6 +273,19 @@ fn write_header(
     extension_for(CodeFileType::Header(language.clone()), is_private)
   )?;
 
+  if write_from_template(
+    project_info,
+    templates,
+    CodeFileType::Header(language.clone()),
+    file_guard,
+    file_info,
+    language,
+    &file_path,
+    is_private
+  )? {
+    return Ok(file_path);
+  }
+
   let mut header_file: File = File::create(&file_path)?;
   write_include_guard_begin(&mut header_file, file_guard)?;
 
@@ -224,7 +335,7 @@ fn write_header(
     writeln!(
       &header_file,
       "#include \"{}\"\n",
-      to_file_include_path(project_info, template_impl_file)
+      to_file_include_path(project_info, file_info, template_impl_file)
     )?;
   }
 
@@ -298,6 +409,8 @@ fn write_compiled_lib_header_section(
 
 fn write_source(
   project_info: &FinalProjectData,
+  templates: &CodeFileTemplates,
+  file_guard: &FileGuardStyle,
   file_info: &SharedFileInfo,
   language: &FileCreationLang,
   maybe_header: &Option<PathBuf>,
@@ -310,13 +423,26 @@ fn write_source(
     extension_for(CodeFileType::Source(language.clone()), is_private)
   )?;
 
+  if write_from_template(
+    project_info,
+    templates,
+    CodeFileType::Source(language.clone()),
+    file_guard,
+    file_info,
+    language,
+    &file_path,
+    is_private
+  )? {
+    return Ok(file_path);
+  }
+
   let source_file = File::create(&file_path)?;
 
   if let Some(header_file) = maybe_header {
     writeln!(
       &source_file,
       "#include \"{}\"\n",
-      to_file_include_path(project_info, &header_file)
+      to_file_include_path(project_info, file_info, &header_file)
     )?;
 
     match language {
@@ -386,6 +512,7 @@ std::vector<float> placeholder_{}(const std::vector<float>& xs, const std::vecto
 
 fn write_template_impl(
   project_info: &FinalProjectData,
+  templates: &CodeFileTemplates,
   file_guard: &FileGuardStyle,
   file_info: &SharedFileInfo,
   language: &FileCreationLang,
@@ -402,6 +529,19 @@ fn write_template_impl(
     extension_for(CodeFileType::TemplateImpl(language.clone()), is_private)
   )?;
 
+  if write_from_template(
+    project_info,
+    templates,
+    CodeFileType::TemplateImpl(language.clone()),
+    file_guard,
+    file_info,
+    language,
+    &file_path,
+    is_private
+  )? {
+    return Ok(file_path);
+  }
+
   let mut template_impl_file = File::create(&file_path)?;
   write_include_guard_begin(&mut template_impl_file, file_guard)?;
 
@@ -447,3 +587,15 @@ fn write_include_guard_end(
 
   Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_include_paths_keep_the_leading_directories() {
+    assert_eq!(include_path_in_prefix("MY-LIB", Path::new(""), "my-thing.hpp"), "MY-LIB/my-thing.hpp");
+    assert_eq!(include_path_in_prefix("MY-LIB", Path::new("sub/nested"), "my-thing.hpp"), "MY-LIB/sub/nested/my-thing.hpp");
+    assert_eq!(include_path_in_prefix("MY-LIB/SUB-LIB", Path::new("./sub"), "my-thing.tpp"), "MY-LIB/SUB-LIB/sub/my-thing.tpp");
+  }
+}
@@ -56,6 +56,17 @@ impl FileTypeGeneratingInfo {
   }
 }
 
+// Include guard used by gen-file for a header named 'shared_name'. For example, "H_MY_LIB_SOME_FILE".
+pub fn default_include_guard_name(base_include_prefix: &str, shared_name: &str) -> String {
+  return format!("H_{}_{}", base_include_prefix, shared_name)
+    .to_uppercase()
+    .replace('-', "_");
+}
+
+pub fn template_impl_guard_name(header_guard_name: &str) -> String {
+  return format!("T_IMPL_{}", header_guard_name);
+}
+
 pub enum FileGuardStyle {
   IncludeGuard(String),
   PragmaOnce
@@ -71,6 +82,27 @@ impl FileGuardStyle {
       Self::IncludeGuard(ident) => Self::IncludeGuard(mapper_func(ident))
     }
   }
+
+  pub fn guard_name(&self) -> &str {
+    match self {
+      Self::PragmaOnce => "",
+      Self::IncludeGuard(ident) => ident
+    }
+  }
+
+  pub fn begin_text(&self) -> String {
+    match self {
+      Self::PragmaOnce => String::from("#pragma once"),
+      Self::IncludeGuard(ident) => format!("#ifndef {}\n#define {}", ident, ident)
+    }
+  }
+
+  pub fn end_text(&self) -> String {
+    match self {
+      Self::PragmaOnce => String::new(),
+      Self::IncludeGuard(_) => String::from("#endif")
+    }
+  }
 }
 
 pub struct SharedFileInfo {
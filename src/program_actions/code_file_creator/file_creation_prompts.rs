@@ -2,10 +2,12 @@ use std::io;
 
 use crate::{common::prompt::{prompt_until_satisfies_or_default}, project_info::validators::is_valid_relative_code_file_path};
 
+pub const DEFAULT_INITIAL_FILE_PAIR_NAME: &'static str = "Placeholder";
+
 pub fn prompt_for_initial_compiled_lib_file_pair_name(_project_name: &str) -> io::Result<String> {
   return prompt_until_satisfies_or_default(
     "Initial file pair name",
     is_valid_relative_code_file_path,
-    String::from("Placeholder")
+    String::from(DEFAULT_INITIAL_FILE_PAIR_NAME)
   );
 }
\ No newline at end of file
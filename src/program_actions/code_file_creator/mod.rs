@@ -1,14 +1,16 @@
 use std::{path::{Path, PathBuf}, rc::Rc};
 
-use crate::{project_info::{final_project_data::FinalProjectData, path_manipulation::{file_relative_to_dir, absolute_path}}, cli_config::clap_cli_config::CreateFilesCommand, common::prompt::{prompt_until_custom}};
-use self::{file_creation_info::{FileTypeGeneratingInfo, validate_which_generating, SharedFileInfo, FileGuardStyle}, code_file_writer::{extension_for, validate_shared_file_info_for_generation, write_code_files, CodeFileType}};
+use crate::{gcmake_error::GCMakeError, project_info::{final_project_data::FinalProjectData, path_manipulation::{file_relative_to_dir, absolute_path}}, cli_config::clap_cli_config::CreateFilesCommand, common::prompt::{prompt_until_custom}};
+use self::{file_creation_info::{FileTypeGeneratingInfo, validate_which_generating, SharedFileInfo, FileGuardStyle}, code_file_writer::{extension_for, validate_shared_file_info_for_generation, write_code_files, CodeFileType}, code_file_templates::CodeFileTemplates};
 
+mod code_file_templates;
 mod code_file_writer;
 mod file_creation_info;
 mod file_creation_prompts;
 use colored::*;
 
-pub use file_creation_prompts::prompt_for_initial_compiled_lib_file_pair_name;
+pub use file_creation_prompts::{prompt_for_initial_compiled_lib_file_pair_name, DEFAULT_INITIAL_FILE_PAIR_NAME};
+pub use file_creation_info::{default_include_guard_name, template_impl_guard_name};
 
 enum FileCollisionHandleOption {
   Unspecified,
@@ -19,18 +21,23 @@ enum FileCollisionHandleOption {
 }
 
 pub fn handle_create_files(
+  root_project: &Rc<FinalProjectData>,
   project_data: &Rc<FinalProjectData>,
   command: &CreateFilesCommand
-) -> Result<bool, String> {
-  let which_generating: FileTypeGeneratingInfo = FileTypeGeneratingInfo::new(&command.which)?;
-  validate_which_generating(project_data.get_language_info(), &command.language, &which_generating)?;
+) -> Result<bool, GCMakeError> {
+  let which_generating: FileTypeGeneratingInfo = FileTypeGeneratingInfo::new(&command.which)
+    .map_err(GCMakeError::Config)?;
+  validate_which_generating(project_data.get_language_info(), &command.language, &which_generating)
+    .map_err(GCMakeError::Config)?;
 
+  let templates: CodeFileTemplates = CodeFileTemplates::for_root_project(root_project);
   let mut global_file_collision_option = FileCollisionHandleOption::Unspecified;
 
   for relative_file_name in &command.relative_file_names {
     create_single_file_set(
       &mut global_file_collision_option,
       project_data,
+      &templates,
       command,
       which_generating.clone(),
       relative_file_name
@@ -51,26 +58,24 @@ pub fn handle_create_files(
 fn create_single_file_set(
   global_file_collision_option: &mut FileCollisionHandleOption,
   project_data: &Rc<FinalProjectData>,
+  templates: &CodeFileTemplates,
   command: &CreateFilesCommand,
   mut which_generating: FileTypeGeneratingInfo,
   full_file_path_spec: &str
-) -> Result<(), String> {
-  let shared_file_info: SharedFileInfo = SharedFileInfo::new(full_file_path_spec)?;
-  validate_shared_file_info_for_generation(&shared_file_info)?;
+) -> Result<(), GCMakeError> {
+  let shared_file_info: SharedFileInfo = SharedFileInfo::new(full_file_path_spec)
+    .map_err(GCMakeError::Config)?;
+  validate_shared_file_info_for_generation(&shared_file_info)
+    .map_err(GCMakeError::Config)?;
 
   let file_guard: FileGuardStyle = if command.use_pragma_guards {
     FileGuardStyle::PragmaOnce
   }
   else {
-    let guard_specifier_string: String = format!(
-      "H_{}_{}",
+    FileGuardStyle::IncludeGuard(default_include_guard_name(
       project_data.get_base_include_prefix(),
       &shared_file_info.shared_name
-    )
-      .to_uppercase()
-      .replace('-', "_");
-
-    FileGuardStyle::IncludeGuard(guard_specifier_string)
+    ))
   };
 
   let used_include_path: &Path = if command.should_files_be_private
@@ -110,7 +115,8 @@ fn create_single_file_set(
     let is_file_about_to_be_written: bool = which_generating.get_is_generating(code_file_type.clone());
 
     if is_file_about_to_be_written && file_path.exists() {
-      let file_path_absolute: PathBuf = absolute_path(file_path)?;
+      let file_path_absolute: PathBuf = absolute_path(file_path)
+        .map_err(GCMakeError::Io)?;
       let file_path_relative_to_working_dir: &Path = file_path_absolute.strip_prefix(project_data.get_absolute_project_root()).unwrap();
 
       let local_collision_mode: FileCollisionHandleOption = prompt_until_custom(
@@ -129,7 +135,7 @@ fn create_single_file_set(
           "a" => Some(FileCollisionHandleOption::ReplaceAll),
           _ => None
         }
-      ).map_err(|io_err| io_err.to_string())?;
+      )?;
 
       match local_collision_mode {
         FileCollisionHandleOption::Unspecified => unreachable!(),
@@ -150,6 +156,7 @@ fn create_single_file_set(
       &shared_file_info,
       &file_guard,
       &project_data,
+      templates,
       &command.language,
       command.should_files_be_private
     );
@@ -163,7 +170,7 @@ fn create_single_file_set(
           );
         }
       }
-      Err(error) => return Err(error.to_string())
+      Err(error) => return Err(error.into())
     }
   }
 
@@ -0,0 +1,460 @@
+use std::{cell::RefCell, collections::{BTreeMap, HashSet}, fs, path::{Path, PathBuf}, rc::Rc};
+
+use colored::*;
+use regex::{Regex, Captures};
+
+use crate::{gcmake_error::GCMakeError, project_info::{include_validation::INCLUDE_DIRECTIVE_REGEX, dependency_graph_mod::dependency_graph::DependencyGraph, final_project_data::FinalProjectData, path_manipulation::{absolute_path, cleaned_pathbuf, unix_style}, validators::is_valid_relative_code_file_path, PreBuildScriptType, RetrievedCodeFileType, ProjectOutputType, CompiledOutputItem}};
+
+use super::code_file_creator::{default_include_guard_name, template_impl_guard_name};
+
+// A header/source/template-impl set is identified by its directory inside one of the project's code
+// directories, plus the base name shared by all files in the set.
+struct CodeFileSetLocation {
+  project: Rc<FinalProjectData>,
+  leading_dir: PathBuf,
+  shared_name: String
+}
+
+struct MovedFile {
+  old_path: PathBuf,
+  new_path: PathBuf,
+  // Only set for headers and template-impl files, since source files are never included.
+  include_paths: Option<(String, String)>,
+  guard_names: Option<(String, String)>,
+  is_private_header: bool
+}
+
+fn all_projects_in_tree(project: &Rc<FinalProjectData>, all_projects: &mut Vec<Rc<FinalProjectData>>) {
+  all_projects.push(Rc::clone(project));
+
+  for (_, subproject) in project.get_subprojects().iter().chain(project.get_test_projects()) {
+    all_projects_in_tree(subproject, all_projects);
+  }
+}
+
+fn is_same_project(first: &FinalProjectData, second: &FinalProjectData) -> bool {
+  return first.get_absolute_project_root() == second.get_absolute_project_root();
+}
+
+// Base name shared by a file set. The base name can't contain a '.', so "thing.private.hpp" is "thing".
+fn shared_name_of(file_name: &str) -> &str {
+  return file_name.split('.').next().unwrap();
+}
+
+fn code_dirs(project: &FinalProjectData) -> Result<[PathBuf; 2], String> {
+  return Ok([
+    absolute_path(project.get_include_dir_relative_to_cwd())?,
+    absolute_path(project.get_src_dir_relative_to_cwd())?
+  ]);
+}
+
+fn containing_project_and_relative_path(
+  given_path: &str,
+  all_projects: &Vec<Rc<FinalProjectData>>
+) -> Result<(Rc<FinalProjectData>, PathBuf), String> {
+  let absolute_given_path: PathBuf = absolute_path(given_path)?;
+
+  for project in all_projects {
+    for code_dir in code_dirs(project)? {
+      if let Ok(relative_path) = absolute_given_path.strip_prefix(&code_dir) {
+        return Ok((Rc::clone(project), relative_path.to_path_buf()));
+      }
+    }
+  }
+
+  return Err(format!(
+    "'{}' is not inside the include or src directory of any project in the project tree. Paths should look like '{}'.",
+    given_path.yellow(),
+    "include/<INCLUDE_PREFIX>/some/file.hpp".yellow()
+  ));
+}
+
+fn locate_existing_set(
+  given_path: &str,
+  all_projects: &Vec<Rc<FinalProjectData>>
+) -> Result<CodeFileSetLocation, String> {
+  let (project, relative_path) = containing_project_and_relative_path(given_path, all_projects)?;
+
+  let shared_name: &str = match relative_path.file_name() {
+    Some(file_name) => shared_name_of(file_name.to_str().unwrap()),
+    None => return Err(format!("'{}' does not name a code file.", given_path.yellow()))
+  };
+
+  return Ok(CodeFileSetLocation {
+    leading_dir: relative_path.parent().map_or(PathBuf::new(), PathBuf::from),
+    shared_name: shared_name.to_string(),
+    project
+  });
+}
+
+// The destination can be a new file path (extensions are optional and ignored), or a directory
+// ending with '/' or which already exists. Moving into a directory keeps the set's current name.
+fn locate_destination_set(
+  given_path: &str,
+  moving_from: &CodeFileSetLocation,
+  all_projects: &Vec<Rc<FinalProjectData>>
+) -> Result<CodeFileSetLocation, String> {
+  let (project, relative_path) = containing_project_and_relative_path(given_path, all_projects)?;
+  let is_dir_destination: bool = given_path.ends_with('/') || Path::new(given_path).is_dir();
+
+  let location: CodeFileSetLocation = match relative_path.file_name() {
+    Some(file_name) if !is_dir_destination => CodeFileSetLocation {
+      leading_dir: relative_path.parent().map_or(PathBuf::new(), PathBuf::from),
+      shared_name: shared_name_of(file_name.to_str().unwrap()).to_string(),
+      project
+    },
+    _ => CodeFileSetLocation {
+      leading_dir: relative_path,
+      shared_name: moving_from.shared_name.clone(),
+      project
+    }
+  };
+
+  if !is_valid_relative_code_file_path(&location.shared_name) {
+    return Err(format!("'{}' is not a valid code file name.", location.shared_name.yellow()));
+  }
+
+  return Ok(location);
+}
+
+fn include_path_for(project: &FinalProjectData, leading_dir: &Path, file_name: &str) -> String {
+  return format!(
+    "{}/{}",
+    project.get_full_include_prefix(),
+    unix_style(cleaned_pathbuf(leading_dir.join(file_name)))
+  );
+}
+
+fn plan_moved_files(from: &CodeFileSetLocation, to: &CodeFileSetLocation) -> Result<Vec<MovedFile>, String> {
+  let [from_include_dir, from_src_dir] = code_dirs(&from.project)?;
+  let [to_include_dir, to_src_dir] = code_dirs(&to.project)?;
+  let mut moved_files: Vec<MovedFile> = Vec::new();
+
+  let file_sets = vec![
+    &from.project.public_headers,
+    &from.project.template_impl_files,
+    &from.project.private_headers,
+    &from.project.src_files
+  ];
+
+  for code_file in file_sets.into_iter().flatten() {
+    if code_file.is_generated {
+      continue;
+    }
+
+    let old_path: PathBuf = cleaned_pathbuf(from.project.get_absolute_project_root().join(&code_file.file_path));
+    let old_file_name: &str = old_path.file_name().unwrap().to_str().unwrap();
+
+    if shared_name_of(old_file_name) != from.shared_name {
+      continue;
+    }
+
+    let (new_code_dir, is_in_src_dir) = if old_path.parent() == Some(from_include_dir.join(&from.leading_dir).as_path())
+      { (&to_include_dir, false) }
+      else if old_path.parent() == Some(from_src_dir.join(&from.leading_dir).as_path())
+      { (&to_src_dir, true) }
+      else { continue; };
+
+    let new_file_name: String = format!("{}{}", to.shared_name, &old_file_name[from.shared_name.len()..]);
+    let new_path: PathBuf = cleaned_pathbuf(new_code_dir.join(&to.leading_dir).join(&new_file_name));
+
+    let old_guard_name: String = default_include_guard_name(from.project.get_base_include_prefix(), &from.shared_name);
+    let new_guard_name: String = default_include_guard_name(to.project.get_base_include_prefix(), &to.shared_name);
+
+    let (include_paths, guard_names) = match code_file.code_file_type() {
+      RetrievedCodeFileType::Source(_) | RetrievedCodeFileType::Unknown => (None, None),
+      file_type => (
+        Some((
+          include_path_for(&from.project, &from.leading_dir, old_file_name),
+          include_path_for(&to.project, &to.leading_dir, &new_file_name)
+        )),
+        Some(match file_type {
+          RetrievedCodeFileType::TemplateImpl => (template_impl_guard_name(&old_guard_name), template_impl_guard_name(&new_guard_name)),
+          _ => (old_guard_name, new_guard_name)
+        })
+      )
+    };
+
+    moved_files.push(MovedFile {
+      is_private_header: is_in_src_dir && include_paths.is_some(),
+      old_path,
+      new_path,
+      include_paths,
+      guard_names
+    });
+  }
+
+  return Ok(moved_files);
+}
+
+// The export macro and export header include path of a compiled library project.
+fn export_names(project: &FinalProjectData) -> Option<(String, String)> {
+  if let ProjectOutputType::CompiledLibProject = project.get_project_output_type() {
+    // Library projects only have one output.
+    if let Some(output_name) = project.get_outputs().keys().nth(0) {
+      return Some((
+        CompiledOutputItem::str_export_macro(output_name),
+        CompiledOutputItem::export_macro_header_include_path(project.get_full_include_prefix(), output_name)
+      ));
+    }
+  }
+
+  return None;
+}
+
+fn all_code_files_in_tree(all_projects: &Vec<Rc<FinalProjectData>>) -> BTreeMap<PathBuf, Rc<FinalProjectData>> {
+  let mut all_files: BTreeMap<PathBuf, Rc<FinalProjectData>> = BTreeMap::new();
+
+  for project in all_projects {
+    let mut relative_file_paths: Vec<&Path> = vec![&project.public_headers, &project.template_impl_files, &project.private_headers, &project.src_files]
+      .into_iter()
+      .flatten()
+      .chain(project.get_outputs().values().map(|output| output.get_entry_file()))
+      .map(|code_file| code_file.file_path.as_path())
+      .collect();
+
+    if let Some(pre_build) = project.get_prebuild_script() {
+      if let PreBuildScriptType::Exe(exe_pre_build) = pre_build.get_type() {
+        relative_file_paths.push(exe_pre_build.get_entry_file().file_path.as_path());
+      }
+    }
+
+    for relative_file_path in relative_file_paths {
+      all_files.insert(
+        cleaned_pathbuf(project.get_absolute_project_root().join(relative_file_path)),
+        Rc::clone(project)
+      );
+    }
+  }
+
+  return all_files;
+}
+
+// Returns the file contents with every include of a moved file rewritten, and the old include
+// paths which were found.
+fn rewrite_includes(file_contents: &str, moved_files: &Vec<MovedFile>) -> (String, Vec<String>) {
+  let mut included_moved_files: Vec<String> = Vec::new();
+
+  let rewritten: String = INCLUDE_DIRECTIVE_REGEX.replace_all(file_contents, |captures: &Captures| {
+    let included_path: &str = &captures[3];
+
+    let maybe_new_include_path: Option<&String> = moved_files.iter()
+      .filter_map(|moved_file| moved_file.include_paths.as_ref())
+      .find(|(old_include_path, _)| old_include_path == included_path)
+      .map(|(_, new_include_path)| new_include_path);
+
+    match maybe_new_include_path {
+      None => captures[0].to_string(),
+      Some(new_include_path) => {
+        included_moved_files.push(included_path.to_string());
+        format!("{}{}{}{}", &captures[1], &captures[2], new_include_path, &captures[4])
+      }
+    }
+  }).to_string();
+
+  return (rewritten, included_moved_files);
+}
+
+// True when some target in the dependent project can use the linked project's public headers.
+fn project_links_to(
+  root_graph: &Rc<RefCell<DependencyGraph>>,
+  dependent_project: &Rc<FinalProjectData>,
+  linked_project: &Rc<FinalProjectData>
+) -> bool {
+  let root_graph_ref = root_graph.as_ref().borrow();
+
+  return match (root_graph_ref.find_using_project_data(dependent_project), root_graph_ref.find_using_project_data(linked_project)) {
+    (Some(dependent_graph), Some(linked_graph)) => {
+      let linked_project_id = linked_graph.as_ref().borrow().project_id();
+
+      dependent_graph.as_ref().borrow().get_this_target_map().borrow()
+        .values()
+        .any(|target| target.as_ref().borrow().transitively_links_project(linked_project_id))
+    },
+    _ => false
+  }
+}
+
+fn remove_empty_dirs(mut dir: &Path, stop_at: &[PathBuf; 2]) {
+  while !stop_at.iter().any(|code_dir| code_dir == dir) {
+    if fs::remove_dir(dir).is_err() {
+      // The directory isn't empty.
+      return;
+    }
+
+    dir = match dir.parent() {
+      Some(parent_dir) => parent_dir,
+      None => return
+    };
+  }
+}
+
+// Moves a header/source/template-impl set to a new directory or project, and rewrites
+// every include of the moved files in the project tree. Returns the old and new path of each
+// moved file, followed by the paths of any other files whose includes were updated.
+pub fn move_code_file_set(
+  root_project: &Rc<FinalProjectData>,
+  root_graph: &Rc<RefCell<DependencyGraph>>,
+  from_path: &str,
+  to_path: &str
+) -> Result<(Vec<(PathBuf, PathBuf)>, Vec<PathBuf>), GCMakeError> {
+  let mut all_projects: Vec<Rc<FinalProjectData>> = Vec::new();
+  all_projects_in_tree(root_project, &mut all_projects);
+
+  let from: CodeFileSetLocation = locate_existing_set(from_path, &all_projects)
+    .map_err(GCMakeError::Config)?;
+  let to: CodeFileSetLocation = locate_destination_set(to_path, &from, &all_projects)
+    .map_err(GCMakeError::Config)?;
+  let moved_files: Vec<MovedFile> = plan_moved_files(&from, &to)
+    .map_err(GCMakeError::Config)?;
+
+  if moved_files.is_empty() {
+    return Err(GCMakeError::Config(format!(
+      "There are no code files named '{}' in '{}'.",
+      from.shared_name.yellow(),
+      from_path.yellow()
+    )));
+  }
+
+  for moved_file in &moved_files {
+    if moved_file.old_path == moved_file.new_path {
+      return Err(GCMakeError::Config(format!("'{}' is already at '{}'.", from_path.yellow(), to_path.yellow())));
+    }
+    else if moved_file.new_path.exists() {
+      return Err(GCMakeError::Io(format!(
+        "Can't move the files because '{}' already exists.",
+        moved_file.new_path.to_str().unwrap().yellow()
+      )));
+    }
+  }
+
+  let moved_old_paths: HashSet<&PathBuf> = moved_files.iter()
+    .map(|moved_file| &moved_file.old_path)
+    .collect();
+  let mut rewritten_files: Vec<(PathBuf, String)> = Vec::new();
+
+  for (file_path, containing_project) in all_code_files_in_tree(&all_projects) {
+    if moved_old_paths.contains(&file_path) || !file_path.is_file() {
+      continue;
+    }
+
+    let file_contents: String = fs::read_to_string(&file_path)
+      .map_err(|err| GCMakeError::Io(format!("Failed to read '{}': {}", file_path.to_str().unwrap(), err.to_string())))?;
+
+    let (rewritten_contents, included_paths) = rewrite_includes(&file_contents, &moved_files);
+
+    for included_path in &included_paths {
+      let included_file: &MovedFile = moved_files.iter()
+        .find(|moved_file| moved_file.include_paths.as_ref().map_or(false, |(old_path, _)| old_path == included_path))
+        .unwrap();
+
+      let can_still_include: bool = if is_same_project(&containing_project, &to.project)
+        { true }
+        else if included_file.is_private_header
+        { false }
+        else { project_links_to(root_graph, &containing_project, &to.project) };
+
+      if !can_still_include {
+        return Err(GCMakeError::Graph(format!(
+          "Can't move '{}' into project '{}', because '{}' includes it but project '{}' does not link to '{}'.",
+          included_path.yellow(),
+          to.project.get_name_for_error_messages().yellow(),
+          file_path.strip_prefix(root_project.get_absolute_project_root()).unwrap().to_str().unwrap().yellow(),
+          containing_project.get_name_for_error_messages().yellow(),
+          to.project.get_name_for_error_messages().yellow()
+        )));
+      }
+    }
+
+    if !included_paths.is_empty() {
+      rewritten_files.push((file_path, rewritten_contents));
+    }
+  }
+
+  // Public headers in a compiled library use its export macro, which needs to change when moving
+  // the header into another library.
+  let export_renames: Vec<(String, String)> = match (export_names(&from.project), export_names(&to.project)) {
+    (Some((old_macro, old_header)), Some((new_macro, new_header))) => vec![(old_macro, new_macro), (old_header, new_header)],
+    _ => Vec::new()
+  };
+
+  let io_err_message = |file_path: &Path, err: std::io::Error| -> GCMakeError {
+    GCMakeError::Io(format!("Failed to write '{}': {}", file_path.to_str().unwrap(), err.to_string()))
+  };
+
+  for moved_file in &moved_files {
+    let file_contents: String = fs::read_to_string(&moved_file.old_path)
+      .map_err(|err| io_err_message(&moved_file.old_path, err))?;
+
+    let (mut new_contents, _) = rewrite_includes(&file_contents, &moved_files);
+
+    for (old_name, new_name) in moved_file.guard_names.iter().chain(&export_renames) {
+      let name_regex: Regex = Regex::new(&format!(r"\b{}\b", regex::escape(old_name))).unwrap();
+      new_contents = name_regex.replace_all(&new_contents, new_name.as_str()).to_string();
+    }
+
+    fs::create_dir_all(moved_file.new_path.parent().unwrap())
+      .and_then(|_| fs::write(&moved_file.new_path, new_contents))
+      .map_err(|err| io_err_message(&moved_file.new_path, err))?;
+
+    fs::remove_file(&moved_file.old_path)
+      .map_err(|err| io_err_message(&moved_file.old_path, err))?;
+
+    remove_empty_dirs(
+      moved_file.old_path.parent().unwrap(),
+      &code_dirs(&from.project).map_err(GCMakeError::Io)?
+    );
+  }
+
+  for (file_path, new_contents) in &rewritten_files {
+    fs::write(file_path, new_contents)
+      .map_err(|err| io_err_message(file_path, err))?;
+  }
+
+  return Ok((
+    moved_files.into_iter()
+      .map(|moved_file| (moved_file.old_path, moved_file.new_path))
+      .collect(),
+    rewritten_files.into_iter()
+      .map(|(file_path, _)| file_path)
+      .collect()
+  ));
+}
+
+#[cfg(test)]
+mod tests {
+  use std::path::PathBuf;
+
+  use super::{MovedFile, rewrite_includes, shared_name_of};
+
+  fn moved_header(old_include_path: &str, new_include_path: &str) -> MovedFile {
+    MovedFile {
+      old_path: PathBuf::new(),
+      new_path: PathBuf::new(),
+      include_paths: Some((old_include_path.to_string(), new_include_path.to_string())),
+      guard_names: None,
+      is_private_header: false
+    }
+  }
+
+  #[test]
+  fn test_shared_name_ignores_every_extension() {
+    assert_eq!(shared_name_of("thing.hpp"), "thing");
+    assert_eq!(shared_name_of("thing.private.hpp"), "thing");
+    assert_eq!(shared_name_of("thing"), "thing");
+  }
+
+  #[test]
+  fn test_only_includes_of_moved_files_are_rewritten() {
+    let moved_files = vec![moved_header("LIB/foo/bar.hpp", "LIB/baz/bar.hpp")];
+    let file_contents = "#include \"LIB/foo/bar.hpp\"\n  #  include <LIB/foo/bar.hpp>\n#include \"LIB/foo/bar.hpp.in\"\n#include <vector>\n// #include \"LIB/foo/bar.hpp\"\n";
+
+    let (rewritten, included_paths) = rewrite_includes(file_contents, &moved_files);
+
+    assert_eq!(
+      rewritten,
+      "#include \"LIB/baz/bar.hpp\"\n  #  include <LIB/baz/bar.hpp>\n#include \"LIB/foo/bar.hpp.in\"\n#include <vector>\n// #include \"LIB/foo/bar.hpp\"\n"
+    );
+    assert_eq!(included_paths.len(), 2);
+  }
+}
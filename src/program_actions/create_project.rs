@@ -1,5 +1,5 @@
 use std::{rc::Rc, collections::HashSet};
-use crate::{cli_config::{CLIProjectGenerationInfo, CLIProjectTypeGenerating}, project_info::{path_manipulation::cleaned_path_str, final_project_data::{FinalProjectData}, raw_data_in::SpecificCompilerSpecifier}, logger::exit_error_log, project_generator::{GeneralNewProjectInfo, create_project_at}};
+use crate::{cli_config::{CLIProjectGenerationInfo, CLIProjectTypeGenerating, NewProjectAnswers}, project_info::{path_manipulation::cleaned_path_str, final_project_data::{FinalProjectData}, raw_data_in::SpecificCompilerSpecifier}, gcmake_error::GCMakeError, logger::{exit_error_log, exit_with_error}, project_generator::{GeneralNewProjectInfo, ProjectTemplate, create_project_at}};
 use colored::*;
 
 pub enum ProjectTypeCreating {
@@ -109,11 +109,27 @@ pub fn handle_create_project(
     }
   };
 
+  let answers: NewProjectAnswers = match &generation_info.answers_file {
+    None => generation_info.answers,
+    Some(answers_file) => match NewProjectAnswers::from_file(answers_file) {
+      Ok(file_answers) => generation_info.answers.or(file_answers),
+      Err(err_msg) => exit_with_error(GCMakeError::Config(err_msg))
+    }
+  };
+
+  if let Err(err_msg) = answers.validate() {
+    exit_with_error(GCMakeError::Config(err_msg));
+  }
+
+  let maybe_template: Option<ProjectTemplate> = generation_info.template_name
+    .as_ref()
+    .map(|template_name| ProjectTemplate::load(template_name).unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg))));
+
   match create_project_at(
     &project_root_generating,
     project_creation_info,
-    generation_info.language,
-    generation_info.project_output_type
+    answers,
+    maybe_template.as_ref()
   ) {
     Ok(maybe_project) => match maybe_project {
       Some(general_new_project_info) => {
@@ -128,7 +144,7 @@ pub fn handle_create_project(
         *should_generate_cmakelists = false;
       }
     },
-    Err(err) => println!("{}", err)
+    Err(err) => exit_with_error(err.into())
   }
 
   return None;
@@ -0,0 +1,298 @@
+use crate::project_info::{dependency_graph_mod::dependency_graph::{TargetNode, ContainedItem}, final_project_data::FinalProjectData, raw_data_in::{BuildType, BuildConfigCompilerSpecifier, SpecificCompilerSpecifier, TargetSpecificBuildType}, CompiledOutputItem, PreBuildScriptType, FinalBuildConfig, CompilerFlag, CompilerDefine, SystemSpecifierWrapper};
+use colored::*;
+
+// A single flag or define, along with where it was configured.
+pub struct EffectiveItem<'a> {
+  pub value: &'a str,
+  pub system_spec: &'a SystemSpecifierWrapper,
+  pub source: &'static str
+}
+
+#[derive(Default)]
+pub struct EffectiveBuildConfig<'a> {
+  pub compiler_flags: Vec<EffectiveItem<'a>>,
+  pub link_time_flags: Vec<EffectiveItem<'a>>,
+  pub linker_flags: Vec<EffectiveItem<'a>>,
+  pub defines: Vec<EffectiveItem<'a>>
+}
+
+fn to_build_config_compiler(compiler: SpecificCompilerSpecifier) -> BuildConfigCompilerSpecifier {
+  return match compiler {
+    SpecificCompilerSpecifier::GCC => BuildConfigCompilerSpecifier::GCC,
+    SpecificCompilerSpecifier::Clang => BuildConfigCompilerSpecifier::Clang,
+    SpecificCompilerSpecifier::MSVC => BuildConfigCompilerSpecifier::MSVC,
+    SpecificCompilerSpecifier::CUDA => BuildConfigCompilerSpecifier::CUDA,
+    SpecificCompilerSpecifier::Emscripten => BuildConfigCompilerSpecifier::Emscripten
+  }
+}
+
+fn flag_items<'a>(flags: &'a Vec<CompilerFlag>, source: &'static str) -> impl Iterator<Item=EffectiveItem<'a>> {
+  flags.iter()
+    .map(move |flag| EffectiveItem {
+      value: flag.flag_string.trim(),
+      system_spec: &flag.system_spec,
+      source
+    })
+}
+
+fn define_items<'a>(defines: &'a Vec<CompilerDefine>, source: &'static str) -> impl Iterator<Item=EffectiveItem<'a>> {
+  defines.iter()
+    .map(move |define| EffectiveItem {
+      value: define.def_string.trim(),
+      system_spec: &define.system_spec,
+      source
+    })
+}
+
+impl<'a> EffectiveBuildConfig<'a> {
+  fn append_config(
+    &mut self,
+    build_config: &'a FinalBuildConfig,
+    compiler: SpecificCompilerSpecifier,
+    is_compiler_specific: bool,
+    source: &'static str
+  ) {
+    // Mirrors the CMakeLists writer: flags are only ever written inside a compiler check,
+    // so flags given for "all compilers" are never used. Defines always are.
+    if is_compiler_specific {
+      self.compiler_flags.extend(flag_items(&build_config.compiler_flags, source));
+
+      if let SpecificCompilerSpecifier::Emscripten = compiler {
+        // Emscripten is also passed the compiler flags at link time.
+        self.link_time_flags.extend(flag_items(&build_config.compiler_flags, source));
+      }
+
+      self.link_time_flags.extend(flag_items(&build_config.link_time_flags, source));
+      self.linker_flags.extend(flag_items(&build_config.linker_flags, source));
+    }
+
+    self.defines.extend(define_items(&build_config.defines, source));
+  }
+}
+
+// Returns the compiled output contained in the target, if the target has a build configuration.
+pub fn target_compiled_output<'a>(target: &'a TargetNode) -> Option<&'a CompiledOutputItem> {
+  return match target.get_contained_item() {
+    ContainedItem::CompiledOutput(output) => Some(output),
+    ContainedItem::PreBuild(pre_build_script) => match pre_build_script.get_type() {
+      PreBuildScriptType::Exe(output) => Some(output),
+      PreBuildScriptType::Python(_) => None
+    },
+    ContainedItem::PredefinedLibrary { .. } => None
+  }
+}
+
+// Merges the project build configs, global defines and the target's own build_config in the
+// same order the generated CMakeLists applies them. Project-wide settings are only written by the
+// root project's CMakeLists and inherited by its subprojects, so they come from root_project.
+pub fn effective_build_config<'a>(
+  root_project: &'a FinalProjectData,
+  output: &'a CompiledOutputItem,
+  build_type: &BuildType,
+  compiler: SpecificCompilerSpecifier
+) -> EffectiveBuildConfig<'a> {
+  let mut effective_config: EffectiveBuildConfig = EffectiveBuildConfig::default();
+  let compiler_specifier: BuildConfigCompilerSpecifier = to_build_config_compiler(compiler);
+
+  effective_config.defines.extend(define_items(root_project.get_global_defines(), "global_defines"));
+
+  if let Some(project_config) = root_project.get_build_configs().get(build_type) {
+    if let Some(all_compilers_config) = project_config.get(&BuildConfigCompilerSpecifier::AllCompilers) {
+      effective_config.append_config(all_compilers_config, compiler, false, "project build_configs");
+    }
+
+    if let Some(compiler_config) = project_config.get(&compiler_specifier) {
+      // Header-only libraries don't inherit the project-wide flags, only the defines.
+      if output.is_header_only_type() {
+        effective_config.defines.extend(define_items(&compiler_config.defines, "project build_configs"));
+      }
+      else {
+        effective_config.append_config(compiler_config, compiler, true, "project build_configs");
+      }
+    }
+  }
+
+  if let Some(target_config_map) = output.get_build_config_map() {
+    let target_build_types: [TargetSpecificBuildType; 2] = [
+      TargetSpecificBuildType::AllConfigs,
+      match build_type {
+        BuildType::Debug => TargetSpecificBuildType::Debug,
+        BuildType::Release => TargetSpecificBuildType::Release,
+        BuildType::MinSizeRel => TargetSpecificBuildType::MinSizeRel,
+        BuildType::RelWithDebInfo => TargetSpecificBuildType::RelWithDebInfo
+      }
+    ];
+
+    for target_build_type in &target_build_types {
+      if let Some(config_by_compiler) = target_config_map.get(target_build_type) {
+        if let Some(all_compilers_config) = config_by_compiler.get(&BuildConfigCompilerSpecifier::AllCompilers) {
+          effective_config.append_config(all_compilers_config, compiler, false, "target build_config");
+        }
+
+        if let Some(compiler_config) = config_by_compiler.get(&compiler_specifier) {
+          effective_config.append_config(compiler_config, compiler, true, "target build_config");
+        }
+      }
+    }
+  }
+
+  return effective_config;
+}
+
+pub fn print_effective_config_header(
+  target: &TargetNode,
+  build_type: &BuildType,
+  compiler: SpecificCompilerSpecifier
+) {
+  println!(
+    "\n========== {} ({}, {}) ==========",
+    target.get_yaml_namespaced_target_name().green(),
+    build_type.name_str(),
+    compiler.name_string()
+  );
+}
+
+pub fn print_effective_items(title: &str, items: &Vec<EffectiveItem>) {
+  println!("{}:", title);
+
+  if items.is_empty() {
+    println!("  (none)");
+    return;
+  }
+
+  for item in items {
+    let system_spec_str: String = match item.system_spec {
+      SystemSpecifierWrapper::All => String::new(),
+      SystemSpecifierWrapper::Specific(spec_tree) => format!(" {}", spec_tree.to_string().yellow())
+    };
+
+    println!(
+      "  {}{} {}",
+      item.value,
+      system_spec_str,
+      format!("[{}]", item.source).cyan()
+    );
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::rc::Rc;
+
+  use crate::{project_info::final_project_data::FinalProjectLoadContext, test_projects::{TestProjectDir, no_dep_configs}};
+
+  use super::*;
+
+  const FLAGS_CMAKE_DATA: &str = "name: flags
+vendor: some-vendor
+include_prefix: FLAGS
+description: Project used to test effective build configs
+version: 0.0.1
+default_build_type: Debug
+languages:
+  cpp:
+    min_standard: 17
+supported_compilers:
+  - GCC
+  - Clang
+global_defines:
+  - GLOBAL_DEF
+build_configs:
+  Debug:
+    AllCompilers:
+      defines:
+        - DEBUG_DEF
+    GCC:
+      compiler_flags:
+        - -Wall
+      defines:
+        - GCC_DEBUG_DEF
+  Release:
+    GCC:
+      compiler_flags:
+        - -O2
+output:
+  flags-exe:
+    output_type: Executable
+    entry_file: main.cpp
+    build_config:
+      AllConfigs:
+        AllCompilers:
+          defines:
+            - TARGET_DEF
+        GCC:
+          compiler_flags:
+            - -Wextra
+      Debug:
+        GCC:
+          linker_flags:
+            - --as-needed
+";
+
+  fn values_of<'a>(items: &'a Vec<EffectiveItem>) -> Vec<&'a str> {
+    return items.iter()
+      .map(|item| item.value)
+      .collect();
+  }
+
+  fn load_flags_project(project_dir: &TestProjectDir) -> Rc<FinalProjectData> {
+    project_dir.write_file("cmake_data.yaml", FLAGS_CMAKE_DATA);
+    project_dir.write_file("main.cpp", "int main() { return 0; }\n");
+    project_dir.write_file(
+      "subprojects/header/cmake_data.yaml",
+      "include_prefix: HEADER\ndescription: d\nversion: 0.0.1\noutput:\n  flags-header:\n    output_type: HeaderOnlyLib\n    entry_file: flags-header.hpp\n"
+    );
+    project_dir.write_file("subprojects/header/flags-header.hpp", "#pragma once\n");
+
+    return FinalProjectData::new(&project_dir.path_str(""), &no_dep_configs(), FinalProjectLoadContext::default())
+      .unwrap_or_else(|err| panic!("{}", err.extract_message()))
+      .root_project;
+  }
+
+  #[test]
+  fn test_project_global_and_target_configs_are_merged_in_order() {
+    let project_dir = TestProjectDir::new("effective-build-config");
+    let project: Rc<FinalProjectData> = load_flags_project(&project_dir);
+    let exe: &CompiledOutputItem = project.get_outputs().get("flags-exe").unwrap();
+
+    let gcc_debug = effective_build_config(&project, exe, &BuildType::Debug, SpecificCompilerSpecifier::GCC);
+    assert_eq!(values_of(&gcc_debug.compiler_flags), vec!["-Wall", "-Wextra"]);
+    assert_eq!(values_of(&gcc_debug.linker_flags), vec!["--as-needed"]);
+    assert_eq!(values_of(&gcc_debug.defines), vec!["GLOBAL_DEF", "DEBUG_DEF", "GCC_DEBUG_DEF", "TARGET_DEF"]);
+    assert_eq!(gcc_debug.compiler_flags[0].source, "project build_configs");
+    assert_eq!(gcc_debug.compiler_flags[1].source, "target build_config");
+
+    let gcc_release = effective_build_config(&project, exe, &BuildType::Release, SpecificCompilerSpecifier::GCC);
+    assert_eq!(values_of(&gcc_release.compiler_flags), vec!["-O2", "-Wextra"]);
+    assert!(gcc_release.linker_flags.is_empty());
+    assert_eq!(values_of(&gcc_release.defines), vec!["GLOBAL_DEF", "TARGET_DEF"]);
+  }
+
+  #[test]
+  fn test_all_compilers_configs_only_contribute_defines_to_every_compiler() {
+    let project_dir = TestProjectDir::new("effective-build-config-all-compilers");
+    let project: Rc<FinalProjectData> = load_flags_project(&project_dir);
+    let exe: &CompiledOutputItem = project.get_outputs().get("flags-exe").unwrap();
+
+    let clang_debug = effective_build_config(&project, exe, &BuildType::Debug, SpecificCompilerSpecifier::Clang);
+
+    assert!(clang_debug.compiler_flags.is_empty());
+    assert!(clang_debug.link_time_flags.is_empty());
+    assert!(clang_debug.linker_flags.is_empty());
+    assert_eq!(values_of(&clang_debug.defines), vec!["GLOBAL_DEF", "DEBUG_DEF", "TARGET_DEF"]);
+  }
+
+  #[test]
+  fn test_header_only_targets_only_inherit_project_defines() {
+    let project_dir = TestProjectDir::new("effective-build-config-header-only");
+    let project: Rc<FinalProjectData> = load_flags_project(&project_dir);
+    let header_lib: &CompiledOutputItem = project.get_subprojects().get("header").unwrap()
+      .get_outputs().get("flags-header").unwrap();
+
+    let gcc_debug = effective_build_config(&project, header_lib, &BuildType::Debug, SpecificCompilerSpecifier::GCC);
+
+    assert!(gcc_debug.compiler_flags.is_empty());
+    assert!(gcc_debug.linker_flags.is_empty());
+    assert_eq!(values_of(&gcc_debug.defines), vec!["GLOBAL_DEF", "DEBUG_DEF", "GCC_DEBUG_DEF"]);
+  }
+}
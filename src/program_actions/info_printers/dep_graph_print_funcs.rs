@@ -0,0 +1,176 @@
+use std::{rc::Rc, cell::RefCell, collections::BTreeSet};
+
+use crate::project_info::{dependency_graph_mod::dependency_graph::{TargetNode, DependencyGraph, ProjectWrapper, Link}, SystemSpecifierWrapper};
+use colored::*;
+
+// Describes where a linked target comes from, relative to the project tree being inspected.
+pub fn target_source_description(target: &TargetNode) -> String {
+  let container_project = target.container_project();
+  let borrowed_container = container_project.as_ref().borrow();
+
+  if let ProjectWrapper::PredefinedDependency(_) = borrowed_container.project_wrapper() {
+    return format!("predefined dependency '{}'", borrowed_container.project_identifier_name());
+  }
+
+  let root_project = borrowed_container.root_project();
+  let borrowed_root = root_project.as_ref().borrow();
+
+  if let ProjectWrapper::GCMakeDependencyRoot(_) = borrowed_root.project_wrapper() {
+    return format!("gcmake dependency '{}'", borrowed_root.project_identifier_name());
+  }
+
+  return match borrowed_container.parent_project() {
+    Some(_) => format!("subproject '{}'", borrowed_container.project_debug_name()),
+    None => format!("root project '{}'", borrowed_container.project_debug_name())
+  }
+}
+
+pub fn system_spec_description(system_spec: &SystemSpecifierWrapper) -> String {
+  return match system_spec {
+    SystemSpecifierWrapper::All => String::from("all systems"),
+    SystemSpecifierWrapper::Specific(spec_tree) => spec_tree.to_string()
+  }
+}
+
+pub fn print_target_dep_tree(target_rc: &Rc<RefCell<TargetNode>>) {
+  print!("{}", target_dep_tree_string(target_rc));
+}
+
+pub fn target_dep_tree_string(target_rc: &Rc<RefCell<TargetNode>>) -> String {
+  let target: &TargetNode = &target_rc.as_ref().borrow();
+  let mut already_expanded: BTreeSet<String> = BTreeSet::new();
+  let mut tree: String = format!("\n{}\n", target.get_yaml_namespaced_target_name().green());
+
+  if target.get_depends_on().is_empty() {
+    tree.push_str("  (no dependencies)\n");
+    return tree;
+  }
+
+  write_links_recursive(&mut tree, target, 1, &mut already_expanded);
+  return tree;
+}
+
+fn write_links_recursive(
+  tree: &mut String,
+  target: &TargetNode,
+  depth: usize,
+  already_expanded: &mut BTreeSet<String>
+) {
+  for (_, link) in target.get_depends_on() {
+    write_single_link(tree, link, depth);
+
+    let linked_target_rc = link.linked_target();
+    let linked_target: &TargetNode = &linked_target_rc.as_ref().borrow();
+
+    if !linked_target.get_depends_on().is_empty() {
+      // Shared dependencies are only expanded once per tree to keep the output readable.
+      if already_expanded.insert(linked_target.get_yaml_namespaced_target_name().to_string()) {
+        write_links_recursive(tree, linked_target, depth + 1, already_expanded);
+      }
+      else {
+        tree.push_str(&format!("{}...\n", "  ".repeat(depth + 1)));
+      }
+    }
+  }
+}
+
+fn write_single_link(tree: &mut String, link: &Link, depth: usize) {
+  let linked_target_rc = link.linked_target();
+  let linked_target: &TargetNode = &linked_target_rc.as_ref().borrow();
+
+  let system_spec_str: String = match link.get_system_spec_info() {
+    SystemSpecifierWrapper::All => String::new(),
+    specific_spec => format!(" {}", system_spec_description(specific_spec).yellow())
+  };
+
+  tree.push_str(&format!(
+    "{}-> {} {}{} [{}]\n",
+    "  ".repeat(depth),
+    format!("({})", link.get_link_mode().to_str()).cyan(),
+    linked_target.get_yaml_namespaced_target_name(),
+    system_spec_str,
+    target_source_description(linked_target)
+  ));
+}
+
+// Collects every target in the project and its subprojects and tests, including pre-build scripts.
+pub fn all_project_targets<'a>(project: &Rc<RefCell<DependencyGraph<'a>>>) -> Vec<Rc<RefCell<TargetNode<'a>>>> {
+  let borrowed_project = project.as_ref().borrow();
+  let mut targets: Vec<Rc<RefCell<TargetNode<'a>>>> = Vec::new();
+
+  if let Some(pre_build_node) = borrowed_project.get_pre_build_node() {
+    targets.push(Rc::clone(pre_build_node));
+  }
+
+  for (_, target_rc) in borrowed_project.get_this_target_map().borrow().iter() {
+    targets.push(Rc::clone(target_rc));
+  }
+
+  for (_, subproject) in borrowed_project.get_subprojects() {
+    targets.extend(all_project_targets(subproject));
+  }
+
+  for (_, test_project) in borrowed_project.get_test_projects() {
+    targets.extend(all_project_targets(test_project));
+  }
+
+  return targets;
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{api::{self, DependencyGraphWarningMode}, project_info::raw_data_in::dependencies::RawPredefinedDependencyMap, test_projects::{TestProjectDir, git_dep_config_yaml}};
+
+  use super::target_dep_tree_string;
+
+  #[test]
+  fn test_dep_tree_shows_link_mode_system_spec_and_origin() {
+    let test_dir = TestProjectDir::new("dep-tree");
+
+    test_dir.write_file("repo/good/dep_config.yaml", &git_dep_config_yaml("good", "https://example.com/good.git"));
+    test_dir.write_root_project(
+      "app",
+      "app",
+      &["((linux)) util::util-lib", "good::lib", "other::other-lib"],
+      "predefined_dependencies:\n  good:\n    git_tag: v1.0.0\ngcmake_dependencies:\n  other:\n    path: ../other\n"
+    );
+    test_dir.write_file(
+      "app/subprojects/util/cmake_data.yaml",
+      "include_prefix: UTIL\ndescription: d\nversion: 0.0.1\noutput:\n  util-lib:\n    output_type: HeaderOnlyLib\n    entry_file: util-lib.hpp\n"
+    );
+    test_dir.write_file("app/subprojects/util/util-lib.hpp", "#pragma once\n");
+    test_dir.write_file(
+      "other/cmake_data.yaml",
+      "name: other\nvendor: some-vendor\ninclude_prefix: OTHER\ndescription: d\nversion: 0.0.1\ndefault_build_type: Debug\nlanguages:\n  cpp:\n    min_standard: 17\nsupported_compilers:\n  - GCC\nbuild_configs:\n  Debug:\n    AllCompilers: {}\noutput:\n  other-lib:\n    output_type: HeaderOnlyLib\n    entry_file: other-lib.hpp\n"
+    );
+    test_dir.write_file("other/other-lib.hpp", "#pragma once\n");
+
+    let dep_config: RawPredefinedDependencyMap = test_dir.dep_config_repo("repo");
+
+    let project_group = api::load_project(&test_dir.path_str("app"), &dep_config).unwrap();
+    let graph_info = api::load_dependency_graph(&project_group, DependencyGraphWarningMode::Off).unwrap();
+    let root_graph = graph_info.root_dep_graph.as_ref().borrow();
+    let exe_target = api::find_targets(&root_graph, "app-exe").unwrap().remove(0);
+
+    let tree: String = target_dep_tree_string(&exe_target);
+    let link_lines: Vec<&str> = tree.lines().filter(|line| line.contains("->")).collect();
+
+    assert_eq!(link_lines.len(), 3, "{}", tree);
+
+    let line_for = |target_name: &str| -> &str {
+      return link_lines.iter()
+        .find(|line| line.contains(target_name))
+        .unwrap_or_else(|| panic!("'{}' is missing from the tree:\n{}", target_name, tree));
+    };
+
+    // Executables link everything privately.
+    let util_line: &str = line_for("app::util-lib");
+    assert!(util_line.contains("(private)"), "{}", util_line);
+    assert!(util_line.contains("((linux))"), "{}", util_line);
+    assert!(util_line.ends_with("[subproject 'app => util']"), "{}", util_line);
+
+    assert!(line_for("good::lib").ends_with("[predefined dependency 'good']"), "{}", tree);
+    assert!(line_for("other::other-lib").ends_with("[gcmake dependency 'other']"), "{}", tree);
+    assert!(!line_for("other::other-lib").contains("linux"), "{}", tree);
+  }
+}
@@ -1,3 +1,5 @@
 pub mod target_info_print_funcs;
 pub mod project_info_print_funcs;
 pub mod predef_dep_info_print_funcs;
+pub mod dep_graph_print_funcs;
+pub mod build_config_print_funcs;
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use crate::{project_info::raw_data_in::dependencies::internal_dep_config::raw_dep_common::RawPredepCommon};
 use colored::*;
 
@@ -73,4 +75,8 @@ pub fn print_predep_doc_link(common_info: &dyn RawPredepCommon) {
     None => println!("Doesn't have a README"),
     Some(gcmake_readme_url) => println!("{}", gcmake_readme_url)
   }
-}
\ No newline at end of file
+}
+
+pub fn print_predep_config_dir(dep_name: &str, config_root_dir: &Path) {
+  println!("{}", config_root_dir.join(dep_name).to_str().unwrap());
+}
@@ -1,4 +1,4 @@
-use crate::project_info::{dependency_graph_mod::dependency_graph::{DependencyGraph, ProjectWrapper}, final_dependencies::FinalPredepInfo};
+use crate::project_info::{dependency_graph_mod::dependency_graph::{DependencyGraph, ProjectWrapper}, final_dependencies::{FinalPredepInfo, FinalGCMakeDependency, GCMakeDependencySource}};
 use colored::*;
 
 pub fn print_project_header(project: &DependencyGraph) {
@@ -33,6 +33,17 @@ pub fn print_immediate_subprojects(project_graph: &DependencyGraph) {
   }
 }
 
+fn gcmake_repo_url(gcmake_dep: &FinalGCMakeDependency) -> Result<String, String> {
+  return match gcmake_dep.source() {
+    GCMakeDependencySource::GitRepo(git_repo) => Ok(git_repo.repo_url.clone()),
+    GCMakeDependencySource::LocalPath(local_path) => Err(format!(
+      "\"{}\" doesn't have a repository URL because it is loaded from the local path '{}'.",
+      gcmake_dep.given_dependency_name(),
+      local_path.to_str().unwrap()
+    ))
+  }
+}
+
 fn extract_repo_url(project_graph: &DependencyGraph) -> Result<String, String> {
   return match project_graph.project_wrapper() {
     ProjectWrapper::PredefinedDependency(predef_def) => match predef_def.predefined_dep_info() {
@@ -46,12 +57,12 @@ fn extract_repo_url(project_graph: &DependencyGraph) -> Result<String, String> {
         ))
       }
     },
-    ProjectWrapper::GCMakeDependencyRoot(gcmake_dep) => Ok(gcmake_dep.repo_url().to_string()),
+    ProjectWrapper::GCMakeDependencyRoot(gcmake_dep) => gcmake_repo_url(gcmake_dep),
     // Normal projects don't have a repository URL because they are part of the repository itself. However,
     // the root of a normal project may be a GCMake dependency.
     ProjectWrapper::NormalProject(_) => {
       if let ProjectWrapper::GCMakeDependencyRoot(root) = project_graph.root_project().as_ref().borrow().project_wrapper() {
-        Ok(root.repo_url().to_string())
+        gcmake_repo_url(root)
       }
       else {
         Err(format!("No repo URL"))
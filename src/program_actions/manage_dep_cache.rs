@@ -0,0 +1,311 @@
+use std::{path::{Path, PathBuf}, fs, io, process::{self, Output}, collections::{HashMap, HashSet}, rc::Rc};
+
+use crate::project_info::{final_project_data::FinalProjectData, final_dependencies::{relative_hash_file_path, FinalPredepInfo, GitRevisionSpecifier, GCMakeDependencyStatus}};
+
+use super::gcmake_dep_cache_dir;
+
+// What we know about the dependency version stored in a cache entry.
+pub enum CachedVersionInfo {
+  // A GCMake project dependency. The hash is the one written by gcmake_write_dep_hash_file_if_missing.
+  GCMakeProject {
+    hash: String
+  },
+  GitCheckout {
+    commit: String,
+    tags: Vec<String>
+  },
+  // Usually a dependency downloaded from a URL, since the extracted archive doesn't record its version.
+  Unknown
+}
+
+impl CachedVersionInfo {
+  pub fn to_display_string(&self) -> String {
+    return match self {
+      Self::GCMakeProject { hash } => format!("gcmake project (hash {})", hash),
+      Self::GitCheckout { commit, tags } => {
+        let short_commit: &str = &commit[..commit.len().min(10)];
+
+        if tags.is_empty() {
+          format!("git commit {}", short_commit)
+        }
+        else {
+          format!("git {} ({})", tags.join(", "), short_commit)
+        }
+      },
+      Self::Unknown => String::from("unknown version")
+    }
+  }
+}
+
+pub struct DepCacheEntry {
+  // CPM lowercases dependency names when creating their cache directories.
+  pub dep_name: String,
+  pub path: PathBuf,
+  pub size_bytes: u64,
+  pub version: CachedVersionInfo
+}
+
+// A git revision configured for a dependency, along with the commit it was locked to (if any).
+// The locked commit is what actually gets checked out.
+struct ReferencedGitRevision {
+  configured: GitRevisionSpecifier,
+  locked_commit: Option<String>
+}
+
+// Decides whether a cached git checkout is the given configured revision. Shallow clones often
+// don't have any tag refs, so a checkout without tags might still be a tagged revision. Those are
+// kept unless the revision is locked, since there's no way to tell without fetching.
+fn is_checkout_of_revision(commit: &str, tags: &[String], revision: &ReferencedGitRevision) -> bool {
+  if let Some(locked_commit) = &revision.locked_commit {
+    if commit == locked_commit {
+      return true;
+    }
+  }
+
+  return match &revision.configured {
+    GitRevisionSpecifier::CommitHash(hash) => commit.starts_with(hash.as_str()),
+    GitRevisionSpecifier::Tag(tag) => tags.contains(tag) || (tags.is_empty() && revision.locked_commit.is_none())
+  }
+}
+
+// Dependency versions referenced by a set of projects. Used to determine which cache entries
+// are still in use.
+#[derive(Default)]
+pub struct DepCacheReferences {
+  gcmake_project_hashes: HashSet<String>,
+  git_revisions: HashMap<String, Vec<ReferencedGitRevision>>,
+  dep_names: HashSet<String>
+}
+
+impl DepCacheReferences {
+  pub fn add_project_tree(&mut self, root_project: &Rc<FinalProjectData>) {
+    for (dep_name, predep_config) in root_project.get_predefined_dependencies() {
+      if let FinalPredepInfo::Subdirectory(subdir_dep) = predep_config.predefined_dep_info() {
+        let cache_name: String = dep_name.to_lowercase();
+
+        if let Some(git_details) = subdir_dep.download_method().git_details() {
+          let locked_commit: Option<String> = root_project.get_dependency_lock()
+            .and_then(|lockfile| lockfile.predefined_dependencies.get(dep_name))
+            .and_then(|locked| locked.locked_commit_for(git_details))
+            .map(String::from);
+
+          self.git_revisions.entry(cache_name.clone())
+            .or_default()
+            .push(ReferencedGitRevision {
+              configured: git_details.revision_specifier.clone(),
+              locked_commit
+            });
+        }
+
+        self.dep_names.insert(cache_name);
+      }
+    }
+
+    for (dep_name, gcmake_dep) in root_project.get_gcmake_dependencies() {
+      if let Some(hash_info) = gcmake_dep.get_hash_info() {
+        self.gcmake_project_hashes.insert(hash_info.hash_string.clone());
+      }
+
+      self.dep_names.insert(dep_name.to_lowercase());
+
+      // Dependencies of downloaded GCMake projects also live in the cache.
+      if let GCMakeDependencyStatus::Available(dep_project) = gcmake_dep.project_status() {
+        self.add_project_tree(dep_project);
+      }
+    }
+  }
+
+  pub fn is_referenced(&self, entry: &DepCacheEntry) -> bool {
+    return match &entry.version {
+      CachedVersionInfo::GCMakeProject { hash } => self.gcmake_project_hashes.contains(hash),
+      CachedVersionInfo::GitCheckout { commit, tags } => match self.git_revisions.get(&entry.dep_name) {
+        None => false,
+        Some(revisions) => revisions.iter().any(|revision| is_checkout_of_revision(commit, tags, revision))
+      },
+      // Without knowing the version, the best we can do is keep every entry of a dependency
+      // which is still in use.
+      CachedVersionInfo::Unknown => self.dep_names.contains(&entry.dep_name)
+    }
+  }
+}
+
+fn dir_size(dir: &Path) -> io::Result<u64> {
+  let mut total_size: u64 = 0;
+
+  for dirent in dir.read_dir()? {
+    let dirent = dirent?;
+    // Don't follow symlinks. They may point outside the cache.
+    let metadata: fs::Metadata = fs::symlink_metadata(dirent.path())?;
+
+    if metadata.is_dir() {
+      total_size += dir_size(&dirent.path())?;
+    }
+    else {
+      total_size += metadata.len();
+    }
+  }
+
+  return Ok(total_size);
+}
+
+fn run_git_in(dir: &Path, args: &[&str]) -> Option<String> {
+  let output: Output = process::Command::new("git")
+    .current_dir(dir)
+    .args(args)
+    .output()
+    .ok()?;
+
+  if !output.status.success() {
+    return None;
+  }
+
+  return String::from_utf8(output.stdout).ok();
+}
+
+fn cached_version_info(entry_path: &Path) -> CachedVersionInfo {
+  let hash_file_path: PathBuf = entry_path.join(relative_hash_file_path());
+
+  if hash_file_path.is_file() {
+    if let Ok(hash) = fs::read_to_string(&hash_file_path) {
+      return CachedVersionInfo::GCMakeProject { hash };
+    }
+  }
+
+  if entry_path.join(".git").exists() {
+    if let Some(commit) = run_git_in(entry_path, &["rev-parse", "HEAD"]) {
+      let tags: Vec<String> = run_git_in(entry_path, &["tag", "--points-at", "HEAD"])
+        .map_or(Vec::new(), |tag_output| tag_output.lines().map(String::from).collect());
+
+      return CachedVersionInfo::GitCheckout {
+        commit: commit.trim().to_string(),
+        tags
+      };
+    }
+  }
+
+  return CachedVersionInfo::Unknown;
+}
+
+// The cache is laid out by CPM as <cache>/<lowercase dep name>/<hash of the CPMAddPackage arguments>.
+// Each dependency directory also contains a cmake.lock file, which is ignored here.
+pub fn list_dep_cache_entries(maybe_dep_name: Option<&str>) -> io::Result<Vec<DepCacheEntry>> {
+  let cache_dir: PathBuf = gcmake_dep_cache_dir();
+  let mut entries: Vec<DepCacheEntry> = Vec::new();
+
+  if !cache_dir.is_dir() {
+    return Ok(entries);
+  }
+
+  for dep_dirent in cache_dir.read_dir()? {
+    let dep_dir: PathBuf = dep_dirent?.path();
+    let dep_name: String = dep_dir.file_name().unwrap().to_str().unwrap().to_string();
+
+    if !dep_dir.is_dir() {
+      continue;
+    }
+
+    if let Some(selected_name) = maybe_dep_name {
+      if dep_name != selected_name.to_lowercase() {
+        continue;
+      }
+    }
+
+    for entry_dirent in dep_dir.read_dir()? {
+      let entry_path: PathBuf = entry_dirent?.path();
+
+      if entry_path.is_dir() {
+        entries.push(DepCacheEntry {
+          dep_name: dep_name.clone(),
+          size_bytes: dir_size(&entry_path)?,
+          version: cached_version_info(&entry_path),
+          path: entry_path
+        });
+      }
+    }
+  }
+
+  entries.sort_by(|left, right| left.dep_name.cmp(&right.dep_name).then(left.path.cmp(&right.path)));
+  return Ok(entries);
+}
+
+pub fn remove_dep_cache_entry(entry: &DepCacheEntry) -> io::Result<()> {
+  fs::remove_dir_all(&entry.path)?;
+
+  // Remove the dependency directory as well once its last entry is gone. Only the lock file is left over.
+  let dep_dir: &Path = entry.path.parent().unwrap();
+  let has_remaining_entries: bool = dep_dir.read_dir()?
+    .filter_map(|dirent| dirent.ok())
+    .any(|dirent| dirent.path().is_dir());
+
+  if !has_remaining_entries {
+    fs::remove_dir_all(dep_dir)?;
+  }
+
+  Ok(())
+}
+
+pub fn human_readable_size(size_bytes: u64) -> String {
+  const UNITS: [&'static str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+  if size_bytes < 1024 {
+    return format!("{} B", size_bytes);
+  }
+
+  let mut size: f64 = size_bytes as f64;
+  let mut unit: &str = UNITS[0];
+
+  for unit_name in UNITS {
+    size /= 1024.0;
+    unit = unit_name;
+
+    if size < 1024.0 {
+      break;
+    }
+  }
+
+  return format!("{:.1} {}", size, unit);
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const LOCKED_COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+  const OTHER_COMMIT: &str = "fedcba9876543210fedcba9876543210fedcba98";
+
+  fn tag_revision(tag: &str, locked_commit: Option<&str>) -> ReferencedGitRevision {
+    return ReferencedGitRevision {
+      configured: GitRevisionSpecifier::Tag(tag.to_string()),
+      locked_commit: locked_commit.map(String::from)
+    };
+  }
+
+  #[test]
+  fn test_locked_tags_match_the_locked_commit_without_tag_refs() {
+    let revision = tag_revision("v1.2.3", Some(LOCKED_COMMIT));
+
+    assert!(is_checkout_of_revision(LOCKED_COMMIT, &[], &revision));
+    assert!(!is_checkout_of_revision(OTHER_COMMIT, &[], &revision));
+    assert!(is_checkout_of_revision(OTHER_COMMIT, &[String::from("v1.2.3")], &revision));
+  }
+
+  #[test]
+  fn test_unlocked_tags_are_kept_when_the_checkout_has_no_tag_refs() {
+    let revision = tag_revision("v1.2.3", None);
+
+    assert!(is_checkout_of_revision(OTHER_COMMIT, &[], &revision));
+    assert!(is_checkout_of_revision(OTHER_COMMIT, &[String::from("v1.2.3")], &revision));
+    assert!(!is_checkout_of_revision(OTHER_COMMIT, &[String::from("v1.0.0")], &revision));
+  }
+
+  #[test]
+  fn test_commit_hashes_match_by_prefix() {
+    let revision = ReferencedGitRevision {
+      configured: GitRevisionSpecifier::CommitHash(String::from("0123456")),
+      locked_commit: None
+    };
+
+    assert!(is_checkout_of_revision(LOCKED_COMMIT, &[], &revision));
+    assert!(!is_checkout_of_revision(OTHER_COMMIT, &[String::from("v1.2.3")], &revision));
+  }
+}
@@ -1,7 +1,21 @@
 use std::{env, path::{PathBuf, Path}, process::{self, Output, Stdio}, io, fs};
 use colored::*;
+use serde::Deserialize;
 
 const GCMAKE_DEP_CONFIG_REPO_SSH_URL: &'static str = "git@github.com:scupit/gcmake-dependency-configs.git";
+const DEP_CONFIG_REPO_LIST_FILE_NAME: &'static str = "dep-config-repos.yaml";
+const DEP_CACHE_DIR_NAME: &'static str = "dep-cache";
+const PROJECT_TEMPLATES_DIR_NAME: &'static str = "templates";
+const FILE_TEMPLATES_DIR_NAME: &'static str = "file-templates";
+
+// Entries in the GCMake config directory which GCMake uses itself. Dependency config repositories
+// are cloned into a directory named after the repository, so they can't use any of these names.
+const RESERVED_CONFIG_DIR_ENTRY_NAMES: [&'static str; 4] = [
+  DEP_CONFIG_REPO_LIST_FILE_NAME,
+  DEP_CACHE_DIR_NAME,
+  PROJECT_TEMPLATES_DIR_NAME,
+  FILE_TEMPLATES_DIR_NAME
+];
 
 pub fn gcmake_config_root_dir() -> PathBuf {
   let user_home_var_name: &str = if cfg!(windows)
@@ -19,20 +33,179 @@ pub fn gcmake_dep_cache_dir() -> PathBuf {
   let mut the_dir: PathBuf = gcmake_config_root_dir();
   // Make sure this is keps in sync with the GCMAKE_DEP_CACHE_DIR variable defined in
   // the gcmake-variables.cmake util file.
-  the_dir.push("dep-cache");
+  the_dir.push(DEP_CACHE_DIR_NAME);
   return the_dir;
 }
 
-fn get_repo_name() -> &'static str {
-  let without_prefix: &str = GCMAKE_DEP_CONFIG_REPO_SSH_URL.trim_end_matches(".git");
-  let first_name_index: usize = GCMAKE_DEP_CONFIG_REPO_SSH_URL.rfind('/')
+pub fn gcmake_project_templates_dir() -> PathBuf {
+  gcmake_config_root_dir().join(PROJECT_TEMPLATES_DIR_NAME)
+}
+
+pub fn gcmake_file_templates_dir() -> PathBuf {
+  gcmake_config_root_dir().join(FILE_TEMPLATES_DIR_NAME)
+}
+
+fn get_repo_name(repo_url: &str) -> &str {
+  let without_prefix: &str = repo_url.trim_end_matches('/').trim_end_matches(".git");
+  let first_name_index: usize = without_prefix.rfind(|c| c == '/' || c == '\\' || c == ':')
     .map_or(0, |index| index + 1);
 
   return &without_prefix[first_name_index..];
 }
 
+fn is_valid_cloned_repo_name(name: &str) -> bool {
+  return !name.is_empty()
+    && name != "."
+    && name != ".."
+    && !name.contains(|c| c == '/' || c == '\\')
+    && !RESERVED_CONFIG_DIR_ENTRY_NAMES.contains(&name);
+}
+
+// Location of the default (public) dependency config repository.
 pub fn gcmake_dep_config_dir() -> PathBuf {
-  gcmake_config_root_dir().join(get_repo_name())
+  gcmake_config_root_dir().join(get_repo_name(GCMAKE_DEP_CONFIG_REPO_SSH_URL))
+}
+
+pub fn dep_config_repo_list_file() -> PathBuf {
+  gcmake_config_root_dir().join(DEP_CONFIG_REPO_LIST_FILE_NAME)
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawDepConfigRepo {
+  name: Option<String>,
+  url: Option<String>,
+  path: Option<String>,
+  branch: Option<String>
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawDepConfigRepoList {
+  repositories: Vec<RawDepConfigRepo>
+}
+
+pub enum DepConfigRepoSource {
+  // Cloned into the GCMake config directory and kept up to date by 'dep-config update'.
+  // The URL can be anything git accepts, including HTTPS URLs and local paths to a mirror.
+  GitRepo {
+    url: String,
+    default_branch: Option<String>
+  },
+  // An existing directory which is used in place and never updated by GCMake.
+  LocalDir
+}
+
+pub struct DepConfigRepo {
+  pub name: String,
+  pub local_dir: PathBuf,
+  pub source: DepConfigRepoSource
+}
+
+impl DepConfigRepo {
+  fn default_repo() -> Self {
+    return Self {
+      name: get_repo_name(GCMAKE_DEP_CONFIG_REPO_SSH_URL).to_string(),
+      local_dir: gcmake_dep_config_dir(),
+      source: DepConfigRepoSource::GitRepo {
+        url: GCMAKE_DEP_CONFIG_REPO_SSH_URL.to_string(),
+        default_branch: Some(String::from("develop"))
+      }
+    }
+  }
+
+  fn from_raw(raw_repo: RawDepConfigRepo) -> Result<Self, String> {
+    return match (raw_repo.url, raw_repo.path) {
+      (Some(url), None) => {
+        let name: String = raw_repo.name.unwrap_or(get_repo_name(&url).to_string());
+
+        if !is_valid_cloned_repo_name(&name) {
+          return Err(format!(
+            "Invalid dependency config repository name '{}'. The name is used as the repository's directory name inside {}, so it can't contain path separators or be one of: {}.",
+            name.yellow(),
+            gcmake_config_root_dir().to_str().unwrap(),
+            RESERVED_CONFIG_DIR_ENTRY_NAMES.join(", ")
+          ));
+        }
+
+        Ok(Self {
+          local_dir: gcmake_config_root_dir().join(&name),
+          name,
+          source: DepConfigRepoSource::GitRepo {
+            url,
+            default_branch: raw_repo.branch
+          }
+        })
+      },
+      (None, Some(path)) => {
+        if raw_repo.branch.is_some() {
+          return Err(format!(
+            "Dependency config repository at path '{}' specifies a branch, but branches can only be given for repositories with a 'url'.",
+            path.yellow()
+          ));
+        }
+
+        let local_dir: PathBuf = gcmake_config_root_dir().join(&path);
+
+        Ok(Self {
+          name: raw_repo.name.unwrap_or(path),
+          local_dir,
+          source: DepConfigRepoSource::LocalDir
+        })
+      },
+      _ => Err(format!(
+        "Each dependency config repository must specify exactly one of '{}' or '{}'.",
+        "url".yellow(),
+        "path".yellow()
+      ))
+    }
+  }
+}
+
+// Returns the configured dependency config repositories, ordered from highest to lowest precedence.
+// When ~/.gcmake/dep-config-repos.yaml doesn't exist, only the default repository is used.
+pub fn configured_dep_config_repos() -> Result<Vec<DepConfigRepo>, String> {
+  let repo_list_file: PathBuf = dep_config_repo_list_file();
+
+  if !repo_list_file.is_file() {
+    return Ok(vec![DepConfigRepo::default_repo()]);
+  }
+
+  let repo_list_contents: String = fs::read_to_string(&repo_list_file)
+    .map_err(|err| err.to_string())?;
+
+  let raw_repo_list: RawDepConfigRepoList = serde_yaml::from_str(&repo_list_contents)
+    .map_err(|err| format!(
+      "{} loading dependency config repository list {}:\n\t{}",
+      "Error".red(),
+      repo_list_file.to_str().unwrap().yellow(),
+      err.to_string()
+    ))?;
+
+  let mut repos: Vec<DepConfigRepo> = Vec::new();
+
+  for raw_repo in raw_repo_list.repositories {
+    let repo: DepConfigRepo = DepConfigRepo::from_raw(raw_repo)?;
+
+    if repos.iter().any(|existing_repo| existing_repo.name == repo.name) {
+      return Err(format!(
+        "Dependency config repository '{}' is listed more than once in {}.",
+        repo.name.yellow(),
+        repo_list_file.to_str().unwrap()
+      ));
+    }
+
+    repos.push(repo);
+  }
+
+  if repos.is_empty() {
+    return Err(format!(
+      "{} doesn't list any dependency config repositories.",
+      repo_list_file.to_str().unwrap()
+    ));
+  }
+
+  return Ok(repos);
 }
 
 fn exited_successfully(output: &Output) -> bool {
@@ -79,16 +252,28 @@ pub enum DepConfigUpdateResult {
   SubprocessError(String),
   NewlyDownloaded {
     local_repo_location: PathBuf,
-    branch: String
+    branch: Option<String>
   },
   UpdatedBranch {
     local_repo_location: PathBuf,
     branch: Option<String>
+  },
+  // Local directory repositories are used as-is.
+  NotManaged {
+    local_repo_location: PathBuf
   }
 }
 
-pub fn update_dependency_config_repo(maybe_branch_name: &Option<String>) -> io::Result<DepConfigUpdateResult> {
-  let local_repo_location: PathBuf = gcmake_dep_config_dir();
+pub fn update_dependency_config_repo(
+  repo: &DepConfigRepo,
+  maybe_branch_name: &Option<String>
+) -> io::Result<DepConfigUpdateResult> {
+  let local_repo_location: PathBuf = repo.local_dir.clone();
+
+  let (repo_url, default_branch) = match &repo.source {
+    DepConfigRepoSource::GitRepo { url, default_branch } => (url, default_branch),
+    DepConfigRepoSource::LocalDir => return Ok(DepConfigUpdateResult::NotManaged { local_repo_location })
+  };
 
   if local_repo_location.is_dir() {
     if let Some(branch_name) = maybe_branch_name {
@@ -123,7 +308,7 @@ pub fn update_dependency_config_repo(maybe_branch_name: &Option<String>) -> io::
       .current_dir(&local_repo_location)
       .args([
         "clone",
-        GCMAKE_DEP_CONFIG_REPO_SSH_URL,
+        repo_url,
         local_repo_location.to_str().unwrap()
       ])
       .output()?;
@@ -135,15 +320,45 @@ pub fn update_dependency_config_repo(maybe_branch_name: &Option<String>) -> io::
       )));
     }
 
-    let default_branch = "develop";
+    // When no branch is given, the repository's default branch (whatever was cloned) is used.
+    let checked_out_branch: Option<String> = maybe_branch_name.clone().or(default_branch.clone());
 
-    if let Some(checkout_error_message) = checkout_branch(&local_repo_location, default_branch)? {
-      return Ok(DepConfigUpdateResult::SubprocessError(checkout_error_message));
+    if let Some(branch_name) = &checked_out_branch {
+      if let Some(checkout_error_message) = checkout_branch(&local_repo_location, branch_name)? {
+        return Ok(DepConfigUpdateResult::SubprocessError(checkout_error_message));
+      }
     }
 
     return Ok(DepConfigUpdateResult::NewlyDownloaded {
-      branch: default_branch.to_string(),
+      branch: checked_out_branch,
       local_repo_location
     });
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn repo_named(name: &str) -> Result<DepConfigRepo, String> {
+    return DepConfigRepo::from_raw(RawDepConfigRepo {
+      name: Some(name.to_string()),
+      url: Some(String::from("https://example.com/configs.git")),
+      path: None,
+      branch: None
+    });
+  }
+
+  #[test]
+  fn test_cloned_repos_cant_use_reserved_config_dir_names() {
+    for reserved_name in RESERVED_CONFIG_DIR_ENTRY_NAMES {
+      assert!(repo_named(reserved_name).is_err(), "'{}' should be reserved", reserved_name);
+    }
+
+    for invalid_name in ["", ".", "..", "nested/name", "nested\\name"] {
+      assert!(repo_named(invalid_name).is_err(), "'{}' should be rejected", invalid_name);
+    }
+
+    assert_eq!(repo_named("company-configs").unwrap().local_dir, gcmake_config_root_dir().join("company-configs"));
+  }
+}
@@ -0,0 +1,346 @@
+use std::{cell::RefCell, collections::BTreeMap, env, fs, path::{Path, PathBuf}, process::{self, Output}, rc::Rc};
+
+use crate::{gcmake_error::GCMakeError, project_info::{dependency_graph_mod::dependency_graph::DependencyGraph, dependency_lock::{DependencyLockfile, LockedDependency, LockedGitRevision, LockedUrlArchive, revision_string}, final_dependencies::{FinalDownloadMethod, FinalGitRepoDescriptor, FinalPredefinedDependencyConfig, FinalPredepInfo, FinalUrlDownloadDescriptor, GitRevisionSpecifier}, final_project_data::FinalProjectData}};
+
+// Downloads an archive and writes its SHA-256 hash to HASH_FILE. CMake is used so that locking
+// URL mode dependencies doesn't need anything a GCMake project can't already build with.
+const URL_HASH_SCRIPT: &'static str = r#"
+file( DOWNLOAD "${URL}" "${ARCHIVE_FILE}" STATUS download_status )
+list( GET download_status 0 status_code )
+if( NOT status_code EQUAL 0 )
+  list( GET download_status 1 status_message )
+  message( FATAL_ERROR "Failed to download ${URL}: ${status_message}" )
+endif()
+file( SHA256 "${ARCHIVE_FILE}" archive_hash )
+file( WRITE "${HASH_FILE}" "${archive_hash}" )
+"#;
+
+pub enum LockedEntryStatus {
+  Unchanged,
+  Added,
+  Updated
+}
+
+pub struct LockResult {
+  pub lockfile: DependencyLockfile,
+  // Every dependency in the new lockfile along with how its entry changed.
+  pub entry_statuses: BTreeMap<String, LockedEntryStatus>
+}
+
+fn command_failure_message(command_run: &str, output: &Output) -> String {
+  return format!(
+    "'{}' failed:\n{}",
+    command_run,
+    String::from_utf8_lossy(&output.stderr).trim()
+  );
+}
+
+// Resolves a tag or branch name to the commit it currently points to, without cloning the repository.
+// Annotated tags are peeled so the commit is recorded instead of the tag object.
+pub fn resolve_git_commit(git_info: &FinalGitRepoDescriptor) -> Result<String, GCMakeError> {
+  let revision: &str = match &git_info.revision_specifier {
+    GitRevisionSpecifier::CommitHash(commit_hash) => return Ok(commit_hash.clone()),
+    GitRevisionSpecifier::Tag(revision) => revision
+  };
+
+  let candidate_refs: Vec<String> = vec![
+    format!("refs/tags/{}^{{}}", revision),
+    format!("refs/tags/{}", revision),
+    format!("refs/heads/{}", revision)
+  ];
+
+  let ls_remote_output: Output = process::Command::new("git")
+    .arg("ls-remote")
+    .arg(&git_info.repo_url)
+    .args(&candidate_refs)
+    .output()
+    .map_err(|err| GCMakeError::Subprocess(format!("Failed to run git: {}", err.to_string())))?;
+
+  if !ls_remote_output.status.success() {
+    return Err(GCMakeError::Subprocess(command_failure_message(
+      &format!("git ls-remote {}", git_info.repo_url),
+      &ls_remote_output
+    )));
+  }
+
+  let remote_refs: BTreeMap<String, String> = String::from_utf8_lossy(&ls_remote_output.stdout)
+    .lines()
+    .filter_map(|line| line.split_once('\t'))
+    .map(|(commit_hash, ref_name)| (ref_name.to_string(), commit_hash.to_string()))
+    .collect();
+
+  return candidate_refs.iter()
+    .find_map(|ref_name| remote_refs.get(ref_name).cloned())
+    .ok_or(GCMakeError::Config(format!(
+      "Revision '{}' doesn't exist in repository '{}'.",
+      revision,
+      git_info.repo_url
+    )));
+}
+
+fn download_and_hash(url: &str, work_dir: &Path) -> Result<String, GCMakeError> {
+  let script_file: PathBuf = work_dir.join("hash_archive.cmake");
+  let hash_file: PathBuf = work_dir.join("archive_hash.txt");
+
+  fs::create_dir_all(work_dir)
+    .and_then(|_| fs::write(&script_file, URL_HASH_SCRIPT))
+    .map_err(|err| GCMakeError::Io(format!("Failed to write the archive hashing script: {}", err.to_string())))?;
+
+  let cmake_output: Output = process::Command::new("cmake")
+    .arg(format!("-DURL={}", url))
+    .arg(format!("-DARCHIVE_FILE={}", work_dir.join("archive").to_str().unwrap()))
+    .arg(format!("-DHASH_FILE={}", hash_file.to_str().unwrap()))
+    .arg("-P")
+    .arg(&script_file)
+    .output()
+    .map_err(|err| GCMakeError::Subprocess(format!("Failed to run cmake, which is needed to hash URL mode dependencies: {}", err.to_string())))?;
+
+  if !cmake_output.status.success() {
+    return Err(GCMakeError::Subprocess(command_failure_message(&format!("cmake -P {}", script_file.to_str().unwrap()), &cmake_output)));
+  }
+
+  return fs::read_to_string(&hash_file)
+    .map(|hash| hash.trim().to_string())
+    .map_err(|err| GCMakeError::Io(format!("Failed to read the archive hash for {}: {}", url, err.to_string())));
+}
+
+pub fn archive_sha256(url: &str) -> Result<String, GCMakeError> {
+  let work_dir: PathBuf = env::temp_dir().join(format!("gcmake-lock-{}", process::id()));
+  let hash_result: Result<String, GCMakeError> = download_and_hash(url, &work_dir);
+
+  // The downloaded archive is only needed for hashing.
+  let _ = fs::remove_dir_all(&work_dir);
+  return hash_result;
+}
+
+fn lock_git_dependency(git_info: &FinalGitRepoDescriptor) -> Result<LockedDependency, GCMakeError> {
+  return Ok(LockedDependency::Git(LockedGitRevision {
+    repo_url: git_info.repo_url.clone(),
+    revision: revision_string(&git_info.revision_specifier).to_string(),
+    commit_hash: resolve_git_commit(git_info)?
+  }));
+}
+
+fn lock_url_dependency(url_info: &FinalUrlDownloadDescriptor) -> Result<LockedDependency, GCMakeError> {
+  let windows_url: String = url_info.windows_url();
+  let unix_url: String = url_info.unix_url();
+  let windows_sha256: String = archive_sha256(&windows_url)?;
+
+  let unix_sha256: String = if unix_url == windows_url
+    { windows_sha256.clone() }
+    else { archive_sha256(&unix_url)? };
+
+  return Ok(LockedDependency::Url(LockedUrlArchive {
+    windows_url,
+    windows_sha256,
+    unix_url,
+    unix_sha256
+  }));
+}
+
+enum LockableDownload<'a> {
+  Git(&'a FinalGitRepoDescriptor),
+  Url(&'a FinalUrlDownloadDescriptor)
+}
+
+impl<'a> LockableDownload<'a> {
+  fn is_locked_by(&self, locked: &LockedDependency) -> bool {
+    return match self {
+      Self::Git(git_info) => locked.locked_commit_for(git_info).is_some(),
+      Self::Url(url_info) => locked.locked_archive_for(url_info).is_some()
+    }
+  }
+
+  fn lock(&self) -> Result<LockedDependency, GCMakeError> {
+    return match self {
+      Self::Git(git_info) => lock_git_dependency(git_info),
+      Self::Url(url_info) => lock_url_dependency(url_info)
+    }
+  }
+}
+
+fn lock_section<'a>(
+  downloads: Vec<(String, LockableDownload<'a>)>,
+  old_section: Option<&BTreeMap<String, LockedDependency>>,
+  should_refresh: &dyn Fn(&str) -> bool,
+  entry_statuses: &mut BTreeMap<String, LockedEntryStatus>
+) -> Result<BTreeMap<String, LockedDependency>, GCMakeError> {
+  let mut new_section: BTreeMap<String, LockedDependency> = BTreeMap::new();
+
+  for (dep_name, download) in downloads {
+    let maybe_old_entry: Option<&LockedDependency> = old_section.and_then(|section| section.get(&dep_name));
+
+    let (new_entry, status) = match maybe_old_entry {
+      Some(old_entry) if download.is_locked_by(old_entry) && !should_refresh(&dep_name) => {
+        (old_entry.clone(), LockedEntryStatus::Unchanged)
+      },
+      _ => {
+        let new_entry: LockedDependency = download.lock()
+          .map_err(|err| err.map_message(|err_msg| format!("Failed to lock dependency '{}': {}", dep_name, err_msg)))?;
+
+        let status: LockedEntryStatus = match maybe_old_entry {
+          None => LockedEntryStatus::Added,
+          Some(old_entry) if *old_entry == new_entry => LockedEntryStatus::Unchanged,
+          Some(_) => LockedEntryStatus::Updated
+        };
+
+        (new_entry, status)
+      }
+    };
+
+    entry_statuses.insert(dep_name.clone(), status);
+    new_section.insert(dep_name, new_entry);
+  }
+
+  return Ok(new_section);
+}
+
+// Builds the lockfile for the root project's dependencies. Existing entries are kept as long as
+// they still match the dependency configuration, unless their dependency is being refreshed.
+// Entries for dependencies which were removed from the project are dropped.
+pub fn lock_dependencies(
+  root_project: &FinalProjectData,
+  root_graph: &Rc<RefCell<DependencyGraph>>,
+  refresh_deps: Option<&Vec<String>>
+) -> Result<LockResult, GCMakeError> {
+  let predefined_deps: Vec<(String, Rc<FinalPredefinedDependencyConfig>)> = root_graph.as_ref().borrow()
+    .get_predefined_dependencies()
+    .iter()
+    .map(|(dep_name, predep_graph)| (
+      dep_name.clone(),
+      predep_graph.as_ref().borrow().project_wrapper().clone().unwrap_predef_dep()
+    ))
+    .collect();
+
+  if let Some(dep_names) = refresh_deps {
+    for dep_name in dep_names {
+      let is_dependency: bool = predefined_deps.iter().any(|(predep_name, _)| predep_name == dep_name)
+        || root_project.get_gcmake_dependencies().contains_key(dep_name);
+
+      if !is_dependency {
+        return Err(GCMakeError::Config(format!(
+          "'{}' isn't a dependency of project [{}].",
+          dep_name,
+          root_project.get_name_for_error_messages()
+        )));
+      }
+    }
+  }
+
+  let should_refresh = |dep_name: &str| match refresh_deps {
+    None => false,
+    Some(dep_names) => dep_names.is_empty() || dep_names.iter().any(|refreshed_name| refreshed_name == dep_name)
+  };
+
+  // Only subdirectory dependencies are downloaded. CMake module dependencies are found on the system.
+  let predefined_downloads: Vec<(String, LockableDownload)> = predefined_deps.iter()
+    .filter_map(|(dep_name, dep_info)| match dep_info.predefined_dep_info() {
+      FinalPredepInfo::Subdirectory(subdir_dep) => Some((
+        dep_name.clone(),
+        match subdir_dep.download_method() {
+          FinalDownloadMethod::GitMode(git_info) => LockableDownload::Git(git_info),
+          FinalDownloadMethod::UrlMode(url_info) => LockableDownload::Url(url_info)
+        }
+      )),
+      _ => None
+    })
+    .collect();
+
+  // Local path dependencies are used as they are checked out, so there's no revision to pin.
+  let gcmake_downloads: Vec<(String, LockableDownload)> = root_project.get_gcmake_dependencies().iter()
+    .filter_map(|(dep_name, gcmake_dep)| gcmake_dep.git_repo()
+      .map(|git_repo| (dep_name.clone(), LockableDownload::Git(git_repo)))
+    )
+    .collect();
+
+  let old_lockfile: Option<&DependencyLockfile> = root_project.get_dependency_lock();
+  let mut entry_statuses: BTreeMap<String, LockedEntryStatus> = BTreeMap::new();
+
+  let lockfile = DependencyLockfile {
+    predefined_dependencies: lock_section(
+      predefined_downloads,
+      old_lockfile.map(|lockfile| &lockfile.predefined_dependencies),
+      &should_refresh,
+      &mut entry_statuses
+    )?,
+    gcmake_dependencies: lock_section(
+      gcmake_downloads,
+      old_lockfile.map(|lockfile| &lockfile.gcmake_dependencies),
+      &should_refresh,
+      &mut entry_statuses
+    )?
+  };
+
+  return Ok(LockResult {
+    lockfile,
+    entry_statuses
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{env, fs, path::{Path, PathBuf}, process};
+
+  use crate::{gcmake_error::GCMakeError, project_info::final_dependencies::{FinalGitRepoDescriptor, GitRevisionSpecifier}};
+
+  use super::resolve_git_commit;
+
+  fn git(repo_dir: &Path, args: &[&str]) -> String {
+    let output = process::Command::new("git")
+      .args(["-c", "user.name=gcmake", "-c", "user.email=gcmake@example.com", "-c", "commit.gpgsign=false", "-c", "tag.gpgsign=false"])
+      .args(args)
+      .current_dir(repo_dir)
+      .output()
+      .unwrap();
+
+    assert!(output.status.success(), "git {:?} failed: {}", args, String::from_utf8_lossy(&output.stderr));
+    return String::from_utf8_lossy(&output.stdout).trim().to_string();
+  }
+
+  fn commit_file(repo_dir: &Path, contents: &str) -> String {
+    fs::write(repo_dir.join("file.txt"), contents).unwrap();
+    git(repo_dir, &["add", "file.txt"]);
+    git(repo_dir, &["commit", "-q", "-m", contents]);
+    return git(repo_dir, &["rev-parse", "HEAD"]);
+  }
+
+  fn resolve_tag(repo_url: &str, revision: &str) -> Result<String, GCMakeError> {
+    return resolve_git_commit(&FinalGitRepoDescriptor {
+      repo_url: repo_url.to_string(),
+      revision_specifier: GitRevisionSpecifier::Tag(revision.to_string())
+    });
+  }
+
+  #[test]
+  fn test_resolves_branches_and_tags_to_commits() {
+    let repo_dir: PathBuf = env::temp_dir().join(format!("gcmake-resolve-{}", process::id()));
+    let _ = fs::remove_dir_all(&repo_dir);
+    fs::create_dir_all(&repo_dir).unwrap();
+
+    git(&repo_dir, &["init", "-q"]);
+    let lightweight_commit: String = commit_file(&repo_dir, "first");
+    git(&repo_dir, &["tag", "v1.0.0"]);
+
+    let annotated_commit: String = commit_file(&repo_dir, "second");
+    git(&repo_dir, &["tag", "-a", "v2.0.0", "-m", "Release 2.0.0"]);
+    let annotated_tag_object: String = git(&repo_dir, &["rev-parse", "v2.0.0"]);
+
+    git(&repo_dir, &["checkout", "-q", "-b", "feature"]);
+    let branch_commit: String = commit_file(&repo_dir, "third");
+
+    let repo_url: String = format!("file://{}", repo_dir.to_str().unwrap());
+
+    let lightweight_result = resolve_tag(&repo_url, "v1.0.0");
+    let annotated_result = resolve_tag(&repo_url, "v2.0.0");
+    let branch_result = resolve_tag(&repo_url, "feature");
+    let missing_result = resolve_tag(&repo_url, "v3.0.0");
+    let _ = fs::remove_dir_all(&repo_dir);
+
+    assert_eq!(lightweight_result.unwrap(), lightweight_commit);
+    // Annotated tags must resolve to the commit (the peeled ^{} ref), not the tag object.
+    assert_ne!(annotated_tag_object, annotated_commit);
+    assert_eq!(annotated_result.unwrap(), annotated_commit);
+    assert_eq!(branch_result.unwrap(), branch_commit);
+    assert!(missing_result.is_err());
+  }
+}
@@ -1,17 +1,26 @@
 mod create_project;
 mod code_file_creator;
+mod code_file_mover;
 mod manage_dependencies;
+mod manage_dep_cache;
+mod manage_dependency_lock;
+mod project_config_editor;
 mod info_printers;
 mod default_file_creator;
 
 pub use create_project::*;
 pub use code_file_creator::*;
+pub use code_file_mover::*;
 pub use manage_dependencies::*;
-use std::{io, path::PathBuf, fs, cell::RefCell, rc::Rc, collections::BTreeMap, iter::FromIterator};
+pub use manage_dep_cache::*;
+pub use manage_dependency_lock::*;
+pub use project_config_editor::*;
+use std::{io, path::{Path, PathBuf}, fs, cell::RefCell, rc::Rc, collections::BTreeMap, iter::FromIterator};
 
-use crate::{cli_config::{clap_cli_config::{UseFilesCommand, CreateFilesCommand, UpdateDependencyConfigsCommand, TargetInfoCommand, ProjectInfoCommand, PredepInfoCommand, ToolInfoCommand, CreateDefaultFilesCommand, CreateDefaultFileOption, SpecificToolPartSubcommand}, CLIProjectGenerationInfo, CLIProjectTypeGenerating}, common::{prompt::prompt_until_boolean}, logger::exit_error_log, project_info::{dep_graph_loader::load_graph, dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper, DependencyGraph, TargetNode, BasicTargetSearchResult, DependencyGraphWarningMode, BasicProjectSearchResult}, feature_map_for_lang, final_project_data::{UseableFinalProjectDataGroup, ProjectLoadFailureReason, FinalProjectData, FinalProjectLoadContext}, path_manipulation::absolute_path, raw_data_in::dependencies::RawPredefinedDependencyMap, validators::{is_valid_target_name, is_valid_project_name}, LinkSpecifier, SystemSpecFeatureType}, file_writers::write_configurations, project_generator::GeneralNewProjectInfo, program_actions::info_printers::{target_info_print_funcs::{print_target_header, print_export_header_include_path, print_target_type}, project_info_print_funcs::{print_project_header, print_project_include_prefix, print_immediate_subprojects, print_project_repo_url, print_project_can_cross_compile, print_project_supports_emscripten, print_project_output_list, print_project_dependencies}}};
+use crate::{api, gcmake_error::GCMakeError, cli_config::{clap_cli_config::{Opts, UseFilesCommand, CreateFilesCommand, MoveFilesCommand, LockDependenciesCommand, UpdateDependencyConfigsCommand, CheckDependencyConfigsCommand, AddPredefinedDependencyCommand, AddLinkCommand, RemoveLinkCommand, ListDepCacheCommand, CleanDepCacheCommand, PruneDepCacheCommand, TargetInfoCommand, DepGraphCommand, DepGraphFormat, DumpModelCommand, ModelDumpFormat, ShowBuildConfigCommand, CLIBuildType, CLICompilerSpecifier, ProjectInfoCommand, PredepInfoCommand, ToolInfoCommand, SchemaCommand, SchemaProjectType, CreateDefaultFilesCommand, CreateDefaultFileOption, SpecificToolPartSubcommand}, CLIProjectGenerationInfo, CLIProjectTypeGenerating}, common::{prompt::prompt_until_boolean}, logger::{self, exit_error_log, exit_with_error}, project_info::{dep_graph_loader::load_graph, final_dependencies::validate_predefined_dep_config, dependency_graph_mod::dependency_graph::{ProjectWrapper, DependencyGraphInfoWrapper, DependencyGraph, TargetNode, BasicTargetSearchResult, DependencyGraphWarningMode, BasicProjectSearchResult}, feature_map_for_lang, final_project_data::{UseableFinalProjectDataGroup, ProjectLoadFailureReason, FinalProjectData, FinalProjectLoadContext, root_project_dir_of}, path_manipulation::absolute_path, gcmake_constants::{DEPENDENCY_LOCK_FILE_NAME, PROJECT_LOCAL_DEP_CONFIGS_DIR}, raw_data_in::{dependencies::RawPredefinedDependencyMap, BuildType, SpecificCompilerSpecifier}, validators::{is_valid_target_name, is_valid_project_name}, LinkSpecifier, SystemSpecFeatureType}, file_writers::{write_configurations, GeneratedFileSet, StaleGeneratedFile, export_dep_graph, DepGraphExportFormat, DepGraphExportOptions, export_model_json, CMakeDataSchemaKind}, project_generator::GeneralNewProjectInfo, program_actions::info_printers::{build_config_print_funcs::{EffectiveBuildConfig, effective_build_config, target_compiled_output, print_effective_config_header, print_effective_items}, dep_graph_print_funcs::{print_target_dep_tree, all_project_targets}, target_info_print_funcs::{print_target_header, print_export_header_include_path, print_target_type}, project_info_print_funcs::{print_project_header, print_project_include_prefix, print_immediate_subprojects, print_project_repo_url, print_project_can_cross_compile, print_project_supports_emscripten, print_project_output_list, print_project_dependencies}}};
 
-use self::{info_printers::predef_dep_info_print_funcs::{print_predef_dep_header, print_predep_targets, print_predep_repo_url, print_predep_github_url, print_predep_can_cross_compile, print_predep_supports_emscripten, print_predep_supported_download_methods, print_predep_doc_link}, default_file_creator::{write_default_doxyfile, write_default_sphinx_files}};
+use self::{info_printers::predef_dep_info_print_funcs::{print_predef_dep_header, print_predep_targets, print_predep_repo_url, print_predep_github_url, print_predep_can_cross_compile, print_predep_supports_emscripten, print_predep_supported_download_methods, print_predep_doc_link, print_predep_config_dir}, default_file_creator::{write_default_doxyfile, write_default_sphinx_files}};
+use clap::{CommandFactory, error::ErrorKind};
 use colored::*;
 
 fn parse_project_info(
@@ -41,7 +50,7 @@ fn get_project_info_or_exit(
 ) -> UseableFinalProjectDataGroup {
   match parse_project_info(project_root_dir, dep_config, project_load_context) {
     Ok(project_group) => project_group,
-    Err(failure_reason) => exit_error_log(failure_reason.extract_message())
+    Err(failure_reason) => exit_with_error(failure_reason.into())
   }
 }
 
@@ -55,7 +64,7 @@ fn get_project_graph_or_exit<'a>(
   project_group: &'a UseableFinalProjectDataGroup,
   warning_mode: DependencyGraphWarningMode
 ) -> RootAndOperatingGraphs<'a> {
-  match load_graph(&project_group, warning_mode) {
+  match api::load_dependency_graph(&project_group, warning_mode) {
     Ok(graph_info) => {
       return RootAndOperatingGraphs {
         operating_on: project_group.operating_on
@@ -71,7 +80,7 @@ fn get_project_graph_or_exit<'a>(
         graph_info_wrapper: graph_info
       }
     },
-    Err(err_msg) => exit_error_log(err_msg)
+    Err(err) => exit_with_error(err)
   }
 }
 
@@ -119,7 +128,7 @@ pub fn print_target_info(
       }
     })
     .collect::<Result<_, String>>()
-    .unwrap_or_else(|err_msg| exit_error_log(err_msg));
+    .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg)));
 
   for list_from_selector in result_list {
     for search_result in list_from_selector {
@@ -149,6 +158,286 @@ pub fn print_target_info(
   }
 }
 
+// Resolves target selectors the same way target-info does. Selectors which don't match
+// any target are reported and skipped.
+fn find_selected_targets<'a>(
+  selectors: &Vec<String>,
+  graph_info: &RootAndOperatingGraphs<'a>
+) -> Vec<Rc<RefCell<TargetNode<'a>>>> {
+  let operating_on = graph_info.operating_on.as_ref().unwrap();
+
+  let result_list: Vec<Vec<BasicTargetSearchResult>> = selectors
+    .iter()
+    .map(|selector| {
+      if is_valid_target_name(selector) {
+        let search_result = graph_info.project_root_graph
+          .as_ref().borrow().find_targets_using_name_list(&vec![selector]);
+        Ok(search_result)
+      }
+      else {
+        let search_result = operating_on.as_ref().borrow().find_targets_using_link_spec(
+          false,
+          &LinkSpecifier::parse_with_full_permissions(selector, None)?
+        )?;
+
+        Ok(search_result)
+      }
+    })
+    .collect::<Result<_, String>>()
+    .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg)));
+
+  let mut found_targets: Vec<Rc<RefCell<TargetNode>>> = Vec::new();
+
+  for list_from_selector in result_list {
+    for search_result in list_from_selector {
+      match search_result.target {
+        None => {
+          println!(
+            "\nUnable to find '{}' in project [{}]",
+            &search_result.searched_with,
+            search_result.searched_project.as_ref().borrow().project_debug_name()
+          );
+        },
+        Some(target_rc) => found_targets.push(target_rc)
+      }
+    }
+  }
+
+  return found_targets;
+}
+
+pub fn print_dep_graph(
+  command: &DepGraphCommand,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  // Trees are printed with colors for the terminal, so only the export formats can be written to a file.
+  if let (DepGraphFormat::Tree, Some(_)) = (&command.format, &command.output_file) {
+    Opts::command()
+      .error(
+        ErrorKind::ArgumentConflict,
+        "'--output' can only be used with the 'dot' and 'mermaid' formats. Pass '--format dot' or '--format mermaid' to export the graph."
+      )
+      .exit();
+  }
+
+  let project_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  );
+
+  let graph_info: RootAndOperatingGraphs = get_project_graph_or_exit(&project_group, DependencyGraphWarningMode::Off);
+
+  assert!(
+    graph_info.operating_on.is_some(),
+    "When printing dependency graphs, there should always be an 'operating on' context project."
+  );
+
+  let operating_on = graph_info.operating_on.as_ref().unwrap();
+
+  let selected_targets: Vec<Rc<RefCell<TargetNode>>> = if command.selectors.is_empty()
+    { all_project_targets(operating_on) }
+    else { find_selected_targets(&command.selectors, &graph_info) };
+
+  let export_format: DepGraphExportFormat = match command.format {
+    DepGraphFormat::Tree => {
+      for target_rc in &selected_targets {
+        print_target_dep_tree(target_rc);
+      }
+      return;
+    },
+    DepGraphFormat::Dot => DepGraphExportFormat::Dot,
+    DepGraphFormat::Mermaid => DepGraphExportFormat::Mermaid
+  };
+
+  let exported_graph: String = export_dep_graph(
+    &graph_info.graph_info_wrapper,
+    &selected_targets,
+    &DepGraphExportOptions {
+      format: export_format,
+      collapse_predefined_deps: command.collapse_predefined_deps
+    }
+  );
+
+  match &command.output_file {
+    None => print!("{}", exported_graph),
+    Some(output_file) => {
+      if let Err(err) = fs::write(output_file, exported_graph) {
+        exit_with_error(GCMakeError::Io(format!(
+          "Failed to write dependency graph to '{}': {}",
+          output_file,
+          err.to_string()
+        )));
+      }
+
+      println!("Dependency graph written to {}", output_file.green());
+    }
+  }
+}
+
+pub enum ShownBuildSettings {
+  Flags,
+  Defines
+}
+
+pub fn dump_project_model(
+  command: &DumpModelCommand,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  let project_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  );
+
+  let graph_info: RootAndOperatingGraphs = get_project_graph_or_exit(&project_group, DependencyGraphWarningMode::Off);
+
+  let dumped_model: String = match command.format {
+    ModelDumpFormat::Json => export_model_json(&graph_info.graph_info_wrapper)
+      .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Io(err_msg)))
+  };
+
+  match &command.output_file {
+    None => println!("{}", dumped_model),
+    Some(output_file) => {
+      if let Err(err) = fs::write(output_file, dumped_model) {
+        exit_with_error(GCMakeError::Io(format!(
+          "Failed to write project model to '{}': {}",
+          output_file,
+          err.to_string()
+        )));
+      }
+
+      println!("Project model written to {}", output_file.green());
+    }
+  }
+}
+
+pub fn print_cmake_data_schema(command: &SchemaCommand, given_root_dir: &str) {
+  let predefined_dep_names: Vec<String> = if command.allow_any_dep_names
+    { Vec::new() }
+    else {
+      let dep_config: RawPredefinedDependencyMap = api::load_dependency_configs()
+        .unwrap_or_else(|err| exit_with_error(err));
+
+      // Projects can use dependencies from their own .gcmake/dep-configs, so those are valid names too.
+      let project_dep_config: Option<RawPredefinedDependencyMap> = dep_config_for_project_tree(given_root_dir, &dep_config)
+        .unwrap_or_else(|err| exit_with_error(err));
+
+      let mut dep_names: Vec<String> = project_dep_config.as_ref().unwrap_or(&dep_config)
+        .available_dep_names().iter().cloned().collect();
+      dep_names.sort();
+      dep_names
+    };
+
+  let schema_kind: CMakeDataSchemaKind = match command.project_type {
+    SchemaProjectType::Root => CMakeDataSchemaKind::RootProject,
+    SchemaProjectType::Subproject => CMakeDataSchemaKind::Subproject,
+    SchemaProjectType::Test => CMakeDataSchemaKind::TestProject
+  };
+
+  let schema: String = api::cmake_data_schema(schema_kind, &predefined_dep_names)
+    .unwrap_or_else(|err| exit_with_error(err));
+
+  match &command.output_file {
+    None => println!("{}", schema),
+    Some(output_file) => {
+      if let Err(err) = fs::write(output_file, schema) {
+        exit_with_error(GCMakeError::Io(format!(
+          "Failed to write cmake_data.yaml schema to '{}': {}",
+          output_file,
+          err.to_string()
+        )));
+      }
+
+      println!("Schema written to {}", output_file.green());
+    }
+  }
+}
+
+pub fn print_effective_build_settings(
+  command: &ShowBuildConfigCommand,
+  shown_settings: ShownBuildSettings,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  let project_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  );
+
+  let graph_info: RootAndOperatingGraphs = get_project_graph_or_exit(&project_group, DependencyGraphWarningMode::Off);
+
+  assert!(
+    graph_info.operating_on.is_some(),
+    "When printing build settings, there should always be an 'operating on' context project."
+  );
+
+  let compiler: SpecificCompilerSpecifier = match command.compiler {
+    CLICompilerSpecifier::GCC => SpecificCompilerSpecifier::GCC,
+    CLICompilerSpecifier::Clang => SpecificCompilerSpecifier::Clang,
+    CLICompilerSpecifier::MSVC => SpecificCompilerSpecifier::MSVC,
+    CLICompilerSpecifier::CUDA => SpecificCompilerSpecifier::CUDA,
+    CLICompilerSpecifier::Emscripten => SpecificCompilerSpecifier::Emscripten
+  };
+
+  for target_rc in find_selected_targets(&command.selectors, &graph_info) {
+    let target: &TargetNode = &target_rc.as_ref().borrow();
+
+    // Build configurations are set by the root project and inherited by every project inside it.
+    let maybe_root_project_data: Option<Rc<FinalProjectData>> = target.container_project().as_ref().borrow()
+      .root_project().as_ref().borrow()
+      .project_wrapper()
+      .maybe_normal_project()
+      .map(Rc::clone);
+
+    let (project_data, output) = match (maybe_root_project_data, target_compiled_output(target)) {
+      (Some(project_data), Some(output)) => (project_data, output),
+      _ => {
+        println!(
+          "\n{} has no build configuration",
+          target.get_yaml_namespaced_target_name().yellow()
+        );
+        continue;
+      }
+    };
+
+    let build_type: BuildType = match command.build_type {
+      None => project_data.get_default_build_config().clone(),
+      Some(CLIBuildType::Debug) => BuildType::Debug,
+      Some(CLIBuildType::Release) => BuildType::Release,
+      Some(CLIBuildType::MinSizeRel) => BuildType::MinSizeRel,
+      Some(CLIBuildType::RelWithDebInfo) => BuildType::RelWithDebInfo
+    };
+
+    if !project_data.get_build_configs().contains_key(&build_type) {
+      exit_with_error(GCMakeError::Config(format!(
+        "Build configuration '{}' is not configured for project [{}]",
+        build_type.name_str(),
+        project_data.get_name_for_error_messages()
+      )));
+    }
+
+    let effective_config: EffectiveBuildConfig = effective_build_config(&project_data, output, &build_type, compiler);
+
+    print_effective_config_header(target, &build_type, compiler);
+
+    match shown_settings {
+      ShownBuildSettings::Flags => {
+        print_effective_items("Compiler flags", &effective_config.compiler_flags);
+        print_effective_items("Link-time flags", &effective_config.link_time_flags);
+        print_effective_items("Linker flags", &effective_config.linker_flags);
+      },
+      ShownBuildSettings::Defines => {
+        print_effective_items("Defines", &effective_config.defines);
+      }
+    }
+  }
+}
+
 pub fn print_project_info(
   command: &ProjectInfoCommand,
   given_root_dir: &str,
@@ -193,7 +482,7 @@ pub fn print_project_info(
       }
     })
     .collect::<Result<_, String>>()
-    .unwrap_or_else(|err_msg| exit_error_log(err_msg));
+    .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg)));
 
   for list_from_selector in result_list {
     for search_result in list_from_selector {
@@ -253,7 +542,14 @@ pub fn print_tool_info(command: ToolInfoCommand) {
   }
 
   if command.show_dep_config_dir {
-    println!("{}", gcmake_dep_config_dir().to_str().unwrap());
+    match configured_dep_config_repos() {
+      Ok(repos) => {
+        for repo in repos {
+          println!("{}", repo.local_dir.to_str().unwrap());
+        }
+      },
+      Err(err_msg) => exit_with_error(GCMakeError::Config(err_msg))
+    }
   }
 
   if let Some(command_part) = command.part {
@@ -280,6 +576,22 @@ pub fn print_tool_info(command: ToolInfoCommand) {
   }
 }
 
+// Project-local dependency configs are used by the whole project tree, so commands which only
+// read dependency configs need to see them too when run from inside a project. Returns None when
+// the given directory isn't in a project, or the project has no local configs.
+pub fn dep_config_for_project_tree(
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) -> Result<Option<RawPredefinedDependencyMap>, GCMakeError> {
+  let root_project_dir: PathBuf = match root_project_dir_of(given_root_dir)? {
+    Some(root_dir) => root_dir,
+    None => return Ok(None)
+  };
+
+  return dep_config.with_project_local_configs(&root_project_dir.join(PROJECT_LOCAL_DEP_CONFIGS_DIR))
+    .map_err(GCMakeError::Config);
+}
+
 pub fn print_predep_info(
   command: &PredepInfoCommand,
   dep_config: &RawPredefinedDependencyMap
@@ -335,6 +647,10 @@ pub fn print_predep_info(
             if command.show_supports_emscripten {
               print_predep_supports_emscripten(common_info);
             }
+
+            if command.show_config_dir {
+              print_predep_config_dir(dep_name, dep_config.config_root_dir_for(dep_name).unwrap());
+            }
           }
         }
       }
@@ -455,10 +771,21 @@ pub fn generate_default_file(
   Ok(())
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ConfigGenerationMode {
+  Write,
+  // Print a diff of the changes instead of writing files.
+  DryRun,
+  // Only report which generated files are out of date.
+  CheckUpToDate
+}
+
+// Returns false when generating in CheckUpToDate mode and at least one generated file is stale.
 pub fn do_generate_project_configs(
   given_root_dir: &str,
-  dep_config: &RawPredefinedDependencyMap
-) {
+  dep_config: &RawPredefinedDependencyMap,
+  generation_mode: ConfigGenerationMode
+) -> bool {
   let project_data_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
     &given_root_dir,
     &dep_config,
@@ -470,6 +797,13 @@ pub fn do_generate_project_configs(
     DependencyGraphWarningMode::All
   );
 
+  // Don't write anything for a project whose configuration produced denied warnings.
+  logger::exit_if_warnings_denied();
+
+  if generation_mode != ConfigGenerationMode::Write {
+    return compare_generated_configs(&graph_info_wrapper, generation_mode);
+  }
+
   let config_write_result: io::Result<()> = write_configurations(
     &graph_info_wrapper,
     |config_name| println!("\nBeginning {} configuration step...", config_name.green()),
@@ -489,9 +823,311 @@ pub fn do_generate_project_configs(
   ); 
   
   if let Err(err) = config_write_result {
-    exit_error_log(err.to_string());
+    exit_with_error(err.into());
   }
   // print_project_info(project_data_group);
+  return true;
+}
+
+fn compare_generated_configs<'a>(
+  graph_info_wrapper: &'a DependencyGraphInfoWrapper<'a>,
+  generation_mode: ConfigGenerationMode
+) -> bool {
+  let generated_files: GeneratedFileSet = api::render_project_configs(graph_info_wrapper)
+    .unwrap_or_else(|err| exit_with_error(err));
+
+  let stale_files: Vec<StaleGeneratedFile> = generated_files.stale_files()
+    .unwrap_or_else(|err| exit_with_error(err.into()));
+
+  if stale_files.is_empty() {
+    println!("{}", "All generated files are up to date.".green());
+    return true;
+  }
+
+  match generation_mode {
+    ConfigGenerationMode::DryRun => {
+      for stale_file in &stale_files {
+        print!("{}", stale_file.unified_diff());
+      }
+
+      println!(
+        "\n{} generated file(s) would be changed.",
+        stale_files.len()
+      );
+      return true;
+    },
+    _ => {
+      println!("{}", "The following generated files are out of date:".red());
+
+      for stale_file in &stale_files {
+        println!(
+          "  {}{}",
+          stale_file.file_path.to_str().unwrap(),
+          if stale_file.is_new_file() { " (missing)" } else { "" }
+        );
+      }
+
+      println!("\nRun gcmake to regenerate them.");
+      return false;
+    }
+  }
+}
+
+// Runs the same project loading and dependency graph resolution steps as generation,
+// but never writes anything. Fails if the project tree is invalid or denied warnings were emitted.
+// Every project in the tree is loaded even when some of them are invalid, so all their problems
+// are reported in one run. The dependency graph can only be checked once the whole tree loads.
+pub fn do_check_project(
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) -> Result<(), GCMakeError> {
+  let project_data_group: UseableFinalProjectDataGroup = match parse_project_info(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  ) {
+    Ok(project_group) => project_group,
+    Err(failure_reason) => {
+      let num_failures: usize = failure_reason.num_failures();
+
+      return Err(GCMakeError::from(failure_reason).map_message(|err_msg| format!(
+        "{}\n\n{} Found {} problem(s) in the project tree.",
+        err_msg,
+        "Error:".red(),
+        num_failures
+      )));
+    }
+  };
+
+  api::load_dependency_graph(&project_data_group, DependencyGraphWarningMode::All)?;
+
+  logger::check_warnings_denied()?;
+
+  println!(
+    "{} No problems found in project '{}'.",
+    "Success:".green(),
+    project_data_group.root_project.get_absolute_project_root().to_str().unwrap()
+  );
+
+  return Ok(());
+}
+
+pub fn do_add_dep_subcommand(
+  command: &AddPredefinedDependencyCommand,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  let project_data_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  );
+
+  let config_edit: ProjectConfigEdit = add_predefined_dependency(command, &project_data_group.root_project, dep_config)
+    .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg)));
+
+  if let Err(err) = revert_edit_if_project_invalid(&config_edit, given_root_dir, dep_config) {
+    exit_with_error(err);
+  }
+
+  println!(
+    "Added predefined dependency '{}' to {}",
+    command.dep_name.green(),
+    config_edit.config_file_path.to_str().unwrap()
+  );
+}
+
+struct EditableOutput<'a> {
+  project: Rc<FinalProjectData>,
+  output_name: String,
+  project_graph: Rc<RefCell<DependencyGraph<'a>>>
+}
+
+// Link edits can only be made to outputs defined in the project tree, since those are the only
+// ones with a cmake_data.yaml we own.
+fn select_editable_output<'a>(
+  selector: &str,
+  graph_info: &RootAndOperatingGraphs<'a>
+) -> Result<EditableOutput<'a>, String> {
+  let found_targets = find_selected_targets(&vec![selector.to_string()], graph_info);
+
+  let target_rc = match found_targets.as_slice() {
+    [target_rc] => target_rc,
+    [] => return Err(format!("'{}' does not select any targets.", selector.yellow())),
+    _ => return Err(format!(
+      "'{}' selects {} targets, but must select exactly one output.",
+      selector.yellow(),
+      found_targets.len()
+    ))
+  };
+
+  let target = target_rc.as_ref().borrow();
+
+  if target.maybe_regular_output().is_none() {
+    return Err(format!(
+      "'{}' is not a project output. Links can only be edited for outputs listed in a project's 'output' section.",
+      target.get_yaml_namespaced_target_name()
+    ));
+  }
+
+  let project_graph = target.container_project();
+  let is_in_project_tree: bool = project_graph.as_ref().borrow().root_project_id()
+    == graph_info.project_root_graph.as_ref().borrow().project_id();
+
+  let project: Rc<FinalProjectData> = match project_graph.as_ref().borrow().project_wrapper() {
+    ProjectWrapper::NormalProject(project_info) if is_in_project_tree => Rc::clone(project_info),
+    _ => return Err(format!(
+      "'{}' is part of a dependency. Links can only be edited for outputs in the current project tree.",
+      target.get_yaml_namespaced_target_name()
+    ))
+  };
+
+  return Ok(EditableOutput {
+    project,
+    output_name: target.get_name().to_string(),
+    project_graph
+  });
+}
+
+// Makes sure every target named by the link specifiers can be found from the output's project.
+fn validate_link_specs(
+  link_specs: &[String],
+  root_project: &FinalProjectData,
+  linking_project: &Rc<RefCell<DependencyGraph>>
+) -> Result<(), String> {
+  let feature_list: Vec<&str> = root_project.get_features().keys()
+    .map(|feature_name| feature_name.as_str())
+    .collect();
+
+  for link_spec_str in link_specs {
+    let link_spec: LinkSpecifier = LinkSpecifier::parse_with_full_permissions(
+      link_spec_str,
+      if feature_list.is_empty() { None } else { Some(&feature_list) }
+    )
+      .map_err(|err_msg| format!("Invalid link '{}':\n{}", link_spec_str.yellow(), err_msg))?;
+
+    // Dependency namespaces are only registered in the root project's graph.
+    let first_namespace: &str = link_spec.get_namespace_queue()[0].as_str();
+    let is_dependency_namespace: bool = root_project.get_predefined_dependencies().contains_key(first_namespace)
+      || root_project.get_gcmake_dependencies().contains_key(first_namespace);
+
+    let searching_project = if is_dependency_namespace
+      { linking_project.as_ref().borrow().root_project() }
+      else { Rc::clone(linking_project) };
+
+    let search_results: Vec<BasicTargetSearchResult> = searching_project.as_ref().borrow()
+      .find_targets_using_link_spec(false, &link_spec)
+      .map_err(|err_msg| format!("Invalid link '{}':\n{}", link_spec_str.yellow(), err_msg))?;
+
+    for search_result in search_results {
+      if search_result.target.is_none() {
+        return Err(format!(
+          "Invalid link '{}': unable to find '{}' in project [{}]",
+          link_spec_str.yellow(),
+          search_result.searched_with,
+          search_result.searched_project.as_ref().borrow().project_debug_name()
+        ));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+// Reloads the whole project tree after its cmake_data.yaml was edited. If it no longer loads,
+// the edit is undone so the project is never left in a broken state.
+fn revert_edit_if_project_invalid(
+  config_edit: &ProjectConfigEdit,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) -> Result<(), GCMakeError> {
+  let load_result: Result<(), GCMakeError> = match parse_project_info(given_root_dir, dep_config, FinalProjectLoadContext::default()) {
+    Err(failure_reason) => Err(failure_reason.into()),
+    Ok(project_data_group) => load_graph(&project_data_group, DependencyGraphWarningMode::Off)
+      .map(|_| ())
+  };
+
+  if let Err(err) = load_result {
+    config_edit.revert()
+      .map_err(GCMakeError::Io)?;
+
+    return Err(err.map_message(|err_msg| format!(
+      "The project is invalid after editing {}, so the edit was undone:\n{}",
+      config_edit.config_file_path.to_str().unwrap(),
+      err_msg
+    )));
+  }
+
+  Ok(())
+}
+
+pub fn do_add_link_subcommand(
+  command: &AddLinkCommand,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  let project_data_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  );
+
+  let config_edit: ProjectConfigEdit = {
+    let graph_info: RootAndOperatingGraphs = get_project_graph_or_exit(&project_data_group, DependencyGraphWarningMode::Off);
+
+    let output: EditableOutput = select_editable_output(&command.target, &graph_info)
+      .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg)));
+
+    validate_link_specs(&command.link_specs, &project_data_group.root_project, &output.project_graph)
+      .and_then(|_| add_output_links(&output.project, &output.output_name, &command.link_specs, command.is_public))
+      .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg)))
+  };
+
+  if let Err(err) = revert_edit_if_project_invalid(&config_edit, given_root_dir, dep_config) {
+    exit_with_error(err);
+  }
+
+  for link_spec in &command.link_specs {
+    println!(
+      "Added link '{}' to {}",
+      link_spec.green(),
+      config_edit.config_file_path.to_str().unwrap()
+    );
+  }
+}
+
+pub fn do_remove_link_subcommand(
+  command: &RemoveLinkCommand,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  let project_data_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  );
+
+  let config_edit: ProjectConfigEdit = {
+    let graph_info: RootAndOperatingGraphs = get_project_graph_or_exit(&project_data_group, DependencyGraphWarningMode::Off);
+
+    let output: EditableOutput = select_editable_output(&command.target, &graph_info)
+      .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg)));
+
+    remove_output_links(&output.project, &output.output_name, &command.link_specs)
+      .unwrap_or_else(|err_msg| exit_with_error(GCMakeError::Config(err_msg)))
+  };
+
+  if let Err(err) = revert_edit_if_project_invalid(&config_edit, given_root_dir, dep_config) {
+    exit_with_error(err);
+  }
+
+  for link_spec in &command.link_specs {
+    println!(
+      "Removed link '{}' from {}",
+      link_spec.red(),
+      config_edit.config_file_path.to_str().unwrap()
+    );
+  }
 }
 
 pub fn do_new_files_subcommand(
@@ -512,14 +1148,92 @@ pub fn do_new_files_subcommand(
 
   // print_project_info(project_data_group);
   if let None = project_data_group.operating_on {
-    exit_error_log("Tried to create files while not operating on a project. Make sure you are inside a project directory containing a cmake_data.yaml file.")
+    exit_with_error(GCMakeError::Config(String::from("Tried to create files while not operating on a project. Make sure you are inside a project directory containing a cmake_data.yaml file.")));
   }
 
-  match handle_create_files(&project_data_group.operating_on.unwrap(), &command) {
+  match handle_create_files(&project_data_group.root_project, &project_data_group.operating_on.unwrap(), &command) {
     Ok(_) => {
       // Nothing needs to happen here, since a creation message is printed for every file that is created.
     },
-    Err(error_message) => exit_error_log(&error_message)
+    Err(err) => exit_with_error(err)
+  }
+}
+
+pub fn do_move_files_subcommand(
+  command: &MoveFilesCommand,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  let project_data_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  );
+
+  let graph_info: RootAndOperatingGraphs = get_project_graph_or_exit(&project_data_group, DependencyGraphWarningMode::Off);
+
+  let (moved_files, updated_files) = move_code_file_set(
+    &project_data_group.root_project,
+    &graph_info.project_root_graph,
+    &command.from,
+    &command.to
+  )
+    .unwrap_or_else(|err| exit_with_error(err));
+
+  let root_dir: &Path = project_data_group.root_project.get_absolute_project_root();
+
+  for (old_path, new_path) in moved_files {
+    println!(
+      "Moved: {} -> {}",
+      old_path.strip_prefix(root_dir).unwrap().to_str().unwrap(),
+      new_path.strip_prefix(root_dir).unwrap().to_str().unwrap().cyan()
+    );
+  }
+
+  for file_path in updated_files {
+    println!(
+      "Updated includes in: {}",
+      file_path.strip_prefix(root_dir).unwrap().to_str().unwrap().cyan()
+    );
+  }
+}
+
+pub fn do_lock_subcommand(
+  command: &LockDependenciesCommand,
+  given_root_dir: &str,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  let project_data_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+    given_root_dir,
+    dep_config,
+    FinalProjectLoadContext::default()
+  );
+
+  let graph_info: RootAndOperatingGraphs = get_project_graph_or_exit(&project_data_group, DependencyGraphWarningMode::Off);
+  let root_project: &FinalProjectData = &project_data_group.root_project;
+
+  let lock_result: LockResult = lock_dependencies(
+    root_project,
+    &graph_info.project_root_graph,
+    if command.update { Some(&command.dep_names) } else { None }
+  )
+    .unwrap_or_else(|err| exit_with_error(err));
+
+  if let Err(err) = lock_result.lockfile.write(root_project.get_absolute_project_root()) {
+    exit_with_error(GCMakeError::Io(format!("Failed to write {}: {}", DEPENDENCY_LOCK_FILE_NAME, err.to_string())));
+  }
+
+  if lock_result.entry_statuses.is_empty() {
+    println!("Project [{}] doesn't download any dependencies, so there is nothing to lock.", root_project.get_name_for_error_messages());
+    return;
+  }
+
+  for (dep_name, status) in &lock_result.entry_statuses {
+    match status {
+      LockedEntryStatus::Added => println!("Locked: {}", dep_name.green()),
+      LockedEntryStatus::Updated => println!("Updated: {}", dep_name.cyan()),
+      LockedEntryStatus::Unchanged => println!("Unchanged: {}", dep_name)
+    }
   }
 }
 
@@ -544,29 +1258,69 @@ pub fn do_new_project_subcommand(
 
       return maybe_general_new_project_info;
     },
-    Err(error_message) => exit_error_log(&error_message)
+    Err(err) => exit_with_error(err)
   }
 }
 
 pub fn do_dependency_config_update_subcommand(command: UpdateDependencyConfigsCommand) {
-  println!("{}", "Beginning dependency config repo update...".green());
+  let all_repos: Vec<DepConfigRepo> = match configured_dep_config_repos() {
+    Ok(repos) => repos,
+    Err(err_msg) => exit_with_error(GCMakeError::Config(err_msg))
+  };
+
+  let updated_repos: Vec<&DepConfigRepo> = match &command.repo_name {
+    None => all_repos.iter().collect(),
+    Some(repo_name) => match all_repos.iter().find(|repo| &repo.name == repo_name) {
+      Some(repo) => vec![repo],
+      None => exit_with_error(GCMakeError::Config(format!(
+        "There is no dependency config repo named '{}'. Configured repos are: {}",
+        repo_name.yellow(),
+        all_repos.iter().map(|repo| repo.name.as_str()).collect::<Vec<&str>>().join(", ")
+      )))
+    }
+  };
 
-  match update_dependency_config_repo(&command.branch) {
-    Err(err) => exit_error_log(format!(
+  if command.branch.is_some() && updated_repos.len() > 1 {
+    exit_with_error(GCMakeError::Config(format!(
+      "Multiple dependency config repos are configured. Use {} to select which repo should be checked out to the given branch.",
+      "--repo".yellow()
+    )));
+  }
+
+  for repo in updated_repos {
+    update_single_dependency_config_repo(repo, &command.branch);
+  }
+}
+
+fn update_single_dependency_config_repo(
+  repo: &DepConfigRepo,
+  maybe_branch: &Option<String>
+) {
+  println!(
+    "{} '{}'...",
+    "Beginning dependency config repo update for".green(),
+    repo.name
+  );
+
+  match update_dependency_config_repo(repo, maybe_branch) {
+    Err(err) => exit_with_error(GCMakeError::Subprocess(format!(
       "{}\n\t{}",
       "Failed to update dependency config repo: ".red(),
       err.to_string()
-    )),
+    ))),
     Ok(status) => match status {
       DepConfigUpdateResult::SubprocessError(git_subprocess_err_msg) => {
-        exit_error_log(git_subprocess_err_msg);
+        exit_with_error(GCMakeError::Subprocess(git_subprocess_err_msg));
       },
       DepConfigUpdateResult::NewlyDownloaded { branch, local_repo_location } => {
         println!(
           "Dependency config repo successfully downloaded to {}.",
           local_repo_location.to_str().unwrap()
         );
-        println!("Checked out '{}' branch.", branch);
+
+        if let Some(checked_out_branch) = branch {
+          println!("Checked out '{}' branch.", checked_out_branch);
+        }
       },
       DepConfigUpdateResult::UpdatedBranch { branch: maybe_branch, .. } => {
         match maybe_branch {
@@ -583,30 +1337,358 @@ pub fn do_dependency_config_update_subcommand(command: UpdateDependencyConfigsCo
             );
           }
         }
+      },
+      DepConfigUpdateResult::NotManaged { local_repo_location } => {
+        println!(
+          "Skipped '{}' because it is a local directory, not a git repo managed by GCMake.",
+          local_repo_location.to_str().unwrap()
+        );
       }
     }
   }
 }
 
+pub fn do_dependency_config_check_subcommand(
+  command: &CheckDependencyConfigsCommand,
+  dep_config: &RawPredefinedDependencyMap
+) -> Result<(), GCMakeError> {
+  let checked_dep_names: Vec<String> = if command.dep_names.is_empty() {
+    let mut all_dep_names: Vec<String> = dep_config.available_dep_names().iter().cloned().collect();
+    all_dep_names.sort();
+    all_dep_names
+  }
+  else {
+    command.dep_names.clone()
+  };
+
+  let mut num_failed: usize = 0;
+
+  for dep_name in &checked_dep_names {
+    let errors: Vec<String> = validate_predefined_dep_config(dep_config, dep_name);
+
+    if errors.is_empty() {
+      println!("{} {}", "ok".green(), dep_name);
+    }
+    else {
+      num_failed += 1;
+      println!("{} {}", "failed".red(), dep_name);
+
+      logger::block(|| {
+        for err_msg in &errors {
+          eprintln!("{}\n", err_msg);
+        }
+      });
+    }
+  }
+
+  if num_failed > 0 {
+    return Err(GCMakeError::Config(format!(
+      "{} {} of {} dependency configurations have errors.",
+      "Error:".red(),
+      num_failed,
+      checked_dep_names.len()
+    )));
+  }
+
+  println!(
+    "\n{} All {} dependency configurations are valid.",
+    "Success:".green(),
+    checked_dep_names.len()
+  );
+  return Ok(());
+}
+
+fn print_dep_cache_entry(entry: &DepCacheEntry) {
+  println!(
+    "  {} {} {}",
+    format!("{:>10}", human_readable_size(entry.size_bytes)).cyan(),
+    entry.version.to_display_string(),
+    entry.path.file_name().unwrap().to_str().unwrap().bright_black()
+  );
+}
+
+fn total_entry_size(entries: &[&DepCacheEntry]) -> u64 {
+  entries.iter().map(|entry| entry.size_bytes).sum()
+}
+
+fn remove_dep_cache_entries(entries: &[&DepCacheEntry]) {
+  for entry in entries {
+    match remove_dep_cache_entry(entry) {
+      Ok(_) => println!("Removed {}", entry.path.to_str().unwrap()),
+      Err(err) => exit_with_error(GCMakeError::Io(format!(
+        "Failed to remove '{}': {}",
+        entry.path.to_str().unwrap(),
+        err.to_string()
+      )))
+    }
+  }
+
+  println!(
+    "\n{} Freed {} from {} cache entries.",
+    "Success:".green(),
+    human_readable_size(total_entry_size(entries)),
+    entries.len()
+  );
+}
+
+fn confirm_dep_cache_removal(entries: &[&DepCacheEntry], skip_confirmation: bool) -> bool {
+  if skip_confirmation {
+    return true;
+  }
+
+  let prompt: String = format!(
+    "Remove {} cache entries ({})?",
+    entries.len(),
+    human_readable_size(total_entry_size(entries))
+  );
+
+  return match prompt_until_boolean(&prompt) {
+    Ok(should_remove) => should_remove,
+    Err(err) => exit_with_error(err.into())
+  }
+}
+
+fn dep_cache_entries_or_exit(maybe_dep_name: Option<&str>) -> Vec<DepCacheEntry> {
+  match list_dep_cache_entries(maybe_dep_name) {
+    Ok(entries) => entries,
+    Err(err) => exit_with_error(GCMakeError::Io(format!(
+      "Failed to read the dependency cache at '{}': {}",
+      gcmake_dep_cache_dir().to_str().unwrap(),
+      err.to_string()
+    )))
+  }
+}
+
+pub fn do_dep_cache_list_subcommand(command: &ListDepCacheCommand) {
+  let entries: Vec<DepCacheEntry> = dep_cache_entries_or_exit(command.dep_name.as_deref());
+
+  if entries.is_empty() {
+    println!("The dependency cache is empty.");
+    return;
+  }
+
+  let mut entries_by_dep: BTreeMap<&str, Vec<&DepCacheEntry>> = BTreeMap::new();
+
+  for entry in &entries {
+    entries_by_dep.entry(&entry.dep_name)
+      .or_default()
+      .push(entry);
+  }
+
+  for (dep_name, dep_entries) in &entries_by_dep {
+    println!(
+      "{} ({})",
+      dep_name.green(),
+      human_readable_size(total_entry_size(dep_entries))
+    );
+
+    for entry in dep_entries {
+      print_dep_cache_entry(entry);
+    }
+  }
+
+  println!(
+    "\nTotal: {} in {} cache entries ({})",
+    human_readable_size(entries.iter().map(|entry| entry.size_bytes).sum()),
+    entries.len(),
+    gcmake_dep_cache_dir().to_str().unwrap()
+  );
+}
+
+pub fn do_dep_cache_clean_subcommand(command: &CleanDepCacheCommand) {
+  let entries: Vec<DepCacheEntry> = dep_cache_entries_or_exit(command.dep_name.as_deref());
+
+  if entries.is_empty() {
+    match &command.dep_name {
+      Some(dep_name) => println!("There are no cached versions of '{}'.", dep_name.yellow()),
+      None => println!("The dependency cache is already empty.")
+    }
+    return;
+  }
+
+  let removed_entries: Vec<&DepCacheEntry> = entries.iter().collect();
+
+  if confirm_dep_cache_removal(&removed_entries, command.skip_confirmation) {
+    remove_dep_cache_entries(&removed_entries);
+  }
+  else {
+    println!("Cache clean canceled.");
+  }
+}
+
+pub fn do_dep_cache_prune_subcommand(
+  command: &PruneDepCacheCommand,
+  dep_config: &RawPredefinedDependencyMap
+) {
+  let mut references = DepCacheReferences::default();
+
+  // Every project must load successfully. Otherwise we can't know which of its dependencies
+  // are still in use, and might remove them.
+  for project_root in &command.project_roots {
+    let project_group: UseableFinalProjectDataGroup = get_project_info_or_exit(
+      project_root,
+      dep_config,
+      FinalProjectLoadContext::default()
+    );
+
+    references.add_project_tree(&project_group.root_project);
+  }
+
+  let entries: Vec<DepCacheEntry> = dep_cache_entries_or_exit(None);
+  let unreferenced_entries: Vec<&DepCacheEntry> = entries.iter()
+    .filter(|entry| !references.is_referenced(entry))
+    .collect();
+
+  if unreferenced_entries.is_empty() {
+    println!("Nothing to prune. Every cache entry is used by the given projects.");
+    return;
+  }
+
+  println!("Cache entries not used by any of the given projects:");
+
+  for entry in &unreferenced_entries {
+    println!("{}", entry.dep_name.green());
+    print_dep_cache_entry(entry);
+  }
+
+  println!();
+
+  if command.dry_run {
+    println!(
+      "Would free {} from {} cache entries.",
+      human_readable_size(total_entry_size(&unreferenced_entries)),
+      unreferenced_entries.len()
+    );
+  }
+  else if confirm_dep_cache_removal(&unreferenced_entries, command.skip_confirmation) {
+    remove_dep_cache_entries(&unreferenced_entries);
+  }
+  else {
+    println!("Cache prune canceled.");
+  }
+}
+
 pub fn get_parent_project_for_new_project(
   current_root: &str,
   dep_config: &RawPredefinedDependencyMap,
   requires_all_yaml_files_present: bool
-) -> Result<Option<UseableFinalProjectDataGroup>, String> {
+) -> Result<Option<UseableFinalProjectDataGroup>, GCMakeError> {
   match parse_project_info(
     current_root,
     dep_config,
     FinalProjectLoadContext::default()
   ) {
     Ok(project_data_group) => Ok(Some(project_data_group)),
-    Err(failure_reason) => match failure_reason {
-      ProjectLoadFailureReason::MissingYaml(error_message) => {
-        if requires_all_yaml_files_present
-          { Err(error_message) }
-          else { Ok(None) }
-      },
-      ProjectLoadFailureReason::Other(error_message) => Err(error_message),
-      ProjectLoadFailureReason::MissingRequiredTestFramework(error_message) => Err(error_message)
-    }
+    Err(ProjectLoadFailureReason::MissingYaml(_)) if !requires_all_yaml_files_present => Ok(None),
+    Err(failure_reason) => Err(failure_reason.into())
   }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{api::{self, DependencyGraphWarningMode}, gcmake_error::CONFIG_ERROR_EXIT_CODE, project_info::raw_data_in::dependencies::RawPredefinedDependencyMap, test_projects::{TestProjectDir, git_dep_config_yaml, no_dep_configs}};
+
+  use super::{do_check_project, do_dependency_config_check_subcommand, compare_generated_configs, dep_config_for_project_tree, ConfigGenerationMode, CheckDependencyConfigsCommand, validate_predefined_dep_config};
+
+  #[test]
+  fn test_check_reports_every_broken_subproject() {
+    let project_dir = TestProjectDir::new("check");
+
+    project_dir.write_root_project("", "checked", &[], "");
+    project_dir.write_file(
+      "subprojects/first/cmake_data.yaml",
+      "include_prefix: FIRST\ndescription: d\nversion: 0.0.1\noutput:\n  first:\n    output_type: NotAnOutputType\n    entry_file: first.hpp\n"
+    );
+    project_dir.write_file(
+      "subprojects/second/cmake_data.yaml",
+      "include_prefix: SECOND\ndescription: d\nversion: 0.0.1\nunknown_key: 1\noutput: {}\n"
+    );
+
+    let err = do_check_project(&project_dir.path_str(""), &no_dep_configs()).unwrap_err();
+
+    assert_eq!(err.exit_code(), CONFIG_ERROR_EXIT_CODE);
+    assert!(err.message().contains("NotAnOutputType"));
+    assert!(err.message().contains("unknown_key"));
+    assert!(err.message().contains("Found 2 problem(s)"));
+  }
+
+  #[test]
+  fn test_check_fails_until_generated_files_are_written() {
+    let test_dir = TestProjectDir::new("check-up-to-date");
+
+    test_dir.write_root_project("", "generated", &[], "");
+
+    let project_group = api::load_project(&test_dir.path_str(""), &no_dep_configs()).unwrap();
+    let graph_info = api::load_dependency_graph(&project_group, DependencyGraphWarningMode::Off).unwrap();
+
+    assert!(!compare_generated_configs(&graph_info, ConfigGenerationMode::CheckUpToDate));
+    // A dry run only reports what would change.
+    assert!(compare_generated_configs(&graph_info, ConfigGenerationMode::DryRun));
+
+    api::render_project_configs(&graph_info).unwrap().write_all().unwrap();
+    assert!(compare_generated_configs(&graph_info, ConfigGenerationMode::CheckUpToDate));
+
+    test_dir.write_file("CMakeLists.txt", "# Edited by hand\n");
+    assert!(!compare_generated_configs(&graph_info, ConfigGenerationMode::CheckUpToDate));
+  }
+
+  #[test]
+  fn test_project_local_dep_configs_override_repo_configs() {
+    let test_dir = TestProjectDir::new("local-dep-configs");
+
+    test_dir.write_file("repo/good/dep_config.yaml", &git_dep_config_yaml("good", "https://example.com/repo-good.git"));
+    test_dir.write_file("repo/other/dep_config.yaml", &git_dep_config_yaml("other", "https://example.com/other.git"));
+    test_dir.write_root_project("project", "project", &[], "");
+    test_dir.write_file("project/.gcmake/dep-configs/good/dep_config.yaml", &git_dep_config_yaml("good", "https://example.com/local-good.git"));
+    test_dir.write_file("project/subprojects/nested/cmake_data.yaml", "include_prefix: NESTED\ndescription: d\nversion: 0.0.1\noutput: {}\n");
+
+    let repo_dep_config: RawPredefinedDependencyMap = test_dir.dep_config_repo("repo");
+
+    // Local configs are found from any project in the tree, not just the root project.
+    let project_dep_config: RawPredefinedDependencyMap = dep_config_for_project_tree(&test_dir.path_str("project/subprojects/nested"), &repo_dep_config)
+      .unwrap()
+      .unwrap();
+    let outside_project = dep_config_for_project_tree(&test_dir.path_str(""), &repo_dep_config);
+
+    let repo_url_of = |dep_config: &RawPredefinedDependencyMap, dep_name: &str| -> String {
+      return dep_config.get(dep_name).unwrap().unwrap()
+        .dep_configs.get_common().unwrap()
+        .repo_url().unwrap()
+        .to_string();
+    };
+
+    assert!(outside_project.unwrap().is_none());
+    assert_eq!(repo_url_of(&project_dep_config, "good"), "https://example.com/local-good.git");
+    assert_eq!(repo_url_of(&project_dep_config, "other"), "https://example.com/other.git");
+    assert_eq!(repo_url_of(&repo_dep_config, "good"), "https://example.com/repo-good.git");
+  }
+
+  #[test]
+  fn test_dep_config_check_reports_every_broken_config() {
+    let test_dir = TestProjectDir::new("dep-config-check");
+
+    test_dir.write_file("repo/good/dep_config.yaml", &git_dep_config_yaml("good", "https://example.com/good.git"));
+    test_dir.write_file("repo/unparseable/dep_config.yaml", "as_subdirectory: [not, a, mapping\n");
+    test_dir.write_file(
+      "repo/bad-requires/dep_config.yaml",
+      &git_dep_config_yaml("bad-requires", "https://example.com/bad-requires.git")
+        .replace("    lib: {}\n", "    lib:\n      requires:\n        - missing-target\n")
+    );
+
+    let dep_config: RawPredefinedDependencyMap = test_dir.dep_config_repo("repo");
+
+    assert!(validate_predefined_dep_config(&dep_config, "good").is_empty());
+    assert!(!validate_predefined_dep_config(&dep_config, "unparseable").is_empty());
+
+    let requires_errors: Vec<String> = validate_predefined_dep_config(&dep_config, "bad-requires");
+    assert!(requires_errors.iter().any(|err_msg| err_msg.contains("missing-target")), "{:?}", requires_errors);
+
+    // Every config is checked, even after one of them fails.
+    let err = do_dependency_config_check_subcommand(&CheckDependencyConfigsCommand { dep_names: Vec::new() }, &dep_config).unwrap_err();
+    assert_eq!(err.exit_code(), CONFIG_ERROR_EXIT_CODE);
+    assert!(err.message().contains("2 of 3 dependency configurations have errors"), "{}", err.message());
+
+    let only_good = CheckDependencyConfigsCommand { dep_names: vec![String::from("good")] };
+    assert!(do_dependency_config_check_subcommand(&only_good, &dep_config).is_ok());
+  }
+}
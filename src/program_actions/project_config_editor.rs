@@ -0,0 +1,336 @@
+use std::{fs, path::PathBuf, collections::{HashMap, HashSet}};
+
+use colored::*;
+
+use crate::{cli_config::clap_cli_config::AddPredefinedDependencyCommand, common::yaml_editing::{YamlDocument, yaml_quoted_string, yaml_string_scalar}, project_info::{final_project_data::FinalProjectData, final_dependencies::FinalPredefinedDependencyConfig, raw_data_in::dependencies::{RawPredefinedDependencyMap, user_given_dep_config::UserGivenPredefinedDependencyConfig, internal_dep_config::{RawPredefinedDependencyInfo, raw_dep_common::RawPredepCommon}}, gcmake_constants::PROJECT_LOCAL_DEP_CONFIGS_DIR, CompiledOutputItem}};
+
+// A cmake_data.yaml which has been edited. Keeps the original contents so the edit can be
+// undone if the project no longer loads afterwards.
+pub struct ProjectConfigEdit {
+  pub config_file_path: PathBuf,
+  original_contents: String
+}
+
+impl ProjectConfigEdit {
+  pub fn revert(&self) -> Result<(), String> {
+    return fs::write(&self.config_file_path, &self.original_contents)
+      .map_err(|err| format!(
+        "Failed to restore '{}': {}",
+        self.config_file_path.to_str().unwrap(),
+        err.to_string()
+      ));
+  }
+}
+
+pub fn edit_project_config(
+  project: &FinalProjectData,
+  make_edit: impl FnOnce(&mut YamlDocument) -> Result<(), String>
+) -> Result<ProjectConfigEdit, String> {
+  let config_file_path: PathBuf = project.get_config_file_path();
+
+  let contents: String = fs::read_to_string(&config_file_path)
+    .map_err(|err| format!(
+      "Failed to read '{}': {}",
+      config_file_path.to_str().unwrap(),
+      err.to_string()
+    ))?;
+
+  let mut document = YamlDocument::new(&contents);
+
+  make_edit(&mut document)
+    .map_err(|err_msg| format!(
+      "Unable to edit '{}':\n{}",
+      config_file_path.to_str().unwrap(),
+      err_msg
+    ))?;
+
+  fs::write(&config_file_path, document.to_string())
+    .map_err(|err| format!(
+      "Failed to write '{}': {}",
+      config_file_path.to_str().unwrap(),
+      err.to_string()
+    ))?;
+
+  return Ok(ProjectConfigEdit {
+    config_file_path,
+    original_contents: contents
+  });
+}
+
+// Lists what the user can specify for a dependency. Used when the dependency can't be added
+// because no download options were given.
+fn download_options_help(dep_name: &str, raw_dep_info: &RawPredefinedDependencyInfo) -> String {
+  let subdir_dep = match &raw_dep_info.dep_configs.as_subdirectory {
+    Some(subdir_dep) => subdir_dep,
+    None => return String::new()
+  };
+
+  let mut help_lines: Vec<String> = Vec::new();
+
+  if subdir_dep.supports_git_download_method() {
+    help_lines.push(format!("Use {} or {} to download '{}' using git.", "--git-tag".yellow(), "--commit".yellow(), dep_name));
+  }
+
+  if let Some(url_info) = &subdir_dep.download_info.url_method {
+    let mut line: String = format!("Use {} to download '{}' as an archive.", "--file-version".yellow(), dep_name);
+
+    if let Some(url_map) = &url_info.url_base_by_version {
+      let mut known_versions: Vec<&String> = url_map.keys().collect();
+      known_versions.sort();
+
+      line.push_str(&format!(
+        " Known versions: {}",
+        known_versions.iter().map(|version| version.as_str()).collect::<Vec<&str>>().join(", ")
+      ));
+    }
+
+    help_lines.push(line);
+  }
+
+  return help_lines.join("\n");
+}
+
+// Each option can only be written to cmake_data.yaml once.
+fn first_duplicate_option_name(options: &[(String, String)]) -> Option<&str> {
+  let mut seen_names: HashSet<&str> = HashSet::new();
+
+  return options.iter()
+    .map(|(option_name, _)| option_name.as_str())
+    .find(|option_name| !seen_names.insert(option_name));
+}
+
+// Validates the dependency the same way it will be validated when the project is loaded, then
+// adds it to the root project's predefined_dependencies.
+pub fn add_predefined_dependency(
+  command: &AddPredefinedDependencyCommand,
+  root_project: &FinalProjectData,
+  dep_config: &RawPredefinedDependencyMap
+) -> Result<ProjectConfigEdit, String> {
+  let dep_name: &str = &command.dep_name;
+
+  if let Some(duplicate_name) = first_duplicate_option_name(&command.options) {
+    return Err(format!(
+      "Option '{}' was given more than once. Pass each --option only once.",
+      duplicate_name.yellow()
+    ));
+  }
+
+  if root_project.get_predefined_dependencies().contains_key(dep_name) {
+    return Err(format!(
+      "Project '{}' already uses the predefined dependency '{}'.",
+      root_project.get_project_base_name(),
+      dep_name.yellow()
+    ));
+  }
+
+  let local_dep_config: Option<RawPredefinedDependencyMap> = dep_config.with_project_local_configs(
+    &root_project.get_absolute_project_root().join(PROJECT_LOCAL_DEP_CONFIGS_DIR)
+  )?;
+  let dep_config: &RawPredefinedDependencyMap = local_dep_config.as_ref().unwrap_or(dep_config);
+
+  let raw_dep_info: &RawPredefinedDependencyInfo = match dep_config.get(dep_name)? {
+    Some(raw_dep_info) => raw_dep_info,
+    None => return Err(format!(
+      "There is no predefined dependency named '{}'. Run `{}` to list all available dependencies.",
+      dep_name.yellow(),
+      "gcmake predep-info".magenta()
+    ))
+  };
+
+  let user_given_config = UserGivenPredefinedDependencyConfig {
+    file_version: command.file_version.clone(),
+    git_tag: command.git_tag.clone(),
+    commit_hash: command.commit_hash.clone(),
+    repo_url: None,
+    options: if command.options.is_empty()
+      { None }
+      else { Some(command.options.iter().cloned().collect::<HashMap<String, String>>()) }
+  };
+
+  let feature_list: Vec<&str> = root_project.get_features().keys()
+    .map(|feature_name| feature_name.as_str())
+    .collect();
+
+  FinalPredefinedDependencyConfig::new(
+    dep_config,
+    &user_given_config,
+    dep_name,
+    if feature_list.is_empty() { None } else { Some(&feature_list) }
+  )
+    .map_err(|err_msg| {
+      let help_text: String = if user_given_config.specifies_git_mode_options() || user_given_config.specifies_url_mode_options()
+        { String::new() }
+        else { download_options_help(dep_name, raw_dep_info) };
+
+      if help_text.is_empty()
+        { err_msg }
+        else { format!("{}\n\n{}", err_msg, help_text) }
+    })?;
+
+  let mut entry_body: Vec<String> = Vec::new();
+
+  for (key, maybe_value) in [("git_tag", &command.git_tag), ("commit_hash", &command.commit_hash), ("file_version", &command.file_version)] {
+    if let Some(value) = maybe_value {
+      entry_body.push(format!("{}: {}", key, yaml_quoted_string(value)));
+    }
+  }
+
+  if !command.options.is_empty() {
+    entry_body.push(String::from("options:"));
+
+    for (option_name, option_value) in &command.options {
+      entry_body.push(format!("  {}: {}", option_name, yaml_quoted_string(option_value)));
+    }
+  }
+
+  return edit_project_config(
+    root_project,
+    |document| document.insert_mapping_entry(&["predefined_dependencies"], dep_name, &entry_body)
+  );
+}
+
+// Link specifiers are compared without whitespace, so 'self::{ a, b }' matches 'self::{a,b}'.
+fn normalized_link_spec(link_spec: &str) -> String {
+  return link_spec.chars()
+    .filter(|c| !c.is_whitespace())
+    .collect();
+}
+
+// Output names in cmake_data.yaml may be prefixed with a system specifier, such as '((windows)) my-exe'.
+fn output_name_from_key(output_key: &str) -> &str {
+  if output_key.starts_with("((") {
+    if let Some(spec_end) = output_key.find("))") {
+      return output_key[spec_end + 2..].trim_start();
+    }
+  }
+
+  return output_key;
+}
+
+fn find_output_key(document: &YamlDocument, output_name: &str) -> Result<String, String> {
+  return document.mapping_keys(&["output"])
+    .into_iter()
+    .find(|output_key| output_name_from_key(output_key) == output_name)
+    .ok_or_else(|| format!(
+      "Unable to find output '{}' in the 'output' section.",
+      output_name.yellow()
+    ));
+}
+
+fn project_output<'a>(project: &'a FinalProjectData, output_name: &str) -> Result<&'a CompiledOutputItem, String> {
+  return project.get_outputs().get(output_name)
+    .ok_or_else(|| format!(
+      "Project '{}' has no output named '{}'.",
+      project.get_project_base_name(),
+      output_name.yellow()
+    ));
+}
+
+// Executables and header-only libraries use a single link list, while compiled libraries
+// categorize their links into public: and private: lists.
+fn link_list_paths(output: &CompiledOutputItem) -> Vec<Vec<&'static str>> {
+  return if output.is_compiled_library_type()
+    { vec![vec!["link", "public"], vec!["link", "private"]] }
+    else { vec![vec!["link"]] };
+}
+
+pub fn add_output_links(
+  project: &FinalProjectData,
+  output_name: &str,
+  link_specs: &[String],
+  is_public: bool
+) -> Result<ProjectConfigEdit, String> {
+  let output: &CompiledOutputItem = project_output(project, output_name)?;
+
+  let link_list: &str = match (output.is_compiled_library_type(), is_public) {
+    (true, true) => "public",
+    (true, false) => "private",
+    (false, true) => return Err(format!(
+      "{} is only valid for compiled libraries, since only their links are categorized into public: and private: lists.",
+      "--public".yellow()
+    )),
+    (false, false) => ""
+  };
+
+  return edit_project_config(project, |document| {
+    let output_key: String = find_output_key(document, output_name)?;
+
+    let mut existing_links: Vec<String> = Vec::new();
+
+    for list_path in link_list_paths(output) {
+      let full_path: Vec<&str> = [vec!["output", output_key.as_str()], list_path].concat();
+      existing_links.extend(document.sequence_items(&full_path).iter().map(|item| normalized_link_spec(item)));
+    }
+
+    let mut link_path: Vec<&str> = vec!["output", &output_key, "link"];
+
+    if !link_list.is_empty() {
+      link_path.push(link_list);
+    }
+
+    for link_spec in link_specs {
+      if existing_links.contains(&normalized_link_spec(link_spec)) {
+        return Err(format!(
+          "Output '{}' already links '{}'.",
+          output_name,
+          link_spec.yellow()
+        ));
+      }
+
+      document.append_sequence_item(&link_path, &yaml_string_scalar(link_spec.trim()))?;
+      existing_links.push(normalized_link_spec(link_spec));
+    }
+
+    Ok(())
+  });
+}
+
+pub fn remove_output_links(
+  project: &FinalProjectData,
+  output_name: &str,
+  link_specs: &[String]
+) -> Result<ProjectConfigEdit, String> {
+  let output: &CompiledOutputItem = project_output(project, output_name)?;
+
+  return edit_project_config(project, |document| {
+    let output_key: String = find_output_key(document, output_name)?;
+
+    for link_spec in link_specs {
+      let normalized_spec: String = normalized_link_spec(link_spec);
+      let mut num_removed: usize = 0;
+
+      for list_path in link_list_paths(output) {
+        let full_path: Vec<&str> = [vec!["output", output_key.as_str()], list_path].concat();
+        num_removed += document.remove_sequence_items(&full_path, |item| normalized_link_spec(item) == normalized_spec);
+      }
+
+      if num_removed == 0 {
+        return Err(format!(
+          "Output '{}' does not link '{}'.",
+          output_name,
+          link_spec.yellow()
+        ));
+      }
+    }
+
+    Ok(())
+  });
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn options(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    return pairs.iter()
+      .map(|(key, value)| (key.to_string(), value.to_string()))
+      .collect();
+  }
+
+  #[test]
+  fn test_duplicate_option_names_are_found() {
+    assert_eq!(first_duplicate_option_name(&options(&[])), None);
+    assert_eq!(first_duplicate_option_name(&options(&[("shared", "ON"), ("tests", "OFF")])), None);
+    assert_eq!(first_duplicate_option_name(&options(&[("shared", "ON"), ("tests", "OFF"), ("shared", "OFF")])), Some("shared"));
+  }
+}
@@ -401,6 +401,7 @@ pub fn get_default_project_config(
     ]),
     predefined_dependencies: needed_predefined_dependencies(&config_options, requires_cppfront),
     gcmake_dependencies: None,
+    warnings: None,
     build_configs: BTreeMap::from_iter([
       (BuildType::Debug, build_configs_debug_default(&config_options)),
       (BuildType::Release, build_configs_release_default(&config_options)),
@@ -3,27 +3,30 @@ mod cpp_file_generation;
 mod default_project_config;
 mod project_generator_prompts;
 mod cpp_test_mains;
+mod project_template;
 
 pub use default_project_config::{*, configuration::*};
+pub use project_template::ProjectTemplate;
 use serde::Serialize;
 
 use std::{fs::{File, remove_dir_all, create_dir_all, self}, io::{self, ErrorKind}, path::{Path, PathBuf}};
 
-use crate::{program_actions::{ProjectTypeCreating, gcmake_config_root_dir}, common::prompt::{prompt_until_boolean, prompt_until_satisfies_or_default}, project_info::{base_include_prefix_for_test, gcmake_constants::{SRC_DIR_NAME, INCLUDE_DIR_NAME, ASSETS_DIR_NAME, SUBPROJECTS_DIR_NAME, TESTS_DIR_NAME}, validators::{is_valid_base_include_prefix}, FinalTestFramework}, project_generator::{project_generator_prompts::{prompt_for_project_output_type, prompt_for_language, prompt_for_vendor, prompt_for_description, prompt_for_needs_custom_main}, c_file_generation::generate_c_main, cpp_file_generation::{generate_cpp_main, TestMainInitInfo}}};
+use crate::{program_actions::{ProjectTypeCreating, gcmake_config_root_dir}, common::prompt::{prompt_until_boolean, prompt_until_satisfies_or_default, answer_or_prompt, answer_or_prompt_with_default}, cli_config::{NewProjectAnswers, how_to_answer}, project_info::{base_include_prefix_for_test, gcmake_constants::{SRC_DIR_NAME, INCLUDE_DIR_NAME, ASSETS_DIR_NAME, SUBPROJECTS_DIR_NAME, TESTS_DIR_NAME}, validators::{is_valid_base_include_prefix}, FinalTestFramework}, project_generator::{project_generator_prompts::{prompt_for_project_output_type, prompt_for_language, prompt_for_vendor, prompt_for_description, prompt_for_needs_custom_main}, c_file_generation::generate_c_main, cpp_file_generation::{generate_cpp_main, TestMainInitInfo}, project_template::TemplatePlaceholders}};
 use colored::*;
 
 pub struct GeneralNewProjectInfo {
   pub project: CreatedProject,
   pub project_lang: MainFileLanguage,
   pub project_output_type: CreationProjectOutputType,
-  pub project_root: String
+  pub project_root: String,
+  pub answers: NewProjectAnswers
 }
 
 pub fn create_project_at(
   new_project_root: &str,
   project_type_creating: ProjectTypeCreating,
-  project_lang: Option<MainFileLanguage>,
-  project_output_type: Option<CreationProjectOutputType>
+  answers: NewProjectAnswers,
+  maybe_template: Option<&ProjectTemplate>
 ) -> io::Result<Option<GeneralNewProjectInfo>> {
   let project_name: &str;
 
@@ -41,7 +44,13 @@ pub fn create_project_at(
   if project_root.is_dir() {
     let prompt: String = format!("Directory {} already exists. Overwrite it?", new_project_root);
 
-    if prompt_until_boolean(&prompt)? {
+    let should_overwrite: bool = answer_or_prompt(
+      answers.overwrite,
+      how_to_answer("overwrite confirmation", "--overwrite or --no-overwrite", "overwrite"),
+      || prompt_until_boolean(&prompt)
+    )?;
+
+    if should_overwrite {
       remove_dir_all(project_root)?;
       println!("Directory removed. Generating new project...");
     }
@@ -70,10 +79,14 @@ pub fn create_project_at(
       .to_uppercase()
       .replace("-", "_");
 
-    let include_prefix: String = prompt_until_satisfies_or_default(
-      "Include prefix".bright_green(),
-      is_valid_base_include_prefix,
-      &default_prefix
+    let include_prefix: String = answer_or_prompt_with_default(
+      answers.include_prefix.clone(),
+      default_prefix.clone(),
+      || prompt_until_satisfies_or_default(
+        "Include prefix".bright_green(),
+        is_valid_base_include_prefix,
+        &default_prefix
+      )
     )?;
 
     let folder_generation_include_prefix: String = match &project_type_creating {
@@ -86,29 +99,49 @@ pub fn create_project_at(
       }
     };
 
-    let output_type_selection: CreationProjectOutputType = match project_output_type {
-      Some(out_type) => out_type,
-      None => prompt_for_project_output_type()?
-    };
+    let output_type_selection: CreationProjectOutputType = answer_or_prompt(
+      answers.project_output_type.clone(),
+      how_to_answer(
+        "project type",
+        if let ProjectTypeCreating::Subproject { .. } = &project_type_creating
+          { "--subproject-type" }
+          else { "--project-type" },
+        "type"
+      ),
+      prompt_for_project_output_type
+    )?;
 
-    let lang_selection: MainFileLanguage = match project_lang {
-      Some(lang) => lang,
-      None => prompt_for_language()?
-    };
+    let lang_selection: MainFileLanguage = answer_or_prompt(
+      answers.language,
+      how_to_answer("project language", "--c, --cpp or --cpp2", "language"),
+      prompt_for_language
+    )?;
 
     let project_vendor: String = match &project_type_creating {
-      ProjectTypeCreating::RootProject { .. } => prompt_for_vendor()?,
+      ProjectTypeCreating::RootProject { .. } => answer_or_prompt(
+        answers.vendor.clone(),
+        how_to_answer("vendor name", "--vendor", "vendor"),
+        prompt_for_vendor
+      )?,
       _ => String::from("THIS IS IGNORED")
     };
     
-    let project_description: String = prompt_for_description()?;
+    let project_description: String = answer_or_prompt(
+      answers.description.clone(),
+      how_to_answer("project description", "--description", "description"),
+      prompt_for_description
+    )?;
 
     let requires_custom_main: Option<bool> = match &project_type_creating {
-      ProjectTypeCreating::Test { .. } => Some(prompt_for_needs_custom_main()?),
+      ProjectTypeCreating::Test { .. } => Some(answer_or_prompt_with_default(
+        answers.requires_custom_main,
+        false,
+        prompt_for_needs_custom_main
+      )?),
       _ => None
     };
 
-    let project_info: DefaultProjectInfo = build_default_project_info(
+    let mut project_info: DefaultProjectInfo = build_default_project_info(
       &project_type_creating,
       project_name,
       &include_prefix,
@@ -119,6 +152,18 @@ pub fn create_project_at(
       requires_custom_main
     );
 
+    let template_placeholders = TemplatePlaceholders {
+      project_name: project_name.to_string(),
+      include_prefix: include_prefix.clone(),
+      vendor: project_vendor.clone()
+    };
+
+    if let (Some(template), DefaultProjectInfo::RootProject(default_config)) = (maybe_template, &project_info) {
+      project_info = DefaultProjectInfo::RootProject(
+        template.apply_to_config(default_config, &template_placeholders)?
+      );
+    }
+
     // ----------------------------------------
     // Folder and file generation section
     // ----------------------------------------
@@ -256,6 +301,11 @@ pub fn create_project_at(
       }
     }
 
+    if let Some(template) = maybe_template {
+      template.copy_files_into(project_root, &template_placeholders)?;
+      println!("\nApplied project template '{}'", template.get_name().cyan());
+    }
+
     return Ok(Some( GeneralNewProjectInfo {
       project: CreatedProject {
         name: project_name.to_string(),
@@ -264,6 +314,7 @@ pub fn create_project_at(
       project_lang: lang_selection.clone(),
       project_output_type: output_type_selection,
       project_root: project_root.to_str().unwrap().to_string(),
+      answers
     }));
   }
 
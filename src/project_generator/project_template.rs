@@ -0,0 +1,274 @@
+use std::{fs, io, path::{Path, PathBuf}};
+
+use colored::*;
+use serde_yaml::{Value, Mapping};
+
+use crate::{common::basic_configure_replace, program_actions::gcmake_project_templates_dir, project_info::raw_data_in::RawProject};
+
+const TEMPLATE_CONFIG_FILE_NAME: &'static str = "cmake_data.yaml";
+
+// Values substituted for @PROJECT_NAME@, @INCLUDE_PREFIX@ and @VENDOR@ in template files.
+pub struct TemplatePlaceholders {
+  pub project_name: String,
+  pub include_prefix: String,
+  pub vendor: String
+}
+
+impl TemplatePlaceholders {
+  fn substitute(&self, text: &str) -> String {
+    return basic_configure_replace(
+      text,
+      [
+        ("PROJECT_NAME", self.project_name.clone()),
+        ("INCLUDE_PREFIX", self.include_prefix.clone()),
+        ("VENDOR", self.vendor.clone())
+      ]
+    );
+  }
+
+  fn substitute_in_value(&self, value: &mut Value) {
+    match value {
+      Value::String(the_str) => *the_str = self.substitute(the_str),
+      Value::Sequence(items) => {
+        for item in items {
+          self.substitute_in_value(item);
+        }
+      },
+      Value::Mapping(mapping) => {
+        let old_mapping: Mapping = std::mem::take(mapping);
+
+        for (mut key, mut item) in old_mapping {
+          self.substitute_in_value(&mut key);
+          self.substitute_in_value(&mut item);
+          mapping.insert(key, item);
+        }
+      },
+      _ => ()
+    }
+  }
+}
+
+fn template_error(message: String) -> io::Error {
+  return io::Error::new(io::ErrorKind::Other, message);
+}
+
+fn available_template_names() -> Vec<String> {
+  let mut template_names: Vec<String> = match fs::read_dir(gcmake_project_templates_dir()) {
+    Err(_) => Vec::new(),
+    Ok(dir_entries) => dir_entries
+      .filter_map(|dirent| dirent.ok())
+      .filter(|dirent| dirent.path().is_dir())
+      .filter_map(|dirent| dirent.file_name().to_str().map(String::from))
+      .collect()
+  };
+
+  template_names.sort();
+  return template_names;
+}
+
+// Mappings are merged key by key. Any other template value replaces the default entirely.
+fn merge_yaml_over(base: &mut Value, overrides: Value) {
+  match (base, overrides) {
+    (Value::Mapping(base_mapping), Value::Mapping(override_mapping)) => {
+      for (key, override_value) in override_mapping {
+        match base_mapping.get_mut(&key) {
+          Some(base_value) => merge_yaml_over(base_value, override_value),
+          None => {
+            base_mapping.insert(key, override_value);
+          }
+        }
+      }
+    },
+    (base_value, override_value) => *base_value = override_value
+  }
+}
+
+fn copy_template_dir(
+  from_dir: &Path,
+  to_dir: &Path,
+  placeholders: &TemplatePlaceholders,
+  is_template_root: bool
+) -> io::Result<()> {
+  for dirent in fs::read_dir(from_dir)? {
+    let from_path: PathBuf = dirent?.path();
+    let file_name: &str = from_path.file_name().unwrap().to_str().unwrap();
+
+    if is_template_root && file_name == TEMPLATE_CONFIG_FILE_NAME {
+      continue;
+    }
+
+    let to_path: PathBuf = to_dir.join(placeholders.substitute(file_name));
+
+    if from_path.is_dir() {
+      fs::create_dir_all(&to_path)?;
+      copy_template_dir(&from_path, &to_path, placeholders, false)?;
+    }
+    else {
+      let file_bytes: Vec<u8> = fs::read(&from_path)?;
+
+      // Binary files such as images are copied as-is.
+      match String::from_utf8(file_bytes) {
+        Ok(file_text) => fs::write(&to_path, placeholders.substitute(&file_text))?,
+        Err(not_text) => fs::write(&to_path, not_text.into_bytes())?
+      }
+    }
+  }
+
+  Ok(())
+}
+
+// A user-defined project template, located in ~/.gcmake/templates/<name>/. The template's
+// cmake_data.yaml (if any) is merged over the generated defaults, and every other file is copied
+// into the new project.
+pub struct ProjectTemplate {
+  name: String,
+  template_dir: PathBuf
+}
+
+// Template names are directory names inside the templates directory, so they can't point
+// anywhere else.
+fn validate_template_name(template_name: &str) -> Result<(), String> {
+  let is_invalid: bool = template_name.trim().is_empty()
+    || template_name.contains(|c: char| c == '/' || c == '\\')
+    || template_name.contains("..")
+    || template_name == ".";
+
+  if is_invalid {
+    return Err(format!(
+      "'{}' is not a valid project template name. Template names are the name of a directory in {}, so they can't contain path separators or '..'.",
+      template_name.yellow(),
+      gcmake_project_templates_dir().to_str().unwrap()
+    ));
+  }
+
+  return Ok(());
+}
+
+impl ProjectTemplate {
+  pub fn load(template_name: &str) -> Result<Self, String> {
+    validate_template_name(template_name)?;
+
+    let template_dir: PathBuf = gcmake_project_templates_dir().join(template_name);
+
+    if !template_dir.is_dir() {
+      let available_names: Vec<String> = available_template_names();
+
+      return Err(format!(
+        "There is no project template named '{}' in {}. {}",
+        template_name.yellow(),
+        gcmake_project_templates_dir().to_str().unwrap(),
+        if available_names.is_empty()
+          { String::from("No templates are available.") }
+          else { format!("Available templates: {}", available_names.join(", ")) }
+      ));
+    }
+
+    return Ok(Self {
+      name: template_name.to_string(),
+      template_dir
+    });
+  }
+
+  pub fn get_name(&self) -> &str {
+    &self.name
+  }
+
+  pub fn apply_to_config(
+    &self,
+    default_config: &RawProject,
+    placeholders: &TemplatePlaceholders
+  ) -> io::Result<RawProject> {
+    let config_file_path: PathBuf = self.template_dir.join(TEMPLATE_CONFIG_FILE_NAME);
+
+    let mut merged_config: Value = serde_yaml::to_value(default_config)
+      .map_err(|err| template_error(err.to_string()))?;
+
+    if config_file_path.is_file() {
+      let mut template_config: Value = serde_yaml::from_str(&fs::read_to_string(&config_file_path)?)
+        .map_err(|err| template_error(format!(
+          "Failed to parse {}: {}",
+          config_file_path.to_str().unwrap(),
+          err.to_string()
+        )))?;
+
+      placeholders.substitute_in_value(&mut template_config);
+
+      if !template_config.is_null() {
+        merge_yaml_over(&mut merged_config, template_config);
+      }
+    }
+
+    return serde_yaml::from_value(merged_config)
+      .map_err(|err| template_error(format!(
+        "Project template '{}' produces an invalid cmake_data.yaml: {}",
+        self.name,
+        err.to_string()
+      )));
+  }
+
+  pub fn copy_files_into(&self, project_root: &Path, placeholders: &TemplatePlaceholders) -> io::Result<()> {
+    return copy_template_dir(&self.template_dir, project_root, placeholders, true);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn yaml(text: &str) -> Value {
+    return serde_yaml::from_str(text).unwrap();
+  }
+
+  fn placeholders() -> TemplatePlaceholders {
+    return TemplatePlaceholders {
+      project_name: String::from("my-project"),
+      include_prefix: String::from("MY_PROJECT"),
+      vendor: String::from("Some Vendor")
+    };
+  }
+
+  #[test]
+  fn test_template_names_cant_leave_the_templates_dir() {
+    assert!(validate_template_name("service").is_ok());
+    assert!(validate_template_name("my.template").is_ok());
+
+    for invalid_name in ["", ".", "..", "../outside", "nested/template", "nested\\template", "/absolute"] {
+      assert!(validate_template_name(invalid_name).is_err(), "'{}' should be rejected", invalid_name);
+    }
+  }
+
+  #[test]
+  fn test_merge_yaml_over_merges_nested_mappings() {
+    let mut base: Value = yaml("name: base\nlanguages:\n  cpp:\n    min_standard: 17\n  c:\n    min_standard: 11\n");
+
+    merge_yaml_over(&mut base, yaml("languages:\n  cpp:\n    min_standard: 20\n    exact_standard: 20\nvendor: v\n"));
+
+    assert_eq!(base, yaml("name: base\nlanguages:\n  cpp:\n    min_standard: 20\n    exact_standard: 20\n  c:\n    min_standard: 11\nvendor: v\n"));
+  }
+
+  #[test]
+  fn test_merge_yaml_over_replaces_sequences_and_scalars() {
+    let mut base: Value = yaml("supported_compilers: [GCC, Clang, MSVC]\nglobal_defines: [FIRST]\ndescription: base\n");
+
+    merge_yaml_over(&mut base, yaml("supported_compilers: [GCC]\nglobal_defines: null\ndescription: [not, a, string]\n"));
+
+    assert_eq!(base, yaml("supported_compilers: [GCC]\nglobal_defines: null\ndescription: [not, a, string]\n"));
+  }
+
+  #[test]
+  fn test_placeholders_are_substituted_in_text() {
+    assert_eq!(
+      placeholders().substitute("#include \"@INCLUDE_PREFIX@/@PROJECT_NAME@.hpp\" // @VENDOR@ @UNKNOWN@"),
+      "#include \"MY_PROJECT/my-project.hpp\" // Some Vendor @UNKNOWN@"
+    );
+  }
+
+  #[test]
+  fn test_placeholders_are_substituted_in_yaml_keys_and_values() {
+    let mut template_config: Value = yaml("output:\n  \"@PROJECT_NAME@\":\n    entry_file: \"@PROJECT_NAME@.hpp\"\n    defines: [\"@INCLUDE_PREFIX@_EXPORT\", 3]\n");
+
+    placeholders().substitute_in_value(&mut template_config);
+
+    assert_eq!(template_config, yaml("output:\n  my-project:\n    entry_file: my-project.hpp\n    defines: [MY_PROJECT_EXPORT, 3]\n"));
+  }
+}
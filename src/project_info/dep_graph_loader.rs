@@ -1,6 +1,9 @@
 use std::{rc::Rc, cell::{RefCell, Ref}, collections::{HashMap, BTreeMap}};
 
-use crate::logger;
+use crate::{gcmake_error::GCMakeError, logger::{self, WarningId}};
+
+use super::include_validation::warn_for_invalid_includes;
+use super::yaml_diagnostics::locate_in_project_yaml;
 
 use super::{final_project_data::{UseableFinalProjectDataGroup}, dependency_graph_mod::dependency_graph::{DependencyGraphInfoWrapper, DependencyGraph, GraphLoadFailureReason, TargetNode, OwningComplexTargetRequirement, DependencyGraphWarningMode, AdditionalConfigValidationFailureReason, MaybePresentOwningTarget}, SystemSpecifierWrapper};
 use colored::*;
@@ -13,6 +16,19 @@ fn borrow_project<'a, 'b>(project: &'b Rc<RefCell<DependencyGraph<'a>>>) -> Ref<
   return project.as_ref().borrow();
 }
 
+// Points to the given section (or the value inside it) in the project's cmake_data.yaml.
+fn located_in_project(
+  project: &Rc<RefCell<DependencyGraph>>,
+  message: String,
+  key_path: &[&str],
+  maybe_value: Option<&str>
+) -> String {
+  return match borrow_project(project).project_wrapper().maybe_normal_project() {
+    Some(project_info) => locate_in_project_yaml(project_info.get_project_root_relative_to_cwd(), message, key_path, maybe_value),
+    None => message
+  }
+}
+
 fn warn_for_unused_deps(dep_graph_info: &DependencyGraphInfoWrapper) {
   // Maps project ID to targets used in the project.
   let mut dependency_project_use_map: HashMap<usize, (u32, String)> = HashMap::new();
@@ -44,7 +60,7 @@ fn warn_for_unused_deps(dep_graph_info: &DependencyGraphInfoWrapper) {
 
   for (dep_name, num_uses) in dep_uses_by_name {
     if num_uses == 0 {
-      logger::warn(format!(
+      logger::warn(WarningId::UnusedDependency, format!(
         "No targets from dependency [{}] are ever used. Did you forget to link one of its targets to an output item or executable pre-build script?",
         dep_name.yellow()
       ));
@@ -55,10 +71,17 @@ fn warn_for_unused_deps(dep_graph_info: &DependencyGraphInfoWrapper) {
 pub fn load_graph(
   project_data: &UseableFinalProjectDataGroup,
   warning_mode: DependencyGraphWarningMode
-) -> Result<DependencyGraphInfoWrapper, String> {
+) -> Result<DependencyGraphInfoWrapper, GCMakeError> {
+  let should_validate_includes: bool = matches!(warning_mode, DependencyGraphWarningMode::All);
+
   match DependencyGraph::new_info_from_root(&project_data.root_project, warning_mode) {
     Ok(dep_graph_info) => {
       warn_for_unused_deps(&dep_graph_info);
+
+      if should_validate_includes {
+        warn_for_invalid_includes(&dep_graph_info);
+      }
+
       return Ok(dep_graph_info);
     },
     // TODO: Improve these error messages. Especially figure out how to add a "project stack trace" to
@@ -67,33 +90,48 @@ pub fn load_graph(
       GraphLoadFailureReason::LinkPointsToInvalidOrNonexistentProject { target, project, link_spec } => {
         let borrowed_target  = borrow_target(&target);
 
-        return wrap_error_msg(format!(
-          "Link specifier '{}' from target '{}' in project '{}' points to an invalid or nonexistent project.",
-          link_spec.original_spec_str(),
-          borrowed_target.get_name(),
-          borrow_project(&project).project_debug_name()
+        return wrap_error_msg(located_in_project(
+          &project,
+          format!(
+            "Link specifier '{}' from target '{}' in project '{}' points to an invalid or nonexistent project.",
+            link_spec.original_spec_str(),
+            borrowed_target.get_name(),
+            borrow_project(&project).project_debug_name()
+          ),
+          &["output", borrowed_target.get_name()],
+          Some(link_spec.original_spec_str())
         ));
       },
       GraphLoadFailureReason::LinkNestedNamespaceInOtherProjectContext { target, project, link_spec } => {
         let borrowed_target = borrow_target(&target);
 
-        return wrap_error_msg(format!(
-          "Link specifier '{}' from target '{}' in project '{}' tries to access nested namespaces in a dependency project, which is forbidden.",
-          link_spec.original_spec_str(),
-          borrowed_target.get_name(),
-          borrow_project(&project).project_debug_name()
+        return wrap_error_msg(located_in_project(
+          &project,
+          format!(
+            "Link specifier '{}' from target '{}' in project '{}' tries to access nested namespaces in a dependency project, which is forbidden.",
+            link_spec.original_spec_str(),
+            borrowed_target.get_name(),
+            borrow_project(&project).project_debug_name()
+          ),
+          &["output", borrowed_target.get_name()],
+          Some(link_spec.original_spec_str())
         ));
       },
       GraphLoadFailureReason::LinkTargetNotFound { target, target_container_project, looking_in_project, link_spec, name_searching } => {
         let borrowed_target = borrow_target(&target);
 
-        return wrap_error_msg(format!(
-          "Unable to find target '{}' in project '{}'.\n\tUsing link specifier '{}' from target '{}' in project '{}'.",
-          name_searching,
-          borrow_project(&looking_in_project).project_debug_name(),
-          link_spec.original_spec_str(),
-          borrowed_target.get_name(),
-          borrow_project(&target_container_project).project_debug_name()
+        return wrap_error_msg(located_in_project(
+          &target_container_project,
+          format!(
+            "Unable to find target '{}' in project '{}'.\n\tUsing link specifier '{}' from target '{}' in project '{}'.",
+            name_searching,
+            borrow_project(&looking_in_project).project_debug_name(),
+            link_spec.original_spec_str(),
+            borrowed_target.get_name(),
+            borrow_project(&target_container_project).project_debug_name()
+          ),
+          &["output", borrowed_target.get_name()],
+          Some(link_spec.original_spec_str())
         ));
       },
       GraphLoadFailureReason::DependencyCycle(mut cycle_vec) => {
@@ -403,13 +441,18 @@ pub fn load_graph(
           gcmake_dep_name,
           feature_name_to_enable
         } => {
-          return wrap_error_msg(format!(
-            "Feature '{}' in project [{}] tries to enable a dependency feature '{}/{}'. However, a GCMake dependency project named [{}] doesn't exist.",
-            container_feature_name.yellow(),
-            borrow_project(project).project_debug_name().yellow(),
-            gcmake_dep_name.purple(),
-            feature_name_to_enable.purple(),
-            gcmake_dep_name.yellow()
+          return wrap_error_msg(located_in_project(
+            project,
+            format!(
+              "Feature '{}' in project [{}] tries to enable a dependency feature '{}/{}'. However, a GCMake dependency project named [{}] doesn't exist.",
+              container_feature_name.yellow(),
+              borrow_project(project).project_debug_name().yellow(),
+              gcmake_dep_name.purple(),
+              feature_name_to_enable.purple(),
+              gcmake_dep_name.yellow()
+            ),
+            &["features", &container_feature_name, "enables"],
+            Some(&format!("{}/{}", gcmake_dep_name, feature_name_to_enable))
           ))
         },
         AdditionalConfigValidationFailureReason::FeatureEnablerDependencyFeatureNotFound {
@@ -417,14 +460,37 @@ pub fn load_graph(
           gcmake_dep_name,
           feature_name_to_enable
         } => {
-          return wrap_error_msg(format!(
-            "Feature '{}' in project [{}] tries to enable a dependency feature '{}/{}'. However, the GCMake dependency project [{}] doesn't have a feature called '{}'.",
-            container_feature_name.yellow(),
-            borrow_project(project).project_debug_name().yellow(),
-            gcmake_dep_name.purple(),
-            feature_name_to_enable.purple(),
-            gcmake_dep_name.yellow(),
-            feature_name_to_enable.purple()
+          return wrap_error_msg(located_in_project(
+            project,
+            format!(
+              "Feature '{}' in project [{}] tries to enable a dependency feature '{}/{}'. However, the GCMake dependency project [{}] doesn't have a feature called '{}'.",
+              container_feature_name.yellow(),
+              borrow_project(project).project_debug_name().yellow(),
+              gcmake_dep_name.purple(),
+              feature_name_to_enable.purple(),
+              gcmake_dep_name.yellow(),
+              feature_name_to_enable.purple()
+            ),
+            &["features", &container_feature_name, "enables"],
+            Some(&format!("{}/{}", gcmake_dep_name, feature_name_to_enable))
+          ))
+        },
+        AdditionalConfigValidationFailureReason::DependencyFeatureNotFound {
+          gcmake_dep_name,
+          feature_name
+        } => {
+          return wrap_error_msg(located_in_project(
+            project,
+            format!(
+              "Project [{}] enables feature '{}' of GCMake dependency [{}], but [{}] doesn't have a feature called '{}'.",
+              borrow_project(project).project_debug_name().yellow(),
+              feature_name.purple(),
+              gcmake_dep_name.yellow(),
+              gcmake_dep_name.yellow(),
+              feature_name.purple()
+            ),
+            &["gcmake_dependencies", &gcmake_dep_name, "features"],
+            Some(&feature_name)
           ))
         },
         AdditionalConfigValidationFailureReason::HasCpp2ButMissingCppfrontDependency { } => {
@@ -460,14 +526,16 @@ pub fn load_graph(
   }
 }
 
-fn wrap_error_msg<T>(msg: impl AsRef<str>) -> Result<T, String> {
-  return Err(
+// Every GraphLoadFailureReason is a problem with how the projects in the tree link together, so
+// they all map to GCMakeError::Graph.
+fn wrap_error_msg<T>(msg: impl AsRef<str>) -> Result<T, GCMakeError> {
+  return Err(GCMakeError::Graph(
     format!(
       "\n{}{}",
       "Error loading project graph: ".red(),
       msg.as_ref().to_string()
     )
-  );
+  ));
 }
 
 fn systems_string(system_spec_info: &SystemSpecifierWrapper) -> String {
@@ -1,9 +1,8 @@
 use std::{cell::RefCell, rc::{Rc, Weak}, hash::{Hash, Hasher}, collections::{BTreeMap, BTreeSet, VecDeque}, path::{Path, PathBuf}, iter::FromIterator, cmp::Ordering};
 
-use crate::{project_info::{LinkMode, CompiledOutputItem, PreBuildScript, OutputItemLinks, final_project_data::{FinalProjectData, CodeFileStats}, final_dependencies::{FinalGCMakeDependency, FinalPredefinedDependencyConfig, GCMakeDependencyStatus, FinalRequirementSpecifier, FinalTargetConfig, FinalExternalRequirementSpecifier, FinalPredepInfo}, LinkSpecifier, FinalProjectType, parsers::{link_spec_parser::{LinkAccessMode, LinkSpecTargetList, LinkSpecifierTarget}, system_spec::platform_spec_parser::SystemSpecifierWrapper}, raw_data_in::{dependencies::internal_dep_config::raw_dep_common::RawEmscriptenConfig, OutputItemType}, FinalFeatureEnabler, PreBuildScriptType, SystemSpecExpressionTree, SingleSystemSpec}};
+use crate::{project_info::{LinkMode, CompiledOutputItem, PreBuildScript, OutputItemLinks, final_project_data::{FinalProjectData, CodeFileStats}, final_dependencies::{FinalGCMakeDependency, FinalPredefinedDependencyConfig, GCMakeDependencyStatus, FinalRequirementSpecifier, FinalTargetConfig, FinalExternalRequirementSpecifier, FinalPredepInfo}, LinkSpecifier, FinalProjectType, parsers::{link_spec_parser::{LinkAccessMode, LinkSpecTargetList, LinkSpecifierTarget}, system_spec::platform_spec_parser::SystemSpecifierWrapper}, raw_data_in::{dependencies::internal_dep_config::raw_dep_common::RawEmscriptenConfig, OutputItemType}, FinalFeatureEnabler, PreBuildScriptType, SystemSpecExpressionTree, SingleSystemSpec}, logger::{self, WarningId}};
 
 use super::hash_wrapper::RcRefcHashWrapper;
-use colored::*;
 
 pub struct BasicTargetSearchResult<'a> {
   pub searched_with: String,
@@ -71,6 +70,10 @@ pub enum AdditionalConfigValidationFailureReason<'a> {
     container_feature_name: String,
     gcmake_dep_name: String,
     feature_name_to_enable: String
+  },
+  DependencyFeatureNotFound {
+    gcmake_dep_name: String,
+    feature_name: String
   }
 }
 
@@ -464,6 +467,35 @@ impl<'a> TargetNode<'a> {
     &self.depends_on
   }
 
+  // True when this target links to a target in the given project, either directly or through a
+  // chain of public/interface links. Those are the targets whose public headers this one can use.
+  pub fn transitively_links_project(&self, project_id: ProjectId) -> bool {
+    let mut visited: BTreeSet<TargetId> = BTreeSet::new();
+    let mut to_visit: Vec<Rc<RefCell<TargetNode<'a>>>> = self.depends_on.values()
+      .map(|link| link.linked_target())
+      .collect();
+
+    while let Some(target) = to_visit.pop() {
+      let borrowed_target = target.as_ref().borrow();
+
+      if !visited.insert(borrowed_target.unique_target_id()) {
+        continue;
+      }
+
+      if borrowed_target.container_project_id() == project_id {
+        return true;
+      }
+
+      for link in borrowed_target.depends_on.values() {
+        if link.get_link_mode() != LinkMode::Private {
+          to_visit.push(link.linked_target());
+        }
+      }
+    }
+
+    return false;
+  }
+
   pub fn simple_output_type(&self) -> SimpleNodeOutputType {
     return self.output_type.clone();
   }
@@ -869,6 +901,10 @@ impl<'a> DependencyGraph<'a> {
     self.graph_id
   }
 
+  pub fn project_group_id(&self) -> ProjectGroupId {
+    self.project_group_id.clone()
+  }
+
   pub fn root_project_id(&self) -> usize {
     return self.root_project().as_ref().borrow().graph_id;
   }
@@ -1029,13 +1065,8 @@ impl<'a> DependencyGraph<'a> {
         };
 
         if let DependencyGraphWarningMode::All = warning_mode {
-          println!(
-            "\n{}Platform-specific subset validation at GCMake configuration time has not been implemented yet. However, the correct CMake generator expressions will still be written.",
-            "Warning: ".yellow()
-          );
-
-          println!(
-            "-- target '{}' in project '{}' links to '{}', which has a platform specifier '{}'. Please make sure the link to {} is prefixed with a platform specifier that is a subset of {}. {}",
+          logger::warn(WarningId::UnvalidatedPlatformSpecifier, format!(
+            "Platform-specific subset validation at GCMake configuration time has not been implemented yet. However, the correct CMake generator expressions will still be written.\n-- target '{}' in project '{}' links to '{}', which has a platform specifier '{}'. Please make sure the link to {} is prefixed with a platform specifier that is a subset of {}. {}",
             target.as_ref().borrow().get_name(),
             target.as_ref().borrow().container_project().as_ref().borrow().project_debug_name(),
             dependency.as_ref().borrow().get_yaml_namespaced_target_name(),
@@ -1043,7 +1074,7 @@ impl<'a> DependencyGraph<'a> {
             dependency.as_ref().borrow().get_yaml_namespaced_target_name(),
             dependency_spec_tree.to_string(),
             current_platform_spec_str
-          );
+          ));
         }
       }
 
@@ -1101,23 +1132,39 @@ impl<'a> DependencyGraph<'a> {
                     });
                   }
                   Some(gcmake_dep) => {
-                    if let Some(available_gcmake_dep) = gcmake_dep.as_ref().borrow().project_wrapper().maybe_normal_project() {
-                      if !available_gcmake_dep.get_features().contains_key(feature_name) {
-                        return Err(GraphLoadFailureReason::FailedAdditionalProjectValidation {
-                          project: Weak::upgrade(&self.current_graph_ref).unwrap(),
-                          failure_reason: AdditionalConfigValidationFailureReason::FeatureEnablerDependencyFeatureNotFound {
-                            container_feature_name: container_feature_name.to_string(),
-                            gcmake_dep_name: dep_name_str.to_string(),
-                            feature_name_to_enable: feature_name.to_string()
-                          }
-                        });
-                      }
+                    let has_feature: Option<bool> = gcmake_dep.as_ref().borrow().project_wrapper().maybe_gcmake_dep()
+                      .and_then(|gcmake_dep_info| gcmake_dep_info.has_feature(feature_name));
+
+                    if let Some(false) = has_feature {
+                      return Err(GraphLoadFailureReason::FailedAdditionalProjectValidation {
+                        project: Weak::upgrade(&self.current_graph_ref).unwrap(),
+                        failure_reason: AdditionalConfigValidationFailureReason::FeatureEnablerDependencyFeatureNotFound {
+                          container_feature_name: container_feature_name.to_string(),
+                          gcmake_dep_name: dep_name_str.to_string(),
+                          feature_name_to_enable: feature_name.to_string()
+                        }
+                      });
                     }
                   },
                 }
               }
             }
           }
+
+          // Ensure features requested in each GCMake dependency's 'features' list exist.
+          for (dep_name, gcmake_dep_info) in normal_project_config.get_gcmake_dependencies() {
+            for feature_name in gcmake_dep_info.specified_features() {
+              if let Some(false) = gcmake_dep_info.has_feature(feature_name) {
+                return Err(GraphLoadFailureReason::FailedAdditionalProjectValidation {
+                  project: Weak::upgrade(&self.current_graph_ref).unwrap(),
+                  failure_reason: AdditionalConfigValidationFailureReason::DependencyFeatureNotFound {
+                    gcmake_dep_name: dep_name.to_string(),
+                    feature_name: feature_name.to_string()
+                  }
+                });
+              }
+            }
+          }
         }
       }
 
@@ -2245,6 +2292,20 @@ impl<'a> DependencyGraph<'a> {
   ) -> Result<Link<'a>, GraphLoadFailureReason<'a>> {
     if let ProjectWrapper::GCMakeDependencyRoot(gcmake_dep) = &self._project_wrapper {
       if let GCMakeDependencyStatus::NotDownloaded(_) = gcmake_dep.project_status() {
+        // When the dependency's package manifest is available, only targets it lists can be linked.
+        let is_known_target: bool = gcmake_dep.package_manifest()
+          .map_or(true, |manifest| manifest.targets.contains_key(link_target_spec.get_name()));
+
+        if !is_known_target {
+          return Err(GraphLoadFailureReason::LinkTargetNotFound {
+            target: Rc::clone(link_spec_container_target),
+            link_spec: whole_link_spec.clone(),
+            looking_in_project: Weak::upgrade(&self.current_graph_ref).unwrap(),
+            target_container_project: mut_target_node.container_project(),
+            name_searching: link_target_spec.get_name().to_string()
+          });
+        }
+
         // Targets should be created on the fly.
         let mut target_map = self.targets.borrow_mut();
         let linkable_name: String = gcmake_dep.get_linkable_target_name(link_target_spec.get_name());
@@ -3094,11 +3155,9 @@ impl<'a> DependencyGraph<'a> {
           // Targets are added on the fly during the link assignment step.
           // Links to an unavailable gcmake dependency project may be incorrect,
           // however we have no way of knowing that since the project isn't available
-          // yet. This is the way we "disable checks" until the repo is cloned.
-          // TODO: In the future, allow checks to be done using a 'predefined gcmake dependency'
-          // document in the project-local .gcmake/ dir. Essentially, this would be a small yaml
-          // file which describes the dependency information and lists its targets, just like
-          // the regular predefined dependency files.
+          // yet. This is the way we "disable checks" until the repo is cloned, unless the
+          // dependency's package manifest was copied into the project-local .gcmake/ dir.
+          // In that case, link names are checked against the targets the manifest lists.
           targets: RefCell::new(BTreeMap::new())
         }));
 
@@ -1,3 +1,3 @@
 
 pub mod dependency_graph;
-mod hash_wrapper;
\ No newline at end of file
+pub mod hash_wrapper;
\ No newline at end of file
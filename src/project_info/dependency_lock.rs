@@ -0,0 +1,147 @@
+use std::{collections::BTreeMap, fs, io, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+
+use super::{final_dependencies::{FinalGitRepoDescriptor, FinalUrlDownloadDescriptor, GitRevisionSpecifier}, gcmake_constants::DEPENDENCY_LOCK_FILE_NAME};
+
+const LOCK_FILE_HEADER: &'static str = "# Generated by gcmake. Refresh with 'gcmake lock --update [dep names]'.\n";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LockedGitRevision {
+  pub repo_url: String,
+  // The git_tag or commit_hash given in cmake_data.yaml when the revision was resolved.
+  pub revision: String,
+  pub commit_hash: String
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct LockedUrlArchive {
+  pub windows_url: String,
+  pub windows_sha256: String,
+  pub unix_url: String,
+  pub unix_sha256: String
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+pub enum LockedDependency {
+  Git(LockedGitRevision),
+  Url(LockedUrlArchive)
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct DependencyLockfile {
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub predefined_dependencies: BTreeMap<String, LockedDependency>,
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub gcmake_dependencies: BTreeMap<String, LockedDependency>
+}
+
+pub fn revision_string(revision: &GitRevisionSpecifier) -> &str {
+  return match revision {
+    GitRevisionSpecifier::Tag(tag) => tag,
+    GitRevisionSpecifier::CommitHash(commit_hash) => commit_hash
+  }
+}
+
+impl LockedDependency {
+  // Lock entries are only used while the dependency's configuration still matches what was locked.
+  // Changing the repo URL, tag or file_version in cmake_data.yaml makes the entry stale.
+  pub fn locked_commit_for(&self, git_info: &FinalGitRepoDescriptor) -> Option<&str> {
+    return match self {
+      Self::Git(locked) if locked.repo_url == git_info.repo_url && locked.revision == revision_string(&git_info.revision_specifier) => {
+        Some(&locked.commit_hash)
+      },
+      _ => None
+    }
+  }
+
+  pub fn locked_archive_for(&self, url_info: &FinalUrlDownloadDescriptor) -> Option<&LockedUrlArchive> {
+    return match self {
+      Self::Url(locked) if locked.windows_url == url_info.windows_url() && locked.unix_url == url_info.unix_url() => {
+        Some(locked)
+      },
+      _ => None
+    }
+  }
+}
+
+impl DependencyLockfile {
+  pub fn file_path(project_root: impl AsRef<Path>) -> PathBuf {
+    return project_root.as_ref().join(DEPENDENCY_LOCK_FILE_NAME);
+  }
+
+  pub fn load(project_root: impl AsRef<Path>) -> Result<Option<Self>, String> {
+    let lock_file_path: PathBuf = Self::file_path(project_root);
+
+    if !lock_file_path.is_file() {
+      return Ok(None);
+    }
+
+    let file_contents: String = fs::read_to_string(&lock_file_path)
+      .map_err(|err| format!("Failed to read {}: {}", lock_file_path.to_str().unwrap(), err.to_string()))?;
+
+    return serde_yaml::from_str(&file_contents)
+      .map(Some)
+      .map_err(|err| format!("Failed to parse {}: {}", lock_file_path.to_str().unwrap(), err.to_string()));
+  }
+
+  pub fn write(&self, project_root: impl AsRef<Path>) -> io::Result<()> {
+    let serialized: String = serde_yaml::to_string(self)
+      .map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+
+    return fs::write(Self::file_path(project_root), format!("{}{}", LOCK_FILE_HEADER, serialized));
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn git_descriptor(repo_url: &str, tag: &str) -> FinalGitRepoDescriptor {
+    FinalGitRepoDescriptor {
+      repo_url: repo_url.to_string(),
+      revision_specifier: GitRevisionSpecifier::Tag(tag.to_string())
+    }
+  }
+
+  #[test]
+  fn test_locked_commit_is_only_used_while_config_matches() {
+    let locked = LockedDependency::Git(LockedGitRevision {
+      repo_url: String::from("https://example.com/lib.git"),
+      revision: String::from("v1.0.0"),
+      commit_hash: String::from("0123456789abcdef0123456789abcdef01234567")
+    });
+
+    assert_eq!(
+      locked.locked_commit_for(&git_descriptor("https://example.com/lib.git", "v1.0.0")),
+      Some("0123456789abcdef0123456789abcdef01234567")
+    );
+    assert_eq!(locked.locked_commit_for(&git_descriptor("https://example.com/lib.git", "v1.1.0")), None);
+    assert_eq!(locked.locked_commit_for(&git_descriptor("https://example.com/fork.git", "v1.0.0")), None);
+  }
+
+  #[test]
+  fn test_lockfile_round_trips_both_entry_kinds() {
+    let mut lockfile = DependencyLockfile::default();
+
+    lockfile.gcmake_dependencies.insert(String::from("some-lib"), LockedDependency::Git(LockedGitRevision {
+      repo_url: String::from("file:///tmp/some-lib"),
+      revision: String::from("main"),
+      commit_hash: String::from("0123456789abcdef0123456789abcdef01234567")
+    }));
+
+    lockfile.predefined_dependencies.insert(String::from("nlohmann_json"), LockedDependency::Url(LockedUrlArchive {
+      windows_url: String::from("https://example.com/json.zip"),
+      windows_sha256: String::from("aa"),
+      unix_url: String::from("https://example.com/json.tar.xz"),
+      unix_sha256: String::from("bb")
+    }));
+
+    let reparsed: DependencyLockfile = serde_yaml::from_str(&serde_yaml::to_string(&lockfile).unwrap()).unwrap();
+    assert_eq!(reparsed, lockfile);
+  }
+}
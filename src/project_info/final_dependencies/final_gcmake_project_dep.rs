@@ -1,6 +1,6 @@
-use std::{rc::Rc, collections::BTreeSet};
+use std::{rc::Rc, collections::BTreeSet, path::{Path, PathBuf}};
 
-use crate::project_info::{final_project_data::FinalProjectData, raw_data_in::dependencies::{user_given_dep_config::UserGivenGCMakeProjectDependency}};
+use crate::project_info::{final_project_data::FinalProjectData, package_manifest::PackageManifest, raw_data_in::dependencies::{user_given_dep_config::UserGivenGCMakeProjectDependency}};
 
 use super::{FinalGitRepoDescriptor, GitRevisionSpecifier};
 
@@ -22,73 +22,138 @@ pub struct GCMakeDepIDHash {
   pub relative_hash_file: String
 }
 
+pub enum GCMakeDependencySource {
+  GitRepo(FinalGitRepoDescriptor),
+  // Path to a GCMake project checked out on this machine, usually next to the dependent project.
+  // Relative paths are relative to the dependent project's root.
+  LocalPath(PathBuf)
+}
+
+impl GCMakeDependencySource {
+  pub fn from_given_config(
+    dep_name: &str,
+    given_config: &UserGivenGCMakeProjectDependency
+  ) -> Result<Self, String> {
+    let specifies_revision: bool = given_config.git_tag.is_some() || given_config.commit_hash.is_some();
+
+    return match (&given_config.repo_url, &given_config.path) {
+      (Some(_), Some(_)) => Err(format!(
+        "GCMake dependency '{}' specifies both a repo_url and a path. Only one of them should be given.",
+        dep_name
+      )),
+      (None, None) => Err(format!(
+        "GCMake dependency '{}' must specify either a repo_url or a path.",
+        dep_name
+      )),
+      (None, Some(_)) if specifies_revision => Err(format!(
+        "GCMake dependency '{}' is loaded from a local path, so it can't specify a git_tag or commit_hash.",
+        dep_name
+      )),
+      (None, Some(local_path)) => Ok(Self::LocalPath(PathBuf::from(local_path))),
+      (Some(repo_url), None) => {
+        let revision_specifier: GitRevisionSpecifier = if let Some(tag_string) = &given_config.git_tag {
+          GitRevisionSpecifier::Tag(tag_string.clone())
+        }
+        else if let Some(hash_string) = &given_config.commit_hash {
+          GitRevisionSpecifier::CommitHash(hash_string.clone())
+        }
+        else {
+          return Err(format!("Must specify either a commit_hash or git_tag for dependency '{}'", dep_name));
+        };
+
+        Ok(Self::GitRepo(FinalGitRepoDescriptor {
+          repo_url: repo_url.clone(),
+          revision_specifier
+        }))
+      }
+    }
+  }
+}
+
 pub struct FinalGCMakeDependency {
   name: String,
-  git_repo: FinalGitRepoDescriptor,
+  source: GCMakeDependencySource,
   dep_project_status: GCMakeDependencyStatus,
   use_default_features: bool,
   features: BTreeSet<String>,
-  hash_info: GCMakeDepIDHash
+  // Only git dependencies are downloaded into the dependency cache, so local path dependencies
+  // don't need a hash to identify them.
+  hash_info: Option<GCMakeDepIDHash>,
+  // Used in place of the project until the dependency is downloaded.
+  package_manifest: Option<PackageManifest>
 }
 
 impl FinalGCMakeDependency {
   pub fn new(
     dep_name: &str,
     given_config: &UserGivenGCMakeProjectDependency,
-    unique_hash: String,
-    maybe_associated_project: Option<Rc<FinalProjectData>>
-  ) -> Result<Self, String> {
-    let download_specifier: GitRevisionSpecifier = if let Some(tag_string) = &given_config.git_tag {
-      GitRevisionSpecifier::Tag(tag_string.clone())
-    }
-    else if let Some(hash_string) = &given_config.commit_hash {
-      GitRevisionSpecifier::CommitHash(hash_string.clone())
-    }
-    else {
-      return Err(format!("Must specify either a commit_hash or git_tag for dependency '{}'", dep_name));
-    };
-
-    return Ok(Self {
+    source: GCMakeDependencySource,
+    unique_hash: Option<String>,
+    maybe_associated_project: Option<Rc<FinalProjectData>>,
+    maybe_package_manifest: Option<PackageManifest>
+  ) -> Self {
+    return Self {
       name: dep_name.to_string(),
-      git_repo: FinalGitRepoDescriptor {
-        repo_url: given_config.repo_url.clone(),
-        revision_specifier: download_specifier
-      },
+      source,
       dep_project_status: match maybe_associated_project {
         Some(project_info) => GCMakeDependencyStatus::Available(project_info),
-        None => GCMakeDependencyStatus::NotDownloaded(dep_name.to_string())
+        // The manifest gives the real project name, so targets can be namespaced correctly
+        // before the dependency is downloaded.
+        None => GCMakeDependencyStatus::NotDownloaded(
+          maybe_package_manifest.as_ref()
+            .map_or(dep_name.to_string(), |manifest| manifest.name.clone())
+        )
       },
       use_default_features: given_config.use_default_features.unwrap_or(true),
-      hash_info: GCMakeDepIDHash {
-        hash_string: unique_hash,
+      hash_info: unique_hash.map(|hash_string| GCMakeDepIDHash {
+        hash_string,
         relative_hash_file: relative_hash_file_path()
-      },
+      }),
       features: given_config.features.clone()
-        .map_or(BTreeSet::default(), |feature_set| feature_set.into_iter().collect())
-    })
+        .map_or(BTreeSet::default(), |feature_set| feature_set.into_iter().collect()),
+      package_manifest: maybe_package_manifest
+    }
   }
 
   pub fn given_dependency_name(&self) -> &str {
     &self.name
   }
 
-  pub fn get_hash_info(&self) -> &GCMakeDepIDHash {
-    &self.hash_info
+  pub fn get_hash_info(&self) -> Option<&GCMakeDepIDHash> {
+    self.hash_info.as_ref()
   }
 
   pub fn project_base_name(&self) -> &str {
     match self.project_status() {
-      GCMakeDependencyStatus::NotDownloaded(_) => self.given_dependency_name(),
+      GCMakeDependencyStatus::NotDownloaded(placeholder_name) => placeholder_name,
       GCMakeDependencyStatus::Available(project) => project.get_project_base_name()
     }
   }
 
-  pub fn repo_url(&self) -> &str {
-    &self.git_repo.repo_url
+  pub fn source(&self) -> &GCMakeDependencySource {
+    &self.source
+  }
+
+  pub fn git_repo(&self) -> Option<&FinalGitRepoDescriptor> {
+    return match &self.source {
+      GCMakeDependencySource::GitRepo(git_repo) => Some(git_repo),
+      GCMakeDependencySource::LocalPath(_) => None
+    }
+  }
+
+  pub fn local_path(&self) -> Option<&Path> {
+    return match &self.source {
+      GCMakeDependencySource::LocalPath(local_path) => Some(local_path),
+      GCMakeDependencySource::GitRepo(_) => None
+    }
+  }
+
+  pub fn repo_url(&self) -> Option<&str> {
+    self.git_repo().map(|git_repo| git_repo.repo_url.as_str())
   }
 
-  pub fn revision(&self) -> &GitRevisionSpecifier {
-    &self.git_repo.revision_specifier
+  pub fn revision(&self) -> Option<&GitRevisionSpecifier> {
+    self.git_repo().map(|git_repo| &git_repo.revision_specifier)
   }
 
   pub fn is_using_default_features(&self) -> bool {
@@ -99,6 +164,20 @@ impl FinalGCMakeDependency {
     &self.features
   }
 
+  pub fn package_manifest(&self) -> Option<&PackageManifest> {
+    self.package_manifest.as_ref()
+  }
+
+  // None when the dependency's features can't be known yet, meaning the dependency isn't downloaded
+  // and has no package manifest.
+  pub fn has_feature(&self, feature_name: &str) -> Option<bool> {
+    return match self.project_status() {
+      GCMakeDependencyStatus::Available(project_info) => Some(project_info.get_features().contains_key(feature_name)),
+      GCMakeDependencyStatus::NotDownloaded(_) => self.package_manifest()
+        .map(|manifest| manifest.features.contains_key(feature_name))
+    }
+  }
+
   pub fn project_status(&self) -> &GCMakeDependencyStatus {
     &self.dep_project_status
   }
@@ -114,7 +193,8 @@ impl FinalGCMakeDependency {
     return match self.project_status() {
       // Use the least permissive mode until the actual state is known. This is kind of a hard
       // edge, and would be fixed if GCMake had some sort of package registry.
-      GCMakeDependencyStatus::NotDownloaded(_) => false,
+      GCMakeDependencyStatus::NotDownloaded(_) => self.package_manifest()
+        .map_or(false, |manifest| manifest.can_trivially_cross_compile),
       GCMakeDependencyStatus::Available(available_gcmake_dep) => available_gcmake_dep.can_trivially_cross_compile()
     }
   }
@@ -124,7 +204,8 @@ impl FinalGCMakeDependency {
       // GCMake will fail with an error if Emscripten is listed in a project's supported compilers but the
       // project itself doesn't support Emscripten. Since the actual Emscripten support status is unknown
       // for a not-yet-downloaded dependency, return true so that the error is not thrown incorrectly.
-      GCMakeDependencyStatus::NotDownloaded(_) => true,
+      GCMakeDependencyStatus::NotDownloaded(_) => self.package_manifest()
+        .map_or(true, |manifest| manifest.supports_emscripten),
       GCMakeDependencyStatus::Available(available_gcmake_dep) => available_gcmake_dep.supports_emscripten()
     }
   }
@@ -144,3 +225,34 @@ impl FinalGCMakeDependency {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn given_config(repo_url: Option<&str>, path: Option<&str>, git_tag: Option<&str>) -> UserGivenGCMakeProjectDependency {
+    UserGivenGCMakeProjectDependency {
+      git_tag: git_tag.map(String::from),
+      commit_hash: None,
+      repo_url: repo_url.map(String::from),
+      path: path.map(String::from),
+      use_default_features: None,
+      features: None
+    }
+  }
+
+  #[test]
+  fn test_dependency_source_requires_exactly_one_location() {
+    assert!(matches!(
+      GCMakeDependencySource::from_given_config("dep", &given_config(None, Some("../dep"), None)),
+      Ok(GCMakeDependencySource::LocalPath(_))
+    ));
+    assert!(matches!(
+      GCMakeDependencySource::from_given_config("dep", &given_config(Some("file:///tmp/dep"), None, Some("v1.0.0"))),
+      Ok(GCMakeDependencySource::GitRepo(_))
+    ));
+    assert!(GCMakeDependencySource::from_given_config("dep", &given_config(None, None, None)).is_err());
+    assert!(GCMakeDependencySource::from_given_config("dep", &given_config(Some("file:///tmp/dep"), Some("../dep"), None)).is_err());
+    assert!(GCMakeDependencySource::from_given_config("dep", &given_config(None, Some("../dep"), Some("v1.0.0"))).is_err());
+  }
+}
@@ -26,6 +26,8 @@ pub struct FinalUrlExtensions {
 
 #[derive(Clone)]
 pub struct FinalUrlDownloadDescriptor {
+  // The file_version given by the user, before any version_transform is applied.
+  pub version: ThreePartVersion,
   pub url_without_extension: String,
   pub extension: FinalUrlExtensions
 }
@@ -237,6 +239,7 @@ fn resolve_download_method(
               )?;
 
             return Ok(FinalDownloadMethod::UrlMode(FinalUrlDownloadDescriptor {
+              version: parsed_version,
               // <baseUrl><transformedVersion>
               url_without_extension: format!(
                 "{}{}",
@@ -4,6 +4,7 @@ mod final_gcmake_project_dep;
 mod final_predefined_cmake_module_dep;
 mod predep_module_common;
 mod final_target_map_common;
+mod predep_config_validator;
 
 use std::{rc::Rc, collections::HashMap};
 
@@ -13,6 +14,7 @@ pub use final_gcmake_project_dep::*;
 pub use final_predefined_cmake_module_dep::*;
 pub use final_target_map_common::{FinalRequirementSpecifier, FinalTargetConfig, FinalExternalRequirementSpecifier};
 pub use predep_module_common::{PredefinedDepFunctionality, FinalDebianPackagesConfig};
+pub use predep_config_validator::validate_predefined_dep_config;
 
 use crate::project_info::{platform_spec_parser::parse_leading_constraint_spec, parsers::general_parser::ParseSuccess, GivenConstraintSpecParseContext};
 
@@ -117,74 +119,12 @@ impl FinalPredefinedDependencyConfig {
       ))
     };
 
-    for (target_name, target_config) in predep_info.target_config_map() {
-      for external_requirement in &target_config.external_requirements_set {
-        match external_requirement {
-          FinalExternalRequirementSpecifier::OneOf(link_spec_list) => {
-            // Each link specifier here is guaranteed to only contain a single namespace and a single library.
-            for link_spec in link_spec_list {
-              assert!(
-                link_spec.get_target_list().len() == 1,
-                "An external link specifier should be guaranteed to only specify one library."
-              );
-
-              assert!(
-                link_spec.get_namespace_queue().len() == 1,
-                "An external link specifier should be guaranteed to have no nested namespaces."
-              );
-            
-              let the_namespace: &str = link_spec.get_namespace_queue().iter().nth(0).unwrap();
-              let required_lib_name: &str = link_spec.get_target_list().iter().nth(0).unwrap().get_name();
-
-              match all_raw_dep_configs.get(the_namespace)? {
-                None => {
-                  return Err(format!(
-                    "The external dependency '{}' of target '{}::{}' requires a library from predefined dependency '{}', however there is no predefined dependency named '{}'.",
-                    link_spec.original_spec_str(),
-                    dep_name,
-                    target_name,
-                    the_namespace,
-                    the_namespace
-                  ));
-                },
-                Some(raw_predep_config) => {
-                  let mut has_matching_target_name: bool = false;
-
-                  for (unparsed_target_name, _) in raw_predep_config.dep_configs.get_common()?.raw_target_map_in() {
-                    let parsing_context = GivenConstraintSpecParseContext {
-                      is_before_output_name: false,
-                      maybe_valid_feature_list: valid_feature_list
-                    };
-
-                    let raw_target_name: &str = match parse_leading_constraint_spec(unparsed_target_name, parsing_context)? {
-                      None => unparsed_target_name,
-                      Some(ParseSuccess { value: _, rest }) => rest
-                    };
-
-                    if raw_target_name == required_lib_name {
-                      has_matching_target_name = true;
-                      break;
-                    }
-                  }
-
-                  if !has_matching_target_name {
-                    return Err(format!(
-                      "The external dependency '{}' of target '{}::{}' requires a library from predefined dependency '{}', but '{}' doesn't have a target named '{}'",
-                      link_spec.original_spec_str(),
-                      dep_name,
-                      target_name,
-                      the_namespace,
-                      the_namespace,
-                      required_lib_name
-                    ));
-                  }
-                }
-              }
-            }
-          }
-        }
-      }
-    }
+    verify_external_requirements(
+      all_raw_dep_configs,
+      dep_name,
+      predep_info.target_config_map(),
+      valid_feature_list
+    )?;
 
     let RawPredefinedDependencyInfo {
       pre_load,
@@ -194,27 +134,10 @@ impl FinalPredefinedDependencyConfig {
       ..
     } = all_raw_dep_configs.get(dep_name)?.unwrap(); 
 
-    match &predep_info {
-      FinalPredepInfo::CMakeModule(module_dep) => match (module_dep.module_type(), custom_find_module) {
-        (CMakeModuleType::CustomFindModule, None) => {
-          return Err(format!(
-            "Predefined dependency '{}' must have an associated custom \"Find Module\" cmake file, but one doesn't exist.",
-            dep_name
-          ));
-        },
-        (CMakeModuleType::BuiltinFindModule, Some(finder_file)) => {
-          return Err(format!(
-            "Predefined dependency '{}' has an associated custom \"Find Module\" cmake file '{}', but is configured as a \"BuildinFindModule\" dependency. To use the custom find module, change the configuration for '{}' to the \"CustomFindModule\" type.",
-            dep_name,
-            finder_file.file_path.file_name().unwrap().to_str().unwrap(),
-            dep_name
-          ));
-        },
-        _ => ()
-      },
-      _ => ()
+    if let FinalPredepInfo::CMakeModule(module_dep) = &predep_info {
+      verify_custom_find_module(dep_name, module_dep.module_type(), custom_find_module)?;
     }
-    
+
     return Ok(Self {
       name: dep_name.to_string(),
       predep_info,
@@ -325,6 +248,110 @@ impl FinalPredefinedDependencyConfig {
   }
 }
 
+// Checks that every external requirement references a target which exists in another
+// predefined dependency.
+fn verify_external_requirements(
+  all_raw_dep_configs: &RawPredefinedDependencyMap,
+  dep_name: &str,
+  target_config_map: &FinalTargetConfigMap,
+  valid_feature_list: Option<&Vec<&str>>
+) -> Result<(), String> {
+  for (target_name, target_config) in target_config_map {
+    for external_requirement in &target_config.external_requirements_set {
+      match external_requirement {
+        FinalExternalRequirementSpecifier::OneOf(link_spec_list) => {
+          // Each link specifier here is guaranteed to only contain a single namespace and a single library.
+          for link_spec in link_spec_list {
+            assert!(
+              link_spec.get_target_list().len() == 1,
+              "An external link specifier should be guaranteed to only specify one library."
+            );
+
+            assert!(
+              link_spec.get_namespace_queue().len() == 1,
+              "An external link specifier should be guaranteed to have no nested namespaces."
+            );
+          
+            let the_namespace: &str = link_spec.get_namespace_queue().iter().nth(0).unwrap();
+            let required_lib_name: &str = link_spec.get_target_list().iter().nth(0).unwrap().get_name();
+
+            match all_raw_dep_configs.get(the_namespace)? {
+              None => {
+                return Err(format!(
+                  "The external dependency '{}' of target '{}::{}' requires a library from predefined dependency '{}', however there is no predefined dependency named '{}'.",
+                  link_spec.original_spec_str(),
+                  dep_name,
+                  target_name,
+                  the_namespace,
+                  the_namespace
+                ));
+              },
+              Some(raw_predep_config) => {
+                let mut has_matching_target_name: bool = false;
+
+                for (unparsed_target_name, _) in raw_predep_config.dep_configs.get_common()?.raw_target_map_in() {
+                  let parsing_context = GivenConstraintSpecParseContext {
+                    is_before_output_name: false,
+                    maybe_valid_feature_list: valid_feature_list
+                  };
+
+                  let raw_target_name: &str = match parse_leading_constraint_spec(unparsed_target_name, parsing_context)? {
+                    None => unparsed_target_name,
+                    Some(ParseSuccess { value: _, rest }) => rest
+                  };
+
+                  if raw_target_name == required_lib_name {
+                    has_matching_target_name = true;
+                    break;
+                  }
+                }
+
+                if !has_matching_target_name {
+                  return Err(format!(
+                    "The external dependency '{}' of target '{}::{}' requires a library from predefined dependency '{}', but '{}' doesn't have a target named '{}'",
+                    link_spec.original_spec_str(),
+                    dep_name,
+                    target_name,
+                    the_namespace,
+                    the_namespace,
+                    required_lib_name
+                  ));
+                }
+              }
+            }
+          }
+        }
+      }
+    }
+  }
+
+  return Ok(());
+}
+
+fn verify_custom_find_module(
+  dep_name: &str,
+  module_type: &CMakeModuleType,
+  custom_find_module: &HookScriptContainer
+) -> Result<(), String> {
+  return match (module_type, custom_find_module) {
+    (CMakeModuleType::CustomFindModule, None) => {
+      Err(format!(
+        "Predefined dependency '{}' must have an associated custom \"Find Module\" cmake file, but one doesn't exist.",
+        dep_name
+      ))
+    },
+    (CMakeModuleType::BuiltinFindModule, Some(finder_file)) => {
+      Err(format!(
+        "Predefined dependency '{}' has an associated custom \"Find Module\" cmake file '{}', but is configured as a \"BuildinFindModule\" dependency. To use the custom find module, change the configuration for '{}' to the \"CustomFindModule\" type.",
+        dep_name,
+        finder_file.file_path.file_name().unwrap().to_str().unwrap(),
+        dep_name
+      ))
+    },
+    _ => Ok(())
+  }
+}
+
 struct PredefinedDependencyAllConfigs {
   as_subdirectory: Option<PredefinedSubdirDep>,
   components_built_in_find_module: Option<PredefinedCMakeComponentsModuleDep>,
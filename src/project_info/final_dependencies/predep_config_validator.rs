@@ -0,0 +1,124 @@
+use colored::Colorize;
+
+use crate::project_info::{raw_data_in::dependencies::{RawPredefinedDependencyMap, internal_dep_config::{RawPredefinedDependencyInfo, RawSubdirectoryDependency, raw_dep_common::RawPredepCommon}}, parsers::{version_parser::ThreePartVersion, version_transform_parser::transform_version}};
+
+use super::{final_target_map_common::make_final_target_config_map, verify_external_requirements, verify_custom_find_module};
+
+// Validates a predefined dependency configuration without needing a project which uses it.
+// Unlike loading the dependency for a project, this collects every problem found instead of
+// stopping at the first one.
+pub fn validate_predefined_dep_config(
+  all_raw_dep_configs: &RawPredefinedDependencyMap,
+  dep_name: &str
+) -> Vec<String> {
+  // Loading the config also parses dep_config.yaml and reads all the hook files.
+  let raw_dep_info: &RawPredefinedDependencyInfo = match all_raw_dep_configs.get(dep_name) {
+    Ok(Some(raw_dep_info)) => raw_dep_info,
+    Ok(None) => return vec![format!("There is no predefined dependency named '{}'.", dep_name.yellow())],
+    Err(err_msg) => return vec![err_msg]
+  };
+
+  let mut errors: Vec<String> = Vec::new();
+  let dep_configs = &raw_dep_info.dep_configs;
+
+  let configs_by_type: [(&str, Option<&dyn RawPredepCommon>); 3] = [
+    ("as_subdirectory", dep_configs.as_subdirectory.as_ref().map(|config| config as &dyn RawPredepCommon)),
+    ("cmake_components_module", dep_configs.cmake_components_module.as_ref().map(|config| config as &dyn RawPredepCommon)),
+    ("cmake_module", dep_configs.cmake_module.as_ref().map(|config| config as &dyn RawPredepCommon))
+  ];
+
+  if configs_by_type.iter().all(|(_, maybe_config)| maybe_config.is_none()) {
+    errors.push(format!(
+      "Predefined dependency '{}' doesn't contain any configuration. Expected at least one of {}.",
+      dep_name.yellow(),
+      configs_by_type.iter().map(|(config_type, _)| *config_type).collect::<Vec<&str>>().join(", ")
+    ));
+  }
+
+  for (config_type, maybe_config) in &configs_by_type {
+    if let Some(config) = maybe_config {
+      // Checks target names, 'requires' references and mutual exclusion sets.
+      match make_final_target_config_map(dep_name, *config, None) {
+        Err(err_msg) => errors.push(in_config_type(config_type, err_msg)),
+        Ok(target_config_map) => {
+          if let Err(err_msg) = verify_external_requirements(all_raw_dep_configs, dep_name, &target_config_map, None) {
+            errors.push(in_config_type(config_type, err_msg));
+          }
+        }
+      }
+    }
+  }
+
+  if let Some(module_dep) = &dep_configs.cmake_module {
+    if let Err(err_msg) = verify_custom_find_module(dep_name, &module_dep.module_type, &raw_dep_info.custom_find_module) {
+      errors.push(in_config_type("cmake_module", err_msg));
+    }
+  }
+
+  if let Some(subdir_dep) = &dep_configs.as_subdirectory {
+    errors.extend(
+      validate_download_info(dep_name, subdir_dep)
+        .into_iter()
+        .map(|err_msg| in_config_type("as_subdirectory", err_msg))
+    );
+  }
+
+  return errors;
+}
+
+fn in_config_type(config_type: &str, err_msg: String) -> String {
+  return format!("In {} configuration: {}", config_type.cyan(), err_msg);
+}
+
+fn validate_download_info(
+  dep_name: &str,
+  subdir_dep: &RawSubdirectoryDependency
+) -> Vec<String> {
+  let mut errors: Vec<String> = Vec::new();
+  let download_info = &subdir_dep.download_info;
+
+  if download_info.git_method.is_none() && download_info.url_method.is_none() {
+    errors.push(format!(
+      "Predefined dependency '{}' supports no download methods. Either git_method or url_method must be specified.",
+      dep_name.yellow()
+    ));
+  }
+
+  if let Some(url_info) = &download_info.url_method {
+    if url_info.url_base.is_none() && url_info.url_base_by_version.is_none() {
+      errors.push(format!(
+        "Predefined dependency '{}' url_method must specify either url_base or url_base_by_version.",
+        dep_name.yellow()
+      ));
+    }
+
+    if let Some(url_map) = &url_info.url_base_by_version {
+      let mut version_keys: Vec<&String> = url_map.keys().collect();
+      version_keys.sort();
+
+      for version_str_key in version_keys {
+        if ThreePartVersion::from_str(version_str_key).is_none() {
+          errors.push(format!(
+            "Invalid version key \"{}\" in url_base_by_version of predefined dependency '{}'.",
+            version_str_key.red(),
+            dep_name.yellow()
+          ));
+        }
+      }
+    }
+
+    // The transform string can only be fully checked by transforming an actual version.
+    let sample_version: ThreePartVersion = ThreePartVersion::from_str("1.2.3").unwrap();
+
+    if let Err(err_msg) = transform_version(&sample_version, &url_info.version_transform) {
+      errors.push(format!(
+        "Invalid version_transform \"{}\" in predefined dependency '{}':\n{}",
+        url_info.version_transform,
+        dep_name.yellow(),
+        err_msg
+      ));
+    }
+  }
+
+  return errors;
+}
@@ -1,8 +1,8 @@
 use std::{collections::{HashMap, HashSet, BTreeMap, BTreeSet}, path::{Path, PathBuf}, io, rc::Rc, fs::{self}, iter::FromIterator};
 
-use crate::{project_info::path_manipulation::cleaned_pathbuf, logger, program_actions::gcmake_dep_cache_dir, common::base64_encoded};
+use crate::{project_info::path_manipulation::cleaned_pathbuf, logger::{self, WarningId}, program_actions::gcmake_dep_cache_dir, common::base64_encoded};
 
-use super::{path_manipulation::{cleaned_path_str, file_relative_to_dir, absolute_path}, final_dependencies::{FinalGCMakeDependency, FinalPredefinedDependencyConfig, relative_hash_file_path}, raw_data_in::{dependencies::RawPredefinedDependencyMap, BuildConfigCompilerSpecifier, BuildType, DefaultCompiledLibType, LanguageConfigMap, LanguageFeatureSection, LinkSection, OutputItemType, PreBuildConfigIn, RawCompiledItem, RawDocGeneratorName, RawDocumentationGeneratorConfig, RawProject, RawTestFramework, SpecificCompilerSpecifier, TargetSpecificBuildType}, final_project_configurables::FinalProjectType, CompiledOutputItem, helpers::{parse_subproject_data, parse_root_project_data, find_prebuild_script, PrebuildScriptFile, validate_raw_project_outputs, ProjectOutputType, RetrievedCodeFileType, code_file_type, parse_test_project_data, find_doxyfile_in, validate_doxyfile_in, SphinxConfigFiles, find_sphinx_files, validate_conf_py_in}, PreBuildScript, FinalTestFramework, base_include_prefix_for_test, gcmake_constants::{SRC_DIR_NAME, INCLUDE_DIR_NAME, TESTS_DIR_NAME, SUBPROJECTS_DIR_NAME, DOCS_DIR_NAME}, FinalInstallerConfig, CompilerDefine, FinalBuildConfigMap, make_final_build_config_map, FinalTargetBuildConfigMap, FinalGlobalProperties, FinalShortcutConfig, parsers::{version_parser::ThreePartVersion, general_parser::ParseSuccess}, platform_spec_parser::parse_leading_constraint_spec, SystemSpecifierWrapper, FinalFeatureConfig, FinalFeatureEnabler, CodeFileInfo, FileRootGroup, PreBuildScriptType, FinalDocGeneratorName, FinalDocumentationInfo, CodeFileLang, GivenConstraintSpecParseContext};
+use super::{path_manipulation::{cleaned_path_str, file_relative_to_dir, absolute_path, unix_style}, final_dependencies::{FinalGCMakeDependency, FinalPredefinedDependencyConfig, GCMakeDependencySource, GCMakeDependencyStatus, relative_hash_file_path}, raw_data_in::{dependencies::RawPredefinedDependencyMap, BuildConfigCompilerSpecifier, BuildType, DefaultCompiledLibType, LanguageConfigMap, LanguageFeatureSection, LinkSection, OutputItemType, PreBuildConfigIn, RawCompiledItem, RawDocGeneratorName, RawDocumentationGeneratorConfig, RawProject, RawTestFramework, RawWarningConfig, SpecificCompilerSpecifier, TargetSpecificBuildType}, final_project_configurables::FinalProjectType, CompiledOutputItem, helpers::{parse_subproject_data, parse_root_project_data, find_prebuild_script, PrebuildScriptFile, validate_raw_project_outputs, ProjectOutputType, RetrievedCodeFileType, code_file_type, parse_test_project_data, find_doxyfile_in, validate_doxyfile_in, SphinxConfigFiles, find_sphinx_files, validate_conf_py_in}, PreBuildScript, FinalTestFramework, base_include_prefix_for_test, gcmake_constants::{SRC_DIR_NAME, INCLUDE_DIR_NAME, TESTS_DIR_NAME, SUBPROJECTS_DIR_NAME, DOCS_DIR_NAME, PROJECT_LOCAL_DEP_CONFIGS_DIR}, FinalInstallerConfig, CompilerDefine, FinalBuildConfigMap, make_final_build_config_map, FinalTargetBuildConfigMap, FinalGlobalProperties, FinalShortcutConfig, parsers::{version_parser::ThreePartVersion, general_parser::ParseSuccess}, platform_spec_parser::parse_leading_constraint_spec, SystemSpecifierWrapper, FinalFeatureConfig, FinalFeatureEnabler, CodeFileInfo, FileRootGroup, PreBuildScriptType, FinalDocGeneratorName, FinalDocumentationInfo, CodeFileLang, GivenConstraintSpecParseContext, dependency_lock::DependencyLockfile, package_manifest::PackageManifest, yaml_diagnostics::{YamlDiagnostic, YamlSourceFile}};
 use colored::*;
 
 const SUBPROJECT_JOIN_STR: &'static str = "_S_";
@@ -59,7 +59,7 @@ pub fn populate_project_files<F>(
           file_list.insert(file_info);
         }
         else {
-          logger::warn(format!(
+          logger::warn(WarningId::FileOutsideIncludePrefix, format!(
             "Ignoring file '{}' because it is outside of the directory '{}'",
             path.to_str().unwrap().yellow(),
             used_dir.to_str().unwrap().yellow()
@@ -249,6 +249,27 @@ fn project_levels_below_root(clean_path_root: &str) -> io::Result<Option<usize>>
   return Ok(None);
 }
 
+// Finds the root of the project tree which contains the given project directory. Returns None
+// when the directory isn't a project.
+pub fn root_project_dir_of(unclean_given_dir: &str) -> io::Result<Option<PathBuf>> {
+  let cleaned_given_dir: String = cleaned_path_str(unclean_given_dir);
+
+  let levels_below_root: usize = match project_levels_below_root(cleaned_given_dir.as_str())? {
+    Some(value) => value,
+    None => return Ok(None)
+  };
+
+  let mut root_dir: PathBuf = PathBuf::from(&cleaned_given_dir);
+
+  // Current project is <level> levels deep. Need to go back <level> * 2 dirs, since subprojects
+  // are nested in the 'subprojects/<subproject name>' directory
+  for _ in 0..(levels_below_root * 2) {
+    root_dir.push("..");
+  }
+
+  return Ok(Some(root_dir));
+}
+
 type SubprojectMap = HashMap<String, Rc<FinalProjectData>>;
 type TestProjectMap = SubprojectMap;
 type GCMakeDependencyMap = HashMap<String, Rc<FinalGCMakeDependency>>;
@@ -258,26 +279,109 @@ type PredefinedDepMap = HashMap<String, Rc<FinalPredefinedDependencyConfig>>;
 pub enum ProjectLoadFailureReason {
   MissingYaml(String),
   MissingRequiredTestFramework(String),
-  Other(String)
+  // Reading the project's directories or files failed.
+  Io(String),
+  // An error which points to where it happened in a cmake_data.yaml. It's only rendered to text
+  // once it's shown to the user.
+  InvalidYaml(YamlDiagnostic),
+  Other(String),
+  // Independent parts of the project tree (such as sibling subprojects) failed to load. These are
+  // collected so that every problem in the tree can be reported at once.
+  Multiple(Vec<ProjectLoadFailureReason>)
 }
 
 impl ProjectLoadFailureReason {
+  // Points to value inside the key_path section of the project's cmake_data.yaml, or to the
+  // section's key. Falls back to a plain message if the cmake_data.yaml can't be read.
+  pub fn located_in_project_yaml(
+    project_root: &Path,
+    message: String,
+    key_path: &[&str],
+    maybe_value: Option<&str>
+  ) -> Self {
+    return match YamlSourceFile::find_in_project(project_root) {
+      Some(source_file) => Self::InvalidYaml(source_file.diagnostic_for(message, key_path, maybe_value)),
+      None => Self::Other(message)
+    }
+  }
+
+  pub fn from_all(failures: Vec<ProjectLoadFailureReason>) -> Self {
+    let mut flattened_failures: Vec<ProjectLoadFailureReason> = Vec::new();
+
+    for failure in failures {
+      flattened_failures.extend(failure.into_failures());
+    }
+
+    return match flattened_failures.len() {
+      1 => flattened_failures.pop().unwrap(),
+      _ => Self::Multiple(flattened_failures)
+    }
+  }
+
+  pub fn into_failures(self) -> Vec<ProjectLoadFailureReason> {
+    return match self {
+      Self::Multiple(failures) => failures,
+      single_failure => vec![single_failure]
+    }
+  }
+
+  pub fn num_failures(&self) -> usize {
+    return match self {
+      Self::Multiple(failures) => failures.len(),
+      _ => 1
+    }
+  }
+
   pub fn map_message(
     self,
-    mapper: impl FnOnce(String) -> String
+    mapper: impl Fn(String) -> String
   ) -> Self {
+    return self.map_message_dyn(&mapper);
+  }
+
+  fn map_message_dyn(self, mapper: &dyn Fn(String) -> String) -> Self {
     match self {
       Self::MissingYaml(err_message) => Self::MissingYaml(mapper(err_message)),
+      Self::Io(err_message) => Self::Io(mapper(err_message)),
+      Self::InvalidYaml(mut diagnostic) => {
+        diagnostic.message = mapper(diagnostic.message);
+        Self::InvalidYaml(diagnostic)
+      },
       Self::Other(err_message) => Self::Other(mapper(err_message)),
-      Self::MissingRequiredTestFramework(err_message) => Self::MissingRequiredTestFramework(mapper(err_message))
+      Self::MissingRequiredTestFramework(err_message) => Self::MissingRequiredTestFramework(mapper(err_message)),
+      Self::Multiple(failures) => Self::Multiple(
+        failures.into_iter()
+          .map(|failure| failure.map_message_dyn(mapper))
+          .collect()
+      )
     }
   }
 
   pub fn extract_message(self) -> String {
     match self {
       Self::MissingYaml(msg) => msg,
+      Self::Io(msg) => msg,
+      Self::InvalidYaml(diagnostic) => diagnostic.to_string(),
       Self::Other(msg) => msg,
-      Self::MissingRequiredTestFramework(msg) => msg
+      Self::MissingRequiredTestFramework(msg) => msg,
+      Self::Multiple(failures) => failures.into_iter()
+        .map(Self::extract_message)
+        .collect::<Vec<String>>()
+        .join("\n\n")
+    }
+  }
+}
+
+// Keeps loading the rest of a project after one of its parts fails to load.
+fn collect_failure<T>(
+  load_result: Result<T, ProjectLoadFailureReason>,
+  failures: &mut Vec<ProjectLoadFailureReason>
+) -> Option<T> {
+  return match load_result {
+    Ok(loaded) => Some(loaded),
+    Err(failure_reason) => {
+      failures.push(failure_reason);
+      None
     }
   }
 }
@@ -395,6 +499,8 @@ pub struct FinalProjectData {
 
   predefined_dependencies: HashMap<String, Rc<FinalPredefinedDependencyConfig>>,
   gcmake_dependency_projects: HashMap<String, Rc<FinalGCMakeDependency>>,
+  // Only loaded for root projects, since they are the only ones which can have dependencies.
+  dependency_lock: Option<DependencyLockfile>,
 
   features: Rc<BTreeMap<String, FinalFeatureConfig>>,
   prebuild_script: Option<PreBuildScript>,
@@ -409,12 +515,12 @@ impl FinalProjectData {
   ) -> Result<UseableFinalProjectDataGroup, ProjectLoadFailureReason> {
     let cleaned_given_root: String = cleaned_path_str(unclean_given_root);
 
-    let levels_below_root: usize = match project_levels_below_root(cleaned_given_root.as_str()) {
-      Err(err) => return Err(ProjectLoadFailureReason::Other(
+    let real_project_root_using: PathBuf = match root_project_dir_of(cleaned_given_root.as_str()) {
+      Err(err) => return Err(ProjectLoadFailureReason::Io(
         format!("Error when trying to find project level: {}", err.to_string())
       )),
-      Ok(maybe_level) => match maybe_level {
-        Some(value) => value,
+      Ok(maybe_root_dir) => match maybe_root_dir {
+        Some(root_dir) => root_dir,
         None => return Err(ProjectLoadFailureReason::MissingYaml(format!(
           "The directory \"{}\" does not contain a {} file, so the project level could not be determined.",
           &cleaned_given_root.yellow(),
@@ -423,15 +529,9 @@ impl FinalProjectData {
       }
     };
 
-    let mut real_project_root_using: PathBuf = PathBuf::from(&cleaned_given_root);
-
-    if levels_below_root > 0 {
-      // Current project is <level> levels deep. Need to go back <level> * 2 dirs, since subprojects
-      // are nested in the 'subprojects/<subproject name>' directory
-      for _ in 0..(levels_below_root * 2) {
-        real_project_root_using.push("..");
-      }
-    }
+    // A previously loaded project tree's warning policy must not apply to this one. The root
+    // project's own policy is applied by create_new once its cmake_data.yaml is parsed.
+    logger::reset_project_warning_levels();
 
     let root_project: Rc<FinalProjectData> = Rc::new(Self::create_new(
       real_project_root_using.to_str().unwrap(),
@@ -439,11 +539,12 @@ impl FinalProjectData {
       dep_config,
       &project_load_context.just_created_library_project_at
         .clone()
-        .map(|creation_root| absolute_path(creation_root).unwrap())
+        .map(|creation_root| absolute_path(creation_root).unwrap()),
+      &[]
     )?);
 
-    root_project.validate_correctness(&project_load_context)
-      .map_err(ProjectLoadFailureReason::Other)?;
+    ensure_gcmake_deps_are_used_once(&root_project, &mut HashMap::new())?;
+    root_project.validate_correctness(&project_load_context)?;
 
     return Ok(UseableFinalProjectDataGroup {
       operating_on: Self::find_with_root(
@@ -455,12 +556,46 @@ impl FinalProjectData {
     });
   }
 
+  // loading_roots contains the canonical root directories of the root projects which are still
+  // being loaded, starting with the root of the project tree. It's only empty when loading the
+  // tree's root project. GCMake dependency projects are loaded as root projects too, and are
+  // added to it so dependencies which point back at a loading project can be detected.
   fn create_new(
     unclean_project_root: &str,
     parent_project_info: Option<NeededParseInfoFromParent>,
     all_dep_config: &RawPredefinedDependencyMap,
-    just_created_project_at: &Option<PathBuf>
+    just_created_project_at: &Option<PathBuf>,
+    loading_roots: &[PathBuf]
   ) -> Result<FinalProjectData, ProjectLoadFailureReason> {
+    let is_tree_root: bool = parent_project_info.is_none() && loading_roots.is_empty();
+
+    let mut loading_roots: Vec<PathBuf> = loading_roots.to_vec();
+
+    if parent_project_info.is_none() {
+      loading_roots.push(
+        fs::canonicalize(unclean_project_root)
+          .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?
+      );
+    }
+
+    // Only root projects (including GCMake dependency projects) can ship their own dependency configs.
+    // Those configs are used by the whole project tree.
+    let project_local_dep_config: Option<RawPredefinedDependencyMap> = match &parent_project_info {
+      Some(_) => None,
+      None => all_dep_config.with_project_local_configs(
+        &Path::new(unclean_project_root).join(PROJECT_LOCAL_DEP_CONFIGS_DIR)
+      )
+        .map_err(ProjectLoadFailureReason::Other)?
+    };
+
+    let all_dep_config: &RawPredefinedDependencyMap = project_local_dep_config.as_ref().unwrap_or(all_dep_config);
+
+    let dependency_lock: Option<DependencyLockfile> = match &parent_project_info {
+      Some(_) => None,
+      None => DependencyLockfile::load(unclean_project_root)
+        .map_err(ProjectLoadFailureReason::Other)?
+    };
+
     let mut initial_project_data: InitialProjectData = make_initial_project_data(
       Path::new(unclean_project_root),
       &parent_project_info,
@@ -468,6 +603,15 @@ impl FinalProjectData {
       // just_created_project_at
     )?;
 
+    // The root project's warning policy covers the whole project tree, so it has to be in place
+    // before any of the tree's other projects are loaded.
+    if is_tree_root {
+      apply_root_warning_config(
+        Path::new(unclean_project_root),
+        initial_project_data.raw_project.warnings.as_ref()
+      )?;
+    }
+
     let valid_feature_list: Option<Vec<String>> = if initial_project_data.features.is_empty()
       { None }
       else {
@@ -551,33 +695,72 @@ impl FinalProjectData {
       )
       .map_err(ProjectLoadFailureReason::Other)?;
 
-    let mut finalized_project_data = FinalProjectData {
-      subprojects: obtain_subprojects(
+    // The rest of the project is still loaded when one part fails, so that every problem in the
+    // project tree can be reported at once.
+    let mut load_failures: Vec<ProjectLoadFailureReason> = Vec::new();
+
+    let maybe_subprojects = collect_failure(
+      obtain_subprojects(
         &project_paths,
         &initial_project_data,
         all_dep_config,
-        just_created_project_at
-      )?,
-      output: obtain_output_items(
+        just_created_project_at,
+        &loading_roots
+      ),
+      &mut load_failures
+    );
+
+    let maybe_output = collect_failure(
+      obtain_output_items(
+        &project_paths.project_root_relative_to_cwd,
         valid_feature_list.as_ref(),
         &mut initial_project_data,
-      )?,
-      predefined_dependencies: obtain_predefined_dependencies(
+      ),
+      &mut load_failures
+    );
+
+    let maybe_predefined_dependencies = collect_failure(
+      obtain_predefined_dependencies(
         valid_feature_list.as_ref(),
         &initial_project_data,
         all_dep_config
-      )?,
-      gcmake_dependency_projects: obtain_gcmake_dep_projects(
+      ),
+      &mut load_failures
+    );
+
+    let maybe_gcmake_dependency_projects = collect_failure(
+      obtain_gcmake_dep_projects(
+        Path::new(unclean_project_root),
         &initial_project_data,
         all_dep_config,
-        just_created_project_at
-      )?,
-      tests: obtain_test_projects(
+        just_created_project_at,
+        &loading_roots
+      ),
+      &mut load_failures
+    );
+
+    let maybe_tests = collect_failure(
+      obtain_test_projects(
         &project_paths,
         &initial_project_data,
         all_dep_config,
-        just_created_project_at
-      )?,
+        just_created_project_at,
+        &loading_roots
+      ),
+      &mut load_failures
+    );
+
+    if !load_failures.is_empty() {
+      return Err(ProjectLoadFailureReason::from_all(load_failures));
+    }
+
+    let mut finalized_project_data = FinalProjectData {
+      subprojects: maybe_subprojects.unwrap(),
+      output: maybe_output.unwrap(),
+      predefined_dependencies: maybe_predefined_dependencies.unwrap(),
+      gcmake_dependency_projects: maybe_gcmake_dependency_projects.unwrap(),
+      dependency_lock,
+      tests: maybe_tests.unwrap(),
 
       project_base_name: initial_project_data.raw_project.name.clone(),
       project_name_for_error_messages,
@@ -649,7 +832,7 @@ impl FinalProjectData {
         _ => false
       }
     )
-      .map_err(|err| ProjectLoadFailureReason::Other(err.to_string()))?;
+      .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?;
 
     populate_project_files(
       usable_project_root.as_path(),
@@ -662,7 +845,7 @@ impl FinalProjectData {
         _ => false
       }
     )
-      .map_err(|err| ProjectLoadFailureReason::Other(err.to_string()))?;
+      .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?;
 
     populate_project_files(
       usable_project_root.as_path(),
@@ -674,7 +857,7 @@ impl FinalProjectData {
         _ => false
       }
     )
-      .map_err(|err| ProjectLoadFailureReason::Other(err.to_string()))?;
+      .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?;
 
     populate_project_files(
       usable_project_root.as_path(),
@@ -686,7 +869,7 @@ impl FinalProjectData {
         _ => false
       }
     )
-      .map_err(|err| ProjectLoadFailureReason::Other(err.to_string()))?;
+      .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?;
 
     return Ok(finalized_project_data);
   }
@@ -815,7 +998,7 @@ impl FinalProjectData {
               ));
             }
 
-            logger::warn(format!(
+            logger::warn(WarningId::ExactLanguageStandard, format!(
               "This project sets an {} for the C language, however doing so isn't recommended unless you never want your project to be compiled with a later standard.",
               "exact_standard".yellow()
             ));
@@ -853,7 +1036,7 @@ impl FinalProjectData {
               ));
             }
 
-            logger::warn(format!(
+            logger::warn(WarningId::ExactLanguageStandard, format!(
               "This project sets an {} for the C++ language, however doing so isn't recommended unless you never want your project to be compiled with a later standard.",
               "exact_standard".yellow()
             ));
@@ -862,20 +1045,14 @@ impl FinalProjectData {
           if self.any_files_contain_cpp2_grammar()
             && standard_cmp(ALLOWED_CPP_STANDARDS.as_slice(), cpp_config.min_standard.as_str(), "20").unwrap().is_lt()
           {
-            logger::block(|| {
-              logger::warn(format!(
-                "Project [{}] contains .cpp2 files, but its C++ standard is currently set to {}. cppfront (.cpp2) requires C++20 or higher. Please set the C++ language standard to {} or later in {}. Example:\n",
-                self.get_name_for_error_messages().yellow(),
-                cpp_config.min_standard.to_string().red(),
-                "20".green(),
-                CONFIG_FILE_NAME
-              ));
-
-              println!(
-                "languages:\n  cpp:\n    standard: {}",
-                "20".green()
-              );
-            })
+            logger::warn(WarningId::Cpp2StandardTooLow, format!(
+              "Project [{}] contains .cpp2 files, but its C++ standard is currently set to {}. cppfront (.cpp2) requires C++20 or higher. Please set the C++ language standard to {} or later in {}. Example:\n\nlanguages:\n  cpp:\n    standard: {}",
+              self.get_name_for_error_messages().yellow(),
+              cpp_config.min_standard.to_string().red(),
+              "20".green(),
+              CONFIG_FILE_NAME,
+              "20".green()
+            ));
           }
         }
       }
@@ -910,7 +1087,7 @@ impl FinalProjectData {
               ));
             }
 
-            logger::warn(format!(
+            logger::warn(WarningId::ExactLanguageStandard, format!(
               "This project sets an {} for the CUDA language, however doing so isn't recommended unless you never want your project to be compiled with a later standard.",
               "exact_standard".yellow()
             ));
@@ -1015,42 +1192,45 @@ impl FinalProjectData {
     Ok(())
   }
 
-  fn validate_correctness(&self, project_load_context: &FinalProjectLoadContext) -> Result<(), String> {
+  fn validate_correctness(&self, project_load_context: &FinalProjectLoadContext) -> Result<(), ProjectLoadFailureReason> {
     if self.get_project_base_name().contains(' ') {
-      return Err(format!(
+      return Err(ProjectLoadFailureReason::Other(format!(
         "Project name cannot contain spaces, but does. (Currently: {})",
         self.get_project_base_name()
-      ));
+      )));
     }
 
     if self.get_full_include_prefix().contains(' ') {
-      return Err(format!(
+      return Err(ProjectLoadFailureReason::Other(format!(
         "Project 'include prefix' cannot contain spaces, but does. (Currently: {})",
         self.get_full_include_prefix()
-      ));
+      )));
     }
 
     if self.supported_compilers.contains(&SpecificCompilerSpecifier::Emscripten) && !self.supports_emscripten() {
-      return Err(format!(
+      return Err(ProjectLoadFailureReason::Other(format!(
         "Emscripten is listed as a supported compiler, but the project's contains dependencies which do not support compilation with Emscripten."
-      ))
+      )))
     }
 
     self.validate_features()?;
-    self.validate_header_names()?;
-    self.ensure_doc_generator_correctness(project_load_context)?;
-    self.ensure_no_file_collision()?;
+    self.validate_header_names()
+      .map_err(ProjectLoadFailureReason::Other)?;
+    self.ensure_doc_generator_correctness(project_load_context)
+      .map_err(ProjectLoadFailureReason::Other)?;
+    self.ensure_no_file_collision()
+      .map_err(ProjectLoadFailureReason::Other)?;
 
     for (_, test_project) in &self.tests {
       if let ProjectOutputType::ExeProject = &test_project.project_output_type {
         test_project.validate_correctness(&project_load_context)?;
       }
       else {
-        return Err(format!(
+        return Err(ProjectLoadFailureReason::Other(format!(
           "Test project '{}' in '{}' is not an executable project. All tests must output only executables.",
           test_project.get_project_base_name(),
           self.get_project_base_name()
-        ));
+        )));
       }
     }
 
@@ -1058,23 +1238,29 @@ impl FinalProjectData {
       subproject.validate_correctness(&project_load_context)?;
     }
 
-    self.ensure_language_config_correctness()?;
-    self.ensure_build_config_correctness()?;
-    self.validate_project_type_specific_info()?;
+    self.ensure_language_config_correctness()
+      .map_err(ProjectLoadFailureReason::Other)?;
+    self.ensure_build_config_correctness()
+      .map_err(ProjectLoadFailureReason::Other)?;
+    self.validate_project_type_specific_info()
+      .map_err(ProjectLoadFailureReason::Other)?;
 
     for (output_name, output_item) in &self.output {
       let the_item_name: String = format!("output \"{}\"", output_name);
-      self.validate_target_info(&the_item_name, output_item, false)?;
+      self.validate_target_info(&the_item_name, output_item, false)
+        .map_err(ProjectLoadFailureReason::Other)?;
     }
 
     if let Some(existing_script) = &self.prebuild_script {
       if let PreBuildScriptType::Exe(script_exe_config) = existing_script.get_type() {
         let the_item_name: String = format!("pre-build script (for project [{}])", self.get_name_for_error_messages());
-        self.validate_target_info(&the_item_name, script_exe_config, true)?;
+        self.validate_target_info(&the_item_name, script_exe_config, true)
+          .map_err(ProjectLoadFailureReason::Other)?;
       }
     }
 
-    self.validate_installer_config()?;
+    self.validate_installer_config()
+      .map_err(ProjectLoadFailureReason::Other)?;
 
     Ok(())
   }
@@ -1086,7 +1272,7 @@ impl FinalProjectData {
   ) {
     let config_tool_name: &str = doc_generator.to_str();
 
-    logger::warn(format!(
+    logger::warn(WarningId::UnusedDocConfigFile, format!(
       "Project [{}] contains file {}, but hasn't enabled a documentation generator in its {}. If this is intended, just ignore this warning. Otherwise, enable the {} documentation generator in {} like this:\n\n{}:\n   generator: {}",
       self.get_name_for_error_messages().yellow(),
       file_relative_to_cwd.as_ref().to_str().unwrap(),
@@ -1316,12 +1502,17 @@ impl FinalProjectData {
     Ok(())
   }
 
-  fn validate_features(&self) -> Result<(), String> {
+  fn validate_features(&self) -> Result<(), ProjectLoadFailureReason> {
     for (feature_name, feature_config) in self.features.iter() {
       if feature_name.contains(" ") {
-        return Err(format!(
-          "Invalid feature name \"{}\" given. Feature names cannot contain whitespace.",
-          feature_name.yellow()
+        return Err(ProjectLoadFailureReason::located_in_project_yaml(
+          self.get_project_root_relative_to_cwd(),
+          format!(
+            "Invalid feature name \"{}\" given. Feature names cannot contain whitespace.",
+            feature_name.yellow()
+          ),
+          &["features", feature_name],
+          None
         ));
       }
 
@@ -1329,11 +1520,16 @@ impl FinalProjectData {
         // Dependency feature enablers are checked in the dependency graph's
         // do_additional_project_checks(...) function.
         if dep_name.is_none() && !self.features.contains_key(feature_name) {
-          return Err(format!(
-            "Feature \"{}\" specifies that it should enable another feature named \"{}\", but the project doesn't define a feature called {}.",
-            feature_name.purple(),
-            feature_name_to_enable.yellow(),
-            feature_name_to_enable.yellow()
+          return Err(ProjectLoadFailureReason::located_in_project_yaml(
+            self.get_project_root_relative_to_cwd(),
+            format!(
+              "Feature \"{}\" specifies that it should enable another feature named \"{}\", but the project doesn't define a feature called {}.",
+              feature_name.purple(),
+              feature_name_to_enable.yellow(),
+              feature_name_to_enable.yellow()
+            ),
+            &["features", feature_name, "enables"],
+            Some(feature_name_to_enable)
           ));
         }
       }
@@ -1694,6 +1890,10 @@ impl FinalProjectData {
     )
   }
 
+  pub fn get_supported_compilers(&self) -> &HashSet<SpecificCompilerSpecifier> {
+    &self.supported_compilers
+  }
+
   pub fn get_features(&self) -> &BTreeMap<String, FinalFeatureConfig> {
     &self.features
   }
@@ -1722,6 +1922,10 @@ impl FinalProjectData {
     self.paths_and_prefixes.absolute_project_root.as_path()
   }
 
+  pub fn get_config_file_path(&self) -> PathBuf {
+    self.get_absolute_project_root().join(CONFIG_FILE_NAME)
+  }
+
   pub fn get_base_include_prefix(&self) -> &str {
     self.paths_and_prefixes.base_include_prefix.as_str()
   }
@@ -1859,6 +2063,10 @@ impl FinalProjectData {
     &self.gcmake_dependency_projects
   }
 
+  pub fn get_dependency_lock(&self) -> Option<&DependencyLockfile> {
+    self.dependency_lock.as_ref()
+  }
+
   pub fn supports_emscripten(&self) -> bool {
     for (_, subproject) in &self.subprojects {
       if !subproject.supports_emscripten() {
@@ -2066,6 +2274,35 @@ fn make_initial_root_project_info(
   });
 }
 
+fn apply_root_warning_config(
+  project_root: &Path,
+  maybe_warning_config: Option<&RawWarningConfig>
+) -> Result<(), ProjectLoadFailureReason> {
+  let warning_config: &RawWarningConfig = match maybe_warning_config {
+    Some(config) => config,
+    None => return Ok(())
+  };
+
+  let denied: BTreeSet<WarningId> = warning_config.deny.clone().unwrap_or_default();
+  let allowed: BTreeSet<WarningId> = warning_config.allow.clone().unwrap_or_default();
+
+  if let Some(conflicting_id) = denied.intersection(&allowed).next() {
+    return Err(ProjectLoadFailureReason::located_in_project_yaml(
+      project_root,
+      format!(
+        "Warning '{}' is both denied and allowed in the root project's {}. Remove it from one of the lists.",
+        conflicting_id.to_str().red(),
+        CONFIG_FILE_NAME
+      ),
+      &["warnings"],
+      Some(conflicting_id.to_str())
+    ));
+  }
+
+  logger::set_project_warning_levels(denied, allowed);
+  return Ok(());
+}
+
 fn obtain_feature_map(raw_project: &RawProject) -> Result<Rc<BTreeMap<String, FinalFeatureConfig>>, ProjectLoadFailureReason> {
   let mut final_feature_map: BTreeMap<String, FinalFeatureConfig> = BTreeMap::new();
   let raw_feature_map = raw_project.features.clone()
@@ -2156,57 +2393,69 @@ fn obtain_test_projects(
   project_paths: &ProjectPaths,
   initial_project_data: &InitialProjectData,
   all_dep_config: &RawPredefinedDependencyMap,
-  just_created_project_at: &Option<PathBuf>
+  just_created_project_at: &Option<PathBuf>,
+  loading_roots: &[PathBuf]
 ) -> Result<SubprojectMap, ProjectLoadFailureReason> {
   let mut test_project_map: SubprojectMap = SubprojectMap::new();
+  let mut load_failures: Vec<ProjectLoadFailureReason> = Vec::new();
 
   if project_paths.test_dir_relative_to_cwd.is_dir() {
     let tests_dir_iter = fs::read_dir(project_paths.test_dir_relative_to_cwd.as_path())
-      .map_err(|err| ProjectLoadFailureReason::Other(err.to_string()))?;
+      .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?;
 
     for dir_entry in tests_dir_iter {
       let test_project_path: PathBuf = match dir_entry {
         Ok(entry) => entry.path(),
-        Err(err) => return Err(ProjectLoadFailureReason::Other(err.to_string()))
+        Err(err) => return Err(ProjectLoadFailureReason::Io(err.to_string()))
       };
     
       if test_project_path.is_dir() {
         let test_project_name: String = test_project_path.file_name().unwrap().to_str().unwrap().to_string();
 
-        let new_test_project: FinalProjectData = FinalProjectData::create_new(
-          test_project_path.to_str().unwrap(),
-          Some(NeededParseInfoFromParent {
-            actual_base_name: test_project_name.clone(),
-            actual_vendor: initial_project_data.vendor.clone(),
-            parent_project_namespaced_name: initial_project_data.full_namespaced_project_name.clone(),
-            parse_mode: ChildParseMode::TestProject,
-            test_framework: initial_project_data.final_test_framework.clone(), 
-            include_prefix: project_paths.full_include_prefix.clone(),
-            target_namespace_prefix: project_paths.target_namespace_prefix.clone(),
-            build_config_map: Rc::clone(&initial_project_data.build_config),
-            language_config_map: Rc::clone(&initial_project_data.language_config),
-            supported_compilers: Rc::clone(&initial_project_data.supported_compiler_set),
-            inherited_features: Rc::clone(&initial_project_data.features)
-          }),
-          all_dep_config,
-          just_created_project_at
-        )
-          .map_err(|failure_reason| {
-            failure_reason.map_message(|err_message| format!(
-              "\t-> in test project '{}'\n{}",
-              cleaned_pathbuf(test_project_path.clone()).to_str().unwrap(),
-              err_message
-            ))
-          })?;
-
-        test_project_map.insert(
-          test_project_name,
-          Rc::new(new_test_project)
+        let maybe_test_project: Option<FinalProjectData> = collect_failure(
+          FinalProjectData::create_new(
+            test_project_path.to_str().unwrap(),
+            Some(NeededParseInfoFromParent {
+              actual_base_name: test_project_name.clone(),
+              actual_vendor: initial_project_data.vendor.clone(),
+              parent_project_namespaced_name: initial_project_data.full_namespaced_project_name.clone(),
+              parse_mode: ChildParseMode::TestProject,
+              test_framework: initial_project_data.final_test_framework.clone(), 
+              include_prefix: project_paths.full_include_prefix.clone(),
+              target_namespace_prefix: project_paths.target_namespace_prefix.clone(),
+              build_config_map: Rc::clone(&initial_project_data.build_config),
+              language_config_map: Rc::clone(&initial_project_data.language_config),
+              supported_compilers: Rc::clone(&initial_project_data.supported_compiler_set),
+              inherited_features: Rc::clone(&initial_project_data.features)
+            }),
+            all_dep_config,
+            just_created_project_at,
+            loading_roots
+          )
+            .map_err(|failure_reason| {
+              failure_reason.map_message(|err_message| format!(
+                "\t-> in test project '{}'\n{}",
+                cleaned_pathbuf(test_project_path.clone()).to_str().unwrap(),
+                err_message
+              ))
+            }),
+          &mut load_failures
         );
+
+        if let Some(new_test_project) = maybe_test_project {
+          test_project_map.insert(
+            test_project_name,
+            Rc::new(new_test_project)
+          );
+        }
       }
     }
   }
 
+  if !load_failures.is_empty() {
+    return Err(ProjectLoadFailureReason::from_all(load_failures));
+  }
+
   return Ok(test_project_map);
 }
 
@@ -2214,84 +2463,140 @@ fn obtain_subprojects(
   project_paths: &ProjectPaths,
   initial_project_data: &InitialProjectData,
   all_dep_config: &RawPredefinedDependencyMap,
-  just_created_project_at: &Option<PathBuf>
+  just_created_project_at: &Option<PathBuf>,
+  loading_roots: &[PathBuf]
 ) -> Result<SubprojectMap, ProjectLoadFailureReason> {
   let mut subproject_map = SubprojectMap::new();
+  let mut load_failures: Vec<ProjectLoadFailureReason> = Vec::new();
 
   if project_paths.subproject_dir_relative_to_cwd.is_dir() {
     let subprojects_dir_iter = fs::read_dir(project_paths.subproject_dir_relative_to_cwd.as_path())
-      .map_err(|err| ProjectLoadFailureReason::Other(err.to_string()))?;
+      .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?;
 
     for dir_entry in subprojects_dir_iter {
       let subproject_path: PathBuf = match dir_entry {
         Ok(entry) => entry.path(),
-        Err(err) => return Err(ProjectLoadFailureReason::Other(err.to_string()))
+        Err(err) => return Err(ProjectLoadFailureReason::Io(err.to_string()))
       };
     
       if subproject_path.is_dir() {
         let subproject_name: String = subproject_path.file_name().unwrap().to_str().unwrap().to_string();
 
-        let new_subproject: FinalProjectData = FinalProjectData::create_new(
-          subproject_path.to_str().unwrap(),
-          Some(NeededParseInfoFromParent {
-            actual_base_name: subproject_name.clone(),
-            actual_vendor: initial_project_data.vendor.clone(),
-            parent_project_namespaced_name: initial_project_data.full_namespaced_project_name.clone(),
-            parse_mode: ChildParseMode::Subproject,
-            test_framework: initial_project_data.final_test_framework.clone(),
-            include_prefix: project_paths.full_include_prefix.clone(),
-            target_namespace_prefix: project_paths.target_namespace_prefix.clone(),
-            supported_compilers: Rc::clone(&initial_project_data.supported_compiler_set),
-            build_config_map: Rc::clone(&initial_project_data.build_config),
-            language_config_map: Rc::clone(&initial_project_data.language_config),
-            inherited_features: Rc::clone(&initial_project_data.features)
-          }),
-          all_dep_config,
-          just_created_project_at
-        )
-          .map_err(|failure_reason| {
-            failure_reason.map_message(|err_message| format!(
-              "\t-> in subproject '{}'\n{}",
-              cleaned_pathbuf(subproject_path.clone()).to_str().unwrap(),
-              err_message
-            ))
-          })?;
-
-        subproject_map.insert(
-          subproject_name,
-          Rc::new(new_subproject)
+        let maybe_subproject: Option<FinalProjectData> = collect_failure(
+          FinalProjectData::create_new(
+            subproject_path.to_str().unwrap(),
+            Some(NeededParseInfoFromParent {
+              actual_base_name: subproject_name.clone(),
+              actual_vendor: initial_project_data.vendor.clone(),
+              parent_project_namespaced_name: initial_project_data.full_namespaced_project_name.clone(),
+              parse_mode: ChildParseMode::Subproject,
+              test_framework: initial_project_data.final_test_framework.clone(),
+              include_prefix: project_paths.full_include_prefix.clone(),
+              target_namespace_prefix: project_paths.target_namespace_prefix.clone(),
+              supported_compilers: Rc::clone(&initial_project_data.supported_compiler_set),
+              build_config_map: Rc::clone(&initial_project_data.build_config),
+              language_config_map: Rc::clone(&initial_project_data.language_config),
+              inherited_features: Rc::clone(&initial_project_data.features)
+            }),
+            all_dep_config,
+            just_created_project_at,
+            loading_roots
+          )
+            .map_err(|failure_reason| {
+              failure_reason.map_message(|err_message| format!(
+                "\t-> in subproject '{}'\n{}",
+                cleaned_pathbuf(subproject_path.clone()).to_str().unwrap(),
+                err_message
+              ))
+            }),
+          &mut load_failures
         );
+
+        if let Some(new_subproject) = maybe_subproject {
+          subproject_map.insert(
+            subproject_name,
+            Rc::new(new_subproject)
+          );
+        }
       }
     }
   }
 
+  if !load_failures.is_empty() {
+    return Err(ProjectLoadFailureReason::from_all(load_failures));
+  }
+
   return Ok(subproject_map);
 }
 
 fn obtain_gcmake_dep_projects(
+  project_root: &Path,
   initial_project_data: &InitialProjectData,
   all_dep_config: &RawPredefinedDependencyMap,
-  just_created_project_at: &Option<PathBuf>
+  just_created_project_at: &Option<PathBuf>,
+  loading_roots: &[PathBuf]
 ) -> Result<GCMakeDependencyMap, ProjectLoadFailureReason> {
   let mut gcmake_dep_project_map = GCMakeDependencyMap::new();
 
   if let Some(gcmake_dep_map) = &initial_project_data.raw_project.gcmake_dependencies {
     for (dep_name, dep_config) in gcmake_dep_map {
-      // CPM hashes dependency directories in the global cache, so we can't immediately determine the
-      // exact repository which matches our specified dependency. We get around that by having CMake
-      // write this hash into a file at configure time. Then when gcmake-rust is run again,
-      // we can find the matching dependency repository by checking whether the contents of that
-      // hash file match what we expect.
-      let expected_hash: String = base64_encoded(format!(
-        "{}->{}->{}",
-        dep_name,
-        dep_config.repo_url,
-        dep_config.commit_hash.clone()
-          .unwrap_or(dep_config.commit_hash.clone().unwrap_or_default())
-      ));
+      let dep_source: GCMakeDependencySource = GCMakeDependencySource::from_given_config(dep_name, dep_config)
+        .map_err(ProjectLoadFailureReason::Other)?;
+
+      let (maybe_dep_path, maybe_expected_hash): (Option<PathBuf>, Option<String>) = match &dep_source {
+        // Local dependencies are loaded straight from their checkout, so they are always available.
+        GCMakeDependencySource::LocalPath(local_path) => {
+          let dep_path: PathBuf = project_root.join(local_path);
+
+          if !dep_path.join(CONFIG_FILE_NAME).is_file() {
+            return Err(ProjectLoadFailureReason::Other(format!(
+              "GCMake dependency '{}' points to '{}', which doesn't contain a {} file.",
+              dep_name.yellow(),
+              local_path.to_str().unwrap(),
+              CONFIG_FILE_NAME
+            )));
+          }
+
+          (Some(dep_path), None)
+        },
+        GCMakeDependencySource::GitRepo(git_repo) => {
+          // CPM hashes dependency directories in the global cache, so we can't immediately determine the
+          // exact repository which matches our specified dependency. We get around that by having CMake
+          // write this hash into a file at configure time. Then when gcmake-rust is run again,
+          // we can find the matching dependency repository by checking whether the contents of that
+          // hash file match what we expect.
+          let expected_hash: String = base64_encoded(format!(
+            "{}->{}->{}",
+            dep_name,
+            git_repo.repo_url,
+            dep_config.commit_hash.clone()
+              .unwrap_or(dep_config.commit_hash.clone().unwrap_or_default())
+          ));
+
+          let maybe_dep_path: Option<PathBuf> = find_matching_gcmake_dep_path(dep_name, &expected_hash)
+            .map_err(|io_err| ProjectLoadFailureReason::Io(io_err.to_string()))?;
+
+          (maybe_dep_path, Some(expected_hash))
+        }
+      };
+
+      if let Some(dep_path) = &maybe_dep_path {
+        let canonical_dep_path: PathBuf = fs::canonicalize(dep_path)
+          .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?;
 
-      let maybe_dep_path: Option<PathBuf> = find_matching_gcmake_dep_path(dep_name, &expected_hash)
-        .map_err(|io_err| ProjectLoadFailureReason::Other(io_err.to_string()))?;
+        if let Some(cycle_start) = loading_roots.iter().position(|loading_root| *loading_root == canonical_dep_path) {
+          let cycle: Vec<String> = loading_roots[cycle_start..].iter()
+            .chain(std::iter::once(&canonical_dep_path))
+            .map(|project_root| unix_style(project_root))
+            .collect();
+
+          return Err(ProjectLoadFailureReason::Other(format!(
+            "GCMake dependency '{}' points to a project which is already being loaded, so the GCMake dependencies form a cycle:\n\t{}",
+            dep_name.yellow(),
+            cycle.join(" -> ")
+          )));
+        }
+      }
 
       let maybe_dep_project: Option<Rc<FinalProjectData>> = match maybe_dep_path {
         None => None,
@@ -2299,21 +2604,27 @@ fn obtain_gcmake_dep_projects(
           dep_path.to_str().unwrap(),
           None,
           all_dep_config,
-          just_created_project_at
+          just_created_project_at,
+          loading_roots
         )?))
       };
 
+      let maybe_package_manifest: Option<PackageManifest> = match &maybe_dep_project {
+        Some(_) => None,
+        None => PackageManifest::load_for_dependency(project_root, dep_name)
+          .map_err(ProjectLoadFailureReason::Other)?
+      };
+
       gcmake_dep_project_map.insert(
         dep_name.clone(),
-        Rc::new(
-          FinalGCMakeDependency::new(
-            &dep_name,
-            dep_config,
-            expected_hash,
-            maybe_dep_project
-          )
-          .map_err(ProjectLoadFailureReason::Other)?
-        )
+        Rc::new(FinalGCMakeDependency::new(
+          &dep_name,
+          dep_config,
+          dep_source,
+          maybe_expected_hash,
+          maybe_dep_project,
+          maybe_package_manifest
+        ))
       );
     }
   }
@@ -2321,7 +2632,49 @@ fn obtain_gcmake_dep_projects(
   return Ok(gcmake_dep_project_map);
 }
 
+// The dependency graph adds a separate copy of a GCMake dependency for every project which declares
+// it, so only one project in the tree can depend on any given project. used_dep_roots maps each
+// dependency project's canonical root to its dependency name and the project which declared it.
+fn ensure_gcmake_deps_are_used_once(
+  project: &FinalProjectData,
+  used_dep_roots: &mut HashMap<PathBuf, (String, String)>
+) -> Result<(), ProjectLoadFailureReason> {
+  let mut sorted_deps: Vec<(&String, &Rc<FinalGCMakeDependency>)> = project.get_gcmake_dependencies().iter().collect();
+  sorted_deps.sort_by_key(|(dep_name, _)| *dep_name);
+
+  for (dep_name, gcmake_dep) in sorted_deps {
+    let dep_project: &Rc<FinalProjectData> = match gcmake_dep.project_status() {
+      GCMakeDependencyStatus::Available(dep_project) => dep_project,
+      GCMakeDependencyStatus::NotDownloaded(_) => continue
+    };
+
+    let canonical_dep_root: PathBuf = fs::canonicalize(dep_project.get_absolute_project_root())
+      .map_err(|err| ProjectLoadFailureReason::Io(err.to_string()))?;
+
+    if let Some((first_dep_name, first_declaring_project)) = used_dep_roots.get(&canonical_dep_root) {
+      return Err(ProjectLoadFailureReason::Other(format!(
+        "GCMake dependency '{}' of project [{}] and GCMake dependency '{}' of project [{}] are the same project ({}). Only one project in the tree can depend on a given GCMake project.",
+        first_dep_name.yellow(),
+        first_declaring_project,
+        dep_name.yellow(),
+        project.get_name_for_error_messages(),
+        unix_style(&canonical_dep_root)
+      )));
+    }
+
+    used_dep_roots.insert(
+      canonical_dep_root,
+      (dep_name.clone(), project.get_name_for_error_messages().to_string())
+    );
+
+    ensure_gcmake_deps_are_used_once(dep_project, used_dep_roots)?;
+  }
+
+  Ok(())
+}
+
 fn obtain_output_items(
+  project_root: &Path,
   valid_feature_list: Option<&Vec<String>>,
   initial_project_data: &mut InitialProjectData,
 ) -> Result<OutputItemMap, ProjectLoadFailureReason> {
@@ -2363,8 +2716,11 @@ fn obtain_output_items(
           actual_output_name = output_name;
           system_spec = None;
         },
-        Err(err_msg) => return Err(ProjectLoadFailureReason::Other(
-          format!("Error when parsing system specifier from output name '{}':\n{}", output_name, err_msg)
+        Err(err_msg) => return Err(ProjectLoadFailureReason::located_in_project_yaml(
+          project_root,
+          format!("Error when parsing system specifier from output name '{}':\n{}", output_name, err_msg),
+          &["output", output_name],
+          None
         ))
       }
     }
@@ -2377,9 +2733,20 @@ fn obtain_output_items(
         system_spec,
         referenced_feature_list(valid_feature_list).as_ref()
       )
-        .map_err(|err_message| ProjectLoadFailureReason::Other(
-          format!("When creating output item named '{}':\n{}", output_name, err_message)
-        ))?
+        .map_err(|err_message| {
+          // Point to the link or language feature specifier which failed to parse, when the error names one.
+          let failed_specifier: Option<&String> = raw_output_item.link.iter()
+            .chain(raw_output_item.language_features.iter())
+            .flat_map(LinkSection::specifiers)
+            .find(|specifier| err_message.contains(&format!("\"{}\"", specifier)));
+
+          ProjectLoadFailureReason::located_in_project_yaml(
+            project_root,
+            format!("When creating output item named '{}':\n{}", output_name, err_message),
+            &["output", output_name],
+            failed_specifier.map(String::as_str)
+          )
+        })?
     );
   }
 
@@ -2417,4 +2784,63 @@ fn obtain_predefined_dependencies(
   }
 
   return Ok(predefined_dependencies);
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{logger::WarningLevel, test_projects::{TestProjectDir, no_dep_configs}};
+
+  use super::*;
+
+  #[test]
+  fn test_warning_policy_does_not_leak_into_the_next_loaded_project() {
+    let test_dir = TestProjectDir::new("warning-policy");
+
+    test_dir.write_root_project("denying", "denying", &[], "warnings:\n  deny:\n    - unused-dependency\n");
+    test_dir.write_root_project("default", "default", &[], "");
+
+    let first_load = FinalProjectData::new(&test_dir.path_str("denying"), &no_dep_configs(), FinalProjectLoadContext::default());
+    let second_load = FinalProjectData::new(&test_dir.path_str("default"), &no_dep_configs(), FinalProjectLoadContext::default());
+
+    assert!(first_load.is_ok());
+    assert!(second_load.is_ok());
+    assert_eq!(logger::warning_level(WarningId::UnusedDependency), WarningLevel::Warn);
+  }
+
+  fn local_deps_section(dep_names: &[&str]) -> String {
+    let dep_entries: String = dep_names.iter()
+      .map(|dep_name| format!("  {}:\n    path: ../{}\n", dep_name, dep_name))
+      .collect();
+
+    return format!("gcmake_dependencies:\n{}", dep_entries);
+  }
+
+  #[test]
+  fn test_local_gcmake_dependency_cycles_and_diamonds_fail_to_load() {
+    let test_dir = TestProjectDir::new("dep-cycle");
+
+    test_dir.write_root_project("first", "first", &[], &local_deps_section(&["second"]));
+    test_dir.write_root_project("second", "second", &[], &local_deps_section(&["first"]));
+    // 'shared' is used by both 'diamond' and 'second-diamond'. That isn't a cycle, but the
+    // dependency graph can't contain the same dependency project twice.
+    test_dir.write_root_project("diamond", "diamond", &[], &local_deps_section(&["second-diamond", "shared"]));
+    test_dir.write_root_project("second-diamond", "second-diamond", &[], &local_deps_section(&["shared"]));
+    test_dir.write_root_project("shared", "shared", &[], "");
+
+    let message_of_failed_load = |project_dir: &str| -> String {
+      return match FinalProjectData::new(&test_dir.path_str(project_dir), &no_dep_configs(), FinalProjectLoadContext::default()) {
+        Ok(_) => panic!("Loading '{}' should fail", project_dir),
+        Err(failure_reason) => failure_reason.extract_message()
+      }
+    };
+
+    let cycle_message: String = message_of_failed_load("first");
+    let diamond_message: String = message_of_failed_load("diamond");
+
+    assert!(cycle_message.contains("form a cycle"), "{}", cycle_message);
+    assert!(cycle_message.contains("first -> "), "{}", cycle_message);
+    assert!(diamond_message.contains("are the same project"), "{}", diamond_message);
+    assert!(diamond_message.contains("[second-diamond]"), "{}", diamond_message);
+    assert!(diamond_message.contains("[diamond]"), "{}", diamond_message);
+  }
+}
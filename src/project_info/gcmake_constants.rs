@@ -4,3 +4,13 @@ pub const DOCS_DIR_NAME: &'static str = "docs";
 pub const SUBPROJECTS_DIR_NAME: &'static str = "subprojects";
 pub const TESTS_DIR_NAME: &'static str = "tests";
 pub const ASSETS_DIR_NAME: &'static str = "resources";
+// Relative to a root project. Contains predefined dependency configs which only that project uses.
+pub const PROJECT_LOCAL_DEP_CONFIGS_DIR: &'static str = ".gcmake/dep-configs";
+// Relative to a root project. Code file templates used by gen-file, which take precedence over ~/.gcmake/file-templates.
+pub const PROJECT_LOCAL_FILE_TEMPLATES_DIR: &'static str = ".gcmake/file-templates";
+// Relative to a root project. Pins the resolved revision of every dependency the project downloads.
+pub const DEPENDENCY_LOCK_FILE_NAME: &'static str = "gcmake.lock";
+// Written to the root of every GCMake project. Describes the project's targets and features to dependents.
+pub const PACKAGE_MANIFEST_FILE_NAME: &'static str = "gcmake-package.yaml";
+// Relative to a root project. Holds copies of GCMake dependencies' package manifests, named <dep name>.yaml.
+pub const PROJECT_LOCAL_PACKAGE_MANIFESTS_DIR: &'static str = ".gcmake/package-manifests";
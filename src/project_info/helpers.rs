@@ -3,7 +3,7 @@ use std::{path::{PathBuf, Path}, fs::{self}, io::{self}};
 use colored::Colorize;
 use regex::Regex;
 
-use super::{raw_data_in::{RawProject, RawSubproject, OutputItemType, RawTestProject}, path_manipulation::cleaned_pathbuf, final_project_data::{ProjectLoadFailureReason, CppFileGrammar}};
+use super::{raw_data_in::{RawProject, RawSubproject, OutputItemType, RawTestProject}, path_manipulation::cleaned_pathbuf, final_project_data::{ProjectLoadFailureReason, CppFileGrammar}, yaml_diagnostics::YamlDiagnostic};
 
 #[derive(Clone, Copy)]
 pub enum CodeFileLang {
@@ -228,11 +228,13 @@ type YamlParseResult<T> = Result<T, ProjectLoadFailureReason>;
 
 fn yaml_parse_helper<T: serde::de::DeserializeOwned>(project_root: &Path) -> YamlParseResult<T> {
   for possible_cmake_data_file in yaml_names_from_dir(project_root) {
-    if let io::Result::Ok(cmake_data_yaml_string) = fs::read_to_string(possible_cmake_data_file) {
+    if let io::Result::Ok(cmake_data_yaml_string) = fs::read_to_string(&possible_cmake_data_file) {
 
       return match serde_yaml::from_str::<T>(&cmake_data_yaml_string) {
         Ok(serialized_project) => Ok(serialized_project),
-        Err(error) => Err(ProjectLoadFailureReason::Other(error.to_string()))
+        Err(error) => Err(ProjectLoadFailureReason::InvalidYaml(
+          YamlDiagnostic::from_serde_error(&error, &possible_cmake_data_file, &cmake_data_yaml_string)
+        ))
       }
     }
   }
@@ -0,0 +1,302 @@
+use std::{cell::RefCell, collections::HashMap, fs, path::{Path, PathBuf}, rc::Rc};
+
+use colored::*;
+use regex::Regex;
+
+use crate::logger::{self, WarningId};
+
+use super::{dependency_graph_mod::dependency_graph::{DependencyGraph, DependencyGraphInfoWrapper, TargetNode}, final_project_data::FinalProjectData, path_manipulation::{cleaned_pathbuf, unix_style}, CodeFileInfo, PreBuildScriptType};
+
+lazy_static! {
+  // Captures: 1 = everything before the path, 2 = opening quote or bracket, 3 = included path, 4 = closing quote or bracket.
+  pub static ref INCLUDE_DIRECTIVE_REGEX: Regex = Regex::new(r#"(?m)^(\s*#\s*include\s*)(["<])([^">]+)([">])"#).unwrap();
+}
+
+struct HeaderOwner {
+  project_id: usize,
+  project_name: String,
+  is_private: bool
+}
+
+struct IncludingFile<'a> {
+  file_path: PathBuf,
+  // Targets the file is compiled into. Every one of them needs access to the included headers.
+  compiled_into: Vec<Rc<RefCell<TargetNode<'a>>>>
+}
+
+fn include_path_of(project: &FinalProjectData, code_dir: &Path, header: &CodeFileInfo) -> Option<String> {
+  return header.file_path.strip_prefix(code_dir)
+    .ok()
+    .map(|relative_path| format!("{}/{}", project.get_full_include_prefix(), unix_style(relative_path)));
+}
+
+// Maps the include path of every header in the project tree and its GCMake dependencies to the
+// project which owns it.
+fn index_headers(graph: &Rc<RefCell<DependencyGraph>>, header_owners: &mut HashMap<String, HeaderOwner>) {
+  let borrowed_graph = graph.as_ref().borrow();
+
+  if let Some(project) = borrowed_graph.project_wrapper().maybe_normal_project() {
+    let header_sets = [
+      (project.get_include_dir_relative_to_project_root(), &project.public_headers, false),
+      (project.get_include_dir_relative_to_project_root(), &project.template_impl_files, false),
+      (project.get_src_dir_relative_to_project_root(), &project.private_headers, true)
+    ];
+
+    for (code_dir, header_set, is_private) in header_sets {
+      for header in header_set {
+        if let Some(include_path) = include_path_of(project, code_dir, header) {
+          header_owners.insert(include_path, HeaderOwner {
+            project_id: borrowed_graph.project_id(),
+            project_name: borrowed_graph.project_debug_name().to_string(),
+            is_private
+          });
+        }
+      }
+    }
+  }
+
+  let nested_graphs = borrowed_graph.get_subprojects().values()
+    .chain(borrowed_graph.get_test_projects().values())
+    .chain(borrowed_graph.get_gcmake_dependencies().values());
+
+  for nested_graph in nested_graphs {
+    index_headers(nested_graph, header_owners);
+  }
+}
+
+fn including_files<'a>(project: &FinalProjectData, graph: &DependencyGraph<'a>) -> Vec<IncludingFile<'a>> {
+  let target_map = graph.get_this_target_map().borrow();
+  let project_root: &Path = project.get_project_root_relative_to_cwd();
+  let mut files: Vec<IncludingFile<'a>> = Vec::new();
+
+  let shared_files = project.public_headers.iter()
+    .chain(&project.template_impl_files)
+    .chain(&project.private_headers)
+    .chain(&project.src_files);
+
+  for code_file in shared_files {
+    files.push(IncludingFile {
+      file_path: project_root.join(&code_file.file_path),
+      compiled_into: target_map.values().map(Rc::clone).collect()
+    });
+  }
+
+  for (output_name, output) in project.get_outputs() {
+    files.push(IncludingFile {
+      file_path: project_root.join(&output.get_entry_file().file_path),
+      compiled_into: target_map.get(output_name).map(Rc::clone).into_iter().collect()
+    });
+  }
+
+  if let (Some(pre_build), Some(pre_build_node)) = (project.get_prebuild_script().as_ref(), graph.get_pre_build_node().as_ref()) {
+    if let PreBuildScriptType::Exe(exe_pre_build) = pre_build.get_type() {
+      files.push(IncludingFile {
+        file_path: project_root.join(&exe_pre_build.get_entry_file().file_path),
+        compiled_into: vec![Rc::clone(pre_build_node)]
+      });
+    }
+  }
+
+  return files;
+}
+
+// Blanks out every comment while keeping line breaks, so commented-out includes are skipped and
+// line numbers still match the original file.
+fn without_comments(file_contents: &str) -> String {
+  let mut result: String = String::with_capacity(file_contents.len());
+  let mut chars = file_contents.chars().peekable();
+  let mut in_string: bool = false;
+
+  while let Some(c) = chars.next() {
+    if in_string {
+      result.push(c);
+
+      if c == '\\' {
+        if let Some(escaped) = chars.next() {
+          result.push(escaped);
+        }
+      }
+      else if c == '"' || c == '\n' {
+        in_string = false;
+      }
+    }
+    else if c == '/' && chars.peek() == Some(&'/') {
+      while let Some(&next) = chars.peek() {
+        if next == '\n' {
+          break;
+        }
+        chars.next();
+      }
+    }
+    else if c == '/' && chars.peek() == Some(&'*') {
+      chars.next();
+      let mut previous: char = ' ';
+
+      while let Some(next) = chars.next() {
+        if next == '\n' {
+          result.push('\n');
+        }
+        else if previous == '*' && next == '/' {
+          break;
+        }
+        previous = next;
+      }
+    }
+    else {
+      in_string = c == '"';
+      result.push(c);
+    }
+  }
+
+  return result;
+}
+
+fn find_include_problems_in_project(
+  graph: &Rc<RefCell<DependencyGraph>>,
+  header_owners: &HashMap<String, HeaderOwner>,
+  problems: &mut Vec<String>
+) {
+  let borrowed_graph = graph.as_ref().borrow();
+
+  if let Some(project) = borrowed_graph.project_wrapper().maybe_normal_project() {
+    for including_file in including_files(project, &borrowed_graph) {
+      // Generated files might not exist yet.
+      let file_contents: String = match fs::read_to_string(&including_file.file_path) {
+        Ok(file_contents) => without_comments(&file_contents),
+        Err(_) => continue
+      };
+
+      for (line_index, line) in file_contents.lines().enumerate() {
+        let included_path: &str = match INCLUDE_DIRECTIVE_REGEX.captures(line) {
+          Some(captures) => captures.get(3).unwrap().as_str(),
+          None => continue
+        };
+
+        let owner: &HeaderOwner = match header_owners.get(included_path) {
+          Some(owner) if owner.project_id != borrowed_graph.project_id() => owner,
+          _ => continue
+        };
+
+        let location: String = format!("{}:{}", unix_style(cleaned_pathbuf(&including_file.file_path)), line_index + 1);
+
+        if owner.is_private {
+          problems.push(format!(
+            "{}: Includes '{}', which is a private header in project [{}]. Private headers can only be included by files in their own project.",
+            location.yellow(),
+            included_path,
+            owner.project_name.yellow()
+          ));
+          continue;
+        }
+
+        let targets_without_access: Vec<String> = including_file.compiled_into.iter()
+          .map(|target| target.as_ref().borrow())
+          .filter(|target| !target.transitively_links_project(owner.project_id))
+          .map(|target| target.get_name().to_string())
+          .collect();
+
+        if !targets_without_access.is_empty() {
+          problems.push(format!(
+            "{}: Includes '{}' from project [{}], but {} {} not link to any of its targets, either directly or through public links.",
+            location.yellow(),
+            included_path,
+            owner.project_name.yellow(),
+            targets_without_access.join(", ").yellow(),
+            if targets_without_access.len() == 1 { "does" } else { "do" }
+          ));
+        }
+      }
+    }
+  }
+
+  for nested_graph in borrowed_graph.get_subprojects().values().chain(borrowed_graph.get_test_projects().values()) {
+    find_include_problems_in_project(nested_graph, header_owners, problems);
+  }
+}
+
+fn find_include_problems(dep_graph_info: &DependencyGraphInfoWrapper) -> Vec<String> {
+  let mut header_owners: HashMap<String, HeaderOwner> = HashMap::new();
+  index_headers(&dep_graph_info.root_dep_graph, &mut header_owners);
+
+  let mut problems: Vec<String> = Vec::new();
+  find_include_problems_in_project(&dep_graph_info.root_dep_graph, &header_owners, &mut problems);
+  return problems;
+}
+
+// Scans the #include directives in every code file in the project tree, and warns about includes of
+// another project's private headers or of headers from projects which aren't linked.
+pub fn warn_for_invalid_includes(dep_graph_info: &DependencyGraphInfoWrapper) {
+  for problem in find_include_problems(dep_graph_info) {
+    logger::warn(WarningId::InvalidInclude, problem);
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use crate::{project_info::{dependency_graph_mod::dependency_graph::{DependencyGraph, DependencyGraphWarningMode}, final_project_data::{FinalProjectData, FinalProjectLoadContext}}, test_projects::{TestProjectDir, no_dep_configs}};
+
+  use super::*;
+
+  fn write_lib_subproject(project_dir: &TestProjectDir, name: &str, link_section: &str, source_contents: &str) {
+    let subproject_dir: String = format!("subprojects/{}", name);
+    let upper_name: String = name.to_uppercase();
+
+    project_dir.write_file(&format!("{}/cmake_data.yaml", subproject_dir), &format!(
+      "include_prefix: {}\ndescription: d\nversion: 0.0.1\noutput:\n  {}:\n    output_type: CompiledLib\n    entry_file: {}.hpp\n{}",
+      upper_name,
+      name,
+      name,
+      link_section
+    ));
+    project_dir.write_file(&format!("{}/{}.hpp", subproject_dir, name), "#pragma once\n");
+    project_dir.write_file(&format!("{}/include/INCL/{}/{}.hpp", subproject_dir, upper_name, name), "#pragma once\n");
+    project_dir.write_file(&format!("{}/src/INCL/{}/{}.cpp", subproject_dir, upper_name, name), source_contents);
+  }
+
+  fn problems_in_project_tree(project_dir: &TestProjectDir) -> Vec<String> {
+    let project_group = FinalProjectData::new(
+      &project_dir.path_str(""),
+      &no_dep_configs(),
+      FinalProjectLoadContext::default()
+    ).unwrap_or_else(|err| panic!("{}", err.extract_message()));
+
+    let graph_info = DependencyGraph::new_info_from_root(&project_group.root_project, DependencyGraphWarningMode::Off)
+      .unwrap_or_else(|_| panic!("Failed to load the dependency graph"));
+
+    return find_include_problems(&graph_info);
+  }
+
+  #[test]
+  fn test_only_uncommented_includes_of_unlinked_projects_are_problems() {
+    let project_dir = TestProjectDir::new("includes");
+
+    project_dir.write_root_project("", "incl", &["top::top", "loose::loose"], "");
+    write_lib_subproject(&project_dir, "base", "", "#include \"INCL/BASE/base.hpp\"\n");
+    write_lib_subproject(&project_dir, "middle", "    link:\n      public:\n        - super::base::base\n", "#include \"INCL/MIDDLE/middle.hpp\"\n");
+    // 'top' only reaches 'base' through the public link in 'middle'.
+    write_lib_subproject(&project_dir, "top", "    link:\n      private:\n        - super::middle::middle\n", "#include \"INCL/BASE/base.hpp\"\n");
+    write_lib_subproject(
+      &project_dir,
+      "loose",
+      "",
+      "// #include \"INCL/BASE/base.hpp\"\n/*\n#include \"INCL/MIDDLE/middle.hpp\"\n*/\n#include \"INCL/TOP/top.hpp\"\n"
+    );
+
+    let problems: Vec<String> = problems_in_project_tree(&project_dir);
+
+    assert_eq!(problems.len(), 1, "{:?}", problems);
+    assert!(problems[0].contains("loose.cpp"));
+    assert!(problems[0].contains(":5"));
+    assert!(problems[0].contains("INCL/TOP/top.hpp"));
+  }
+
+  #[test]
+  fn test_comments_are_removed_without_changing_line_numbers() {
+    let contents: &str = "#include <a.hpp> // trailing\n/* first\n#include \"b.hpp\" */ int x;\nconst char* s = \"// not a comment\";\n";
+
+    assert_eq!(
+      without_comments(contents),
+      "#include <a.hpp> \n\n int x;\nconst char* s = \"// not a comment\";\n"
+    );
+  }
+}
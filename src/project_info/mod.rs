@@ -7,6 +7,10 @@ pub mod final_project_configurables;
 pub mod dependency_graph_mod;
 pub mod gcmake_constants;
 pub mod dep_graph_loader;
+pub mod include_validation;
+pub mod dependency_lock;
+pub mod package_manifest;
+pub mod yaml_diagnostics;
 pub mod validators;
 mod parsers;
 
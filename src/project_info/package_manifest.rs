@@ -0,0 +1,126 @@
+use std::{collections::{BTreeMap, BTreeSet}, fs, path::{Path, PathBuf}};
+
+use serde::{Deserialize, Serialize};
+
+use super::{final_project_data::FinalProjectData, gcmake_constants::PROJECT_LOCAL_PACKAGE_MANIFESTS_DIR, raw_data_in::{OutputItemType, SpecificCompilerSpecifier}};
+
+const MANIFEST_FILE_HEADER: &'static str = "# Generated by gcmake. Describes this project to projects which use it as a GCMake dependency.\n";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PackageManifestTarget {
+  pub output_type: OutputItemType
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PackageManifestFeature {
+  pub enabled_by_default: bool
+}
+
+// Forward-declared information about a GCMake project. Lets a project validate links and features
+// of a GCMake dependency before a CMake configure has downloaded the dependency.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct PackageManifest {
+  pub name: String,
+  pub version: String,
+  // Every library in the project tree (excluding tests), keyed by the name used to link to it.
+  pub targets: BTreeMap<String, PackageManifestTarget>,
+  #[serde(default)]
+  pub features: BTreeMap<String, PackageManifestFeature>,
+  pub supported_compilers: BTreeSet<SpecificCompilerSpecifier>,
+  pub supports_emscripten: bool,
+  pub can_trivially_cross_compile: bool
+}
+
+fn collect_library_targets(project: &FinalProjectData, targets: &mut BTreeMap<String, PackageManifestTarget>) {
+  for (output_name, output) in project.get_outputs() {
+    if output.is_library_type() {
+      targets.insert(output_name.clone(), PackageManifestTarget {
+        output_type: output.get_output_type().clone()
+      });
+    }
+  }
+
+  for (_, subproject) in project.get_subprojects() {
+    collect_library_targets(subproject, targets);
+  }
+}
+
+impl PackageManifest {
+  pub fn from_project(root_project: &FinalProjectData) -> Self {
+    let mut targets: BTreeMap<String, PackageManifestTarget> = BTreeMap::new();
+    collect_library_targets(root_project, &mut targets);
+
+    return Self {
+      name: root_project.get_project_base_name().to_string(),
+      version: root_project.version.to_string(),
+      targets,
+      features: root_project.get_features().iter()
+        .map(|(feature_name, feature_config)| (
+          feature_name.clone(),
+          PackageManifestFeature { enabled_by_default: feature_config.is_enabled_by_default }
+        ))
+        .collect(),
+      supported_compilers: root_project.get_supported_compilers().iter().cloned().collect(),
+      supports_emscripten: root_project.supports_emscripten(),
+      can_trivially_cross_compile: root_project.can_trivially_cross_compile()
+    };
+  }
+
+  // Manifests of GCMake dependencies are copied into the dependent root project's
+  // .gcmake/package-manifests/ directory, named after the dependency.
+  pub fn local_manifest_path(project_root: impl AsRef<Path>, dep_name: &str) -> PathBuf {
+    return project_root.as_ref()
+      .join(PROJECT_LOCAL_PACKAGE_MANIFESTS_DIR)
+      .join(format!("{}.yaml", dep_name));
+  }
+
+  pub fn load_for_dependency(project_root: impl AsRef<Path>, dep_name: &str) -> Result<Option<Self>, String> {
+    let manifest_path: PathBuf = Self::local_manifest_path(project_root, dep_name);
+
+    if !manifest_path.is_file() {
+      return Ok(None);
+    }
+
+    let file_contents: String = fs::read_to_string(&manifest_path)
+      .map_err(|err| format!("Failed to read package manifest {}: {}", manifest_path.to_str().unwrap(), err.to_string()))?;
+
+    return serde_yaml::from_str(&file_contents)
+      .map(Some)
+      .map_err(|err| format!("Failed to parse package manifest {}: {}", manifest_path.to_str().unwrap(), err.to_string()));
+  }
+
+  pub fn to_file_contents(&self) -> Result<String, String> {
+    return serde_yaml::to_string(self)
+      .map(|serialized| format!("{}{}", MANIFEST_FILE_HEADER, serialized))
+      .map_err(|err| err.to_string());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_manifest_round_trips() {
+    let manifest = PackageManifest {
+      name: String::from("some-lib"),
+      version: String::from("1.2.0"),
+      targets: BTreeMap::from([
+        (String::from("some-lib"), PackageManifestTarget { output_type: OutputItemType::CompiledLib }),
+        (String::from("helpers"), PackageManifestTarget { output_type: OutputItemType::HeaderOnlyLib })
+      ]),
+      features: BTreeMap::from([
+        (String::from("use-fmt"), PackageManifestFeature { enabled_by_default: false })
+      ]),
+      supported_compilers: BTreeSet::from([SpecificCompilerSpecifier::GCC, SpecificCompilerSpecifier::Clang]),
+      supports_emscripten: false,
+      can_trivially_cross_compile: true
+    };
+
+    let file_contents: String = manifest.to_file_contents().unwrap();
+    assert_eq!(serde_yaml::from_str::<PackageManifest>(&file_contents).unwrap(), manifest);
+  }
+}
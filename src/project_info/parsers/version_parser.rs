@@ -2,7 +2,7 @@ pub fn parse_version(version_str: &str) -> Option<ThreePartVersion> {
   return ThreePartVersion::from_str(version_str);
 }
 
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Clone)]
 pub struct ThreePartVersion {
   pub major: u32,
   pub minor: u32,
@@ -3,7 +3,7 @@ pub mod user_given_dep_config;
 
 use std::{cell::RefCell, collections::{HashMap, HashSet}, fs::{DirEntry, self}, path::{PathBuf, Path}, rc::Rc};
 
-use crate::program_actions::gcmake_dep_config_dir;
+use crate::program_actions::{configured_dep_config_repos, DepConfigRepo, DepConfigRepoSource};
 
 use self::internal_dep_config::{SingleRawPredefinedDependencyConfigGroup, RawPredefinedDependencyInfo, PredefinedCMakeDepHookFile};
 use colored::*;
@@ -13,8 +13,12 @@ type InternalDepHashMap = HashMap<String, RawPredefinedDependencyInfo>;
 // This is a lazy "HashMap" of predefined dependency configurations.
 // Since loading dependency configurations can be expensive (requires multiple file reads per dependency),
 // configs are only loaded when first requested.
+//
+// Configs can come from multiple repositories. When more than one repository contains a config with the
+// same name, the config from the repository with the highest precedence is used.
 pub struct RawPredefinedDependencyMap {
-  dep_config_dir: PathBuf,
+  // Maps each dependency name to the config directory which provides it.
+  config_dirs_by_name: HashMap<String, PathBuf>,
   allowed_config_names: HashSet<String>,
   configs: RefCell<InternalDepHashMap>
 }
@@ -40,7 +44,8 @@ impl RawPredefinedDependencyMap {
       // return Err(format!("Tried to retrieve configuration for dependency '{}', which doesn't exist. Did you misspell the dependency name?", config_name.yellow()))
     }
 
-    let mut config_file_path: PathBuf = self.dep_config_dir.join(config_name);
+    let config_dir: &Path = self.config_dirs_by_name.get(config_name).unwrap().as_path();
+    let mut config_file_path: PathBuf = config_dir.join(config_name);
     config_file_path.push("dep_config.yaml");
 
     let config_file_contents: String = fs::read_to_string(&config_file_path)
@@ -55,8 +60,7 @@ impl RawPredefinedDependencyMap {
       ))?;
 
     let find_module_base_name: &str = dep_configs.get_common()?.find_module_base_name().unwrap_or(config_name);
-    
-    let config_dir: &Path = self.dep_config_dir.as_path();
+
     let dep_config_container = RawPredefinedDependencyInfo {
       custom_find_module: load_hook_file(
         config_dir,
@@ -90,7 +94,8 @@ impl RawPredefinedDependencyMap {
     }
   }
 
-  pub fn new(dep_config_repo_dir: &Path) -> Result<Self, String> {
+  // Repositories are given in order of precedence, highest first.
+  pub fn new(dep_config_repos: &[DepConfigRepo]) -> Result<Self, String> {
     /*
       Whole bunch of TODOS related to the new dependency configuration system.
       ================================================================================
@@ -106,34 +111,82 @@ impl RawPredefinedDependencyMap {
           checkout the given branch, and pull the latest changes on that branch.
     */
 
-    if !dep_config_repo_dir.is_dir() {
-      return Err(format!(
-        "Failed to retrieve dependency information because the 'external dependency configuration repository' was not found on the local system (should be at {}). Running `gcmake dep-config update` should fix the issue.",
-        dep_config_repo_dir.to_str().unwrap()
-      ));
+    let mut config_dirs_by_name: HashMap<String, PathBuf> = HashMap::new();
+
+    for repo in dep_config_repos {
+      let dep_config_repo_dir: &Path = repo.local_dir.as_path();
+
+      if !dep_config_repo_dir.is_dir() {
+        return Err(match &repo.source {
+          DepConfigRepoSource::GitRepo { .. } => format!(
+            "Failed to retrieve dependency information because the 'external dependency configuration repository' '{}' was not found on the local system (should be at {}). Running `gcmake dep-config update` should fix the issue.",
+            repo.name,
+            dep_config_repo_dir.to_str().unwrap()
+          ),
+          DepConfigRepoSource::LocalDir => format!(
+            "Failed to retrieve dependency information because the dependency configuration directory '{}' does not exist.",
+            dep_config_repo_dir.to_str().unwrap()
+          )
+        });
+      }
+
+      for dep_dir_name in config_names_in_dir(dep_config_repo_dir)? {
+        // Earlier repositories take precedence, so names which were already found are skipped.
+        config_dirs_by_name.entry(dep_dir_name)
+          .or_insert_with(|| dep_config_repo_dir.to_path_buf());
+      }
     }
 
-    let mut allowed_config_names: HashSet<String> = HashSet::new();
+    return Ok(Self {
+      allowed_config_names: config_dirs_by_name.keys().cloned().collect(),
+      config_dirs_by_name,
+      configs: RefCell::new(HashMap::new())
+    });
+  }
 
-    let dir_data = fs::read_dir(&dep_config_repo_dir)
-      .map_err(|err| err.to_string())?;
+  // Returns a copy of this map which consults the given project-local config directory before any of
+  // the configured repositories. Returns None when the project doesn't have any local configs.
+  pub fn with_project_local_configs(&self, local_config_dir: &Path) -> Result<Option<Self>, String> {
+    if !local_config_dir.is_dir() {
+      return Ok(None);
+    }
 
-    for maybe_entry in dir_data {
-      let entry: DirEntry = maybe_entry.map_err(|err| err.to_string())?;
-      let entry_path: PathBuf = entry.path();
-      let dep_dir_name: &str = entry_path.file_name().unwrap().to_str().unwrap();
+    let mut config_dirs_by_name: HashMap<String, PathBuf> = self.config_dirs_by_name.clone();
 
-      if entry_path.is_dir() && !dep_dir_name.starts_with('.') {
-        allowed_config_names.insert(dep_dir_name.to_string());
-      }
+    for dep_dir_name in config_names_in_dir(local_config_dir)? {
+      config_dirs_by_name.insert(dep_dir_name, local_config_dir.to_path_buf());
     }
 
-    return Ok(Self {
-      allowed_config_names,
-      dep_config_dir: dep_config_repo_dir.to_path_buf(),
+    return Ok(Some(Self {
+      allowed_config_names: config_dirs_by_name.keys().cloned().collect(),
+      config_dirs_by_name,
       configs: RefCell::new(HashMap::new())
-    });
+    }));
+  }
+
+  // Returns the directory containing the given dependency's config directory.
+  pub fn config_root_dir_for(&self, config_name: &str) -> Option<&Path> {
+    self.config_dirs_by_name.get(config_name).map(|dir| dir.as_path())
+  }
+}
+
+fn config_names_in_dir(dep_config_repo_dir: &Path) -> Result<Vec<String>, String> {
+  let mut config_names: Vec<String> = Vec::new();
+
+  let dir_data = fs::read_dir(&dep_config_repo_dir)
+    .map_err(|err| err.to_string())?;
+
+  for maybe_entry in dir_data {
+    let entry: DirEntry = maybe_entry.map_err(|err| err.to_string())?;
+    let entry_path: PathBuf = entry.path();
+    let dep_dir_name: &str = entry_path.file_name().unwrap().to_str().unwrap();
+
+    if entry_path.is_dir() && !dep_dir_name.starts_with('.') {
+      config_names.push(dep_dir_name.to_string());
+    }
   }
+
+  return Ok(config_names);
 }
 
 fn load_hook_file(
@@ -158,5 +211,5 @@ fn load_hook_file(
 }
 
 pub fn all_raw_supported_dependency_configs() -> Result<RawPredefinedDependencyMap, String> {
-  return RawPredefinedDependencyMap::new(gcmake_dep_config_dir().as_path());
+  return RawPredefinedDependencyMap::new(&configured_dep_config_repos()?);
 }
\ No newline at end of file
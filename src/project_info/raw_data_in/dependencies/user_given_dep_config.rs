@@ -1,5 +1,6 @@
 use std::collections::{HashSet, HashMap};
 
+use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -20,7 +21,7 @@ predefined_dependencies:
   nlohmann_json:
     git_tag: v3.11.2
 */
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct UserGivenPredefinedDependencyConfig {
   // URL mode options
@@ -48,12 +49,14 @@ impl UserGivenPredefinedDependencyConfig {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct UserGivenGCMakeProjectDependency {
   pub git_tag: Option<String>,
   pub commit_hash: Option<String>,
-  pub repo_url: String,
+  pub repo_url: Option<String>,
+  // Alternative to repo_url for projects checked out locally. Relative to the dependent project's root.
+  pub path: Option<String>,
 
   pub use_default_features: Option<bool>,
   pub features: Option<HashSet<String>>
@@ -1,14 +1,47 @@
 use std::collections::{HashMap, HashSet, BTreeSet, BTreeMap};
 use enum_iterator::Sequence;
+use schemars::{JsonSchema, gen::SchemaGenerator, schema::{Schema, SchemaObject, InstanceType, ObjectValidation}};
 use serde::{Serialize, Deserialize};
 
+use crate::logger::WarningId;
 use super::{dependencies::user_given_dep_config::{UserGivenPredefinedDependencyConfig}, project_common_types::{PredefinedDepMap, GCMakeDepMap}};
 
 pub type BuildTypeOptionMap = BTreeMap<BuildConfigCompilerSpecifier, RawBuildConfig>;
 pub type BuildConfigMap = BTreeMap<BuildType, BuildTypeOptionMap>;
 pub type TargetBuildConfigMap = BTreeMap<TargetSpecificBuildType, BuildTypeOptionMap>;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+// schemars describes maps as objects with arbitrary keys. These also restrict the keys
+// to the enum's values, so editors can complete build type and compiler names.
+fn enum_keyed_map_schema<K: JsonSchema>(generator: &mut SchemaGenerator, value_schema: Schema) -> Schema {
+  return SchemaObject {
+    instance_type: Some(InstanceType::Object.into()),
+    object: Some(Box::new(ObjectValidation {
+      property_names: Some(Box::new(generator.subschema_for::<K>())),
+      additional_properties: Some(Box::new(value_schema)),
+      ..Default::default()
+    })),
+    ..Default::default()
+  }.into();
+}
+
+fn build_type_option_map_schema(generator: &mut SchemaGenerator) -> Schema {
+  let value_schema: Schema = generator.subschema_for::<RawBuildConfig>();
+  return enum_keyed_map_schema::<BuildConfigCompilerSpecifier>(generator, value_schema);
+}
+
+fn build_config_map_schema(generator: &mut SchemaGenerator) -> Schema {
+  let value_schema: Schema = build_type_option_map_schema(generator);
+  return enum_keyed_map_schema::<BuildType>(generator, value_schema);
+}
+
+fn optional_target_build_config_map_schema(generator: &mut SchemaGenerator) -> Schema {
+  let value_schema: Schema = build_type_option_map_schema(generator);
+  let mut schema_object: SchemaObject = enum_keyed_map_schema::<TargetSpecificBuildType>(generator, value_schema).into_object();
+  schema_object.instance_type = Some(vec![InstanceType::Object, InstanceType::Null].into());
+  return schema_object.into();
+}
+
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct LanguageConfigMap {
   pub c: Option<SingleLanguageConfig>,
@@ -16,7 +49,7 @@ pub struct LanguageConfigMap {
   pub cuda: Option<SingleLanguageConfig>
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub enum RawTestFramework {
   #[serde(rename = "catch2")]
@@ -27,14 +60,14 @@ pub enum RawTestFramework {
   DocTest(UserGivenPredefinedDependencyConfig),
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub enum RawDocGeneratorName {
   Doxygen,
   Sphinx
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct RawDocumentationGeneratorConfig {
   pub generator: RawDocGeneratorName,
@@ -60,13 +93,13 @@ impl RawTestFramework {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct RawShortcutConfig {
   pub name: String
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct RawInstallerConfig {
   pub title: Option<String>,
@@ -75,7 +108,7 @@ pub struct RawInstallerConfig {
   pub shortcuts: Option<HashMap<String, RawShortcutConfig>>
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub enum DefaultCompiledLibType {
   Static,
@@ -83,7 +116,7 @@ pub enum DefaultCompiledLibType {
 }
 
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct RawGlobalPropertyConfig {
   pub ipo_enabled_by_default_for: Option<BTreeSet<BuildType>>,
@@ -92,16 +125,25 @@ pub struct RawGlobalPropertyConfig {
   // TODO: Add option for setting default Emscripten mode.
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct RawFeatureConfig {
   pub default: bool,
   pub enables: Option<HashSet<String>>
 }
 
+// Lists of warning IDs. Only the root project can set these, and they apply to warnings from
+// the whole project tree, including its dependencies.
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RawWarningConfig {
+  pub deny: Option<BTreeSet<WarningId>>,
+  pub allow: Option<BTreeSet<WarningId>>
+}
+
 fn make_none<T>() -> Option<T> { None }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct RawProject {
   pub name: String,
@@ -123,6 +165,8 @@ pub struct RawProject {
     with = "serde_yaml::with::singleton_map",
     default = "make_none"
   )]
+  // A singleton map is the same shape as an externally tagged enum.
+  #[schemars(with = "Option<RawTestFramework>")]
   pub test_framework: Option<RawTestFramework>,
 
   pub output: HashMap<String, RawCompiledItem>,
@@ -130,7 +174,9 @@ pub struct RawProject {
   pub global_properties: Option<RawGlobalPropertyConfig>,
   pub predefined_dependencies: Option<PredefinedDepMap>,
   pub gcmake_dependencies: Option<GCMakeDepMap>,
-  pub build_configs: BuildConfigMap
+  #[schemars(schema_with = "build_config_map_schema")]
+  pub build_configs: BuildConfigMap,
+  pub warnings: Option<RawWarningConfig>
 }
 
 impl RawProject {
@@ -162,23 +208,25 @@ pub enum ImplementationLanguage {
   Cpp
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct PreBuildConfigIn {
   pub link: Option<Vec<String>>,
   pub language_features: Option<Vec<String>>,
+  #[serde(default)]
+  #[schemars(schema_with = "optional_target_build_config_map_schema")]
   pub build_config: Option<TargetBuildConfigMap>,
   pub generated_code: Option<BTreeSet<String>>
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct SingleLanguageConfig {
   pub min_standard: String,
   pub exact_standard: Option<String>
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord, Sequence)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Eq, PartialEq, Hash, Clone, Copy, PartialOrd, Ord, Sequence)]
 #[serde(deny_unknown_fields)]
 pub enum BuildType {
   Debug,
@@ -198,7 +246,7 @@ impl BuildType {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Eq, PartialEq, Hash, Clone, PartialOrd, Ord)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Eq, PartialEq, Hash, Clone, PartialOrd, Ord)]
 #[serde(deny_unknown_fields)]
 pub enum BuildConfigCompilerSpecifier {
   AllCompilers,
@@ -223,7 +271,7 @@ impl BuildConfigCompilerSpecifier {
 }
 
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct RawBuildConfig {
   pub compiler_flags: Option<Vec<String>>,
@@ -232,7 +280,7 @@ pub struct RawBuildConfig {
   pub defines: Option<Vec<String>>
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub enum OutputItemType {
   Executable,
@@ -254,7 +302,7 @@ impl OutputItemType {
   }
 }
 
-#[derive(Serialize, Deserialize, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Serialize, Deserialize, JsonSchema, Hash, Debug, Eq, PartialEq, PartialOrd, Ord, Clone, Copy)]
 #[serde(deny_unknown_fields)]
 pub enum SpecificCompilerSpecifier {
   GCC,
@@ -276,7 +324,7 @@ impl SpecificCompilerSpecifier {
   }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Hash, PartialEq, Eq, Ord, PartialOrd)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug, Clone, Hash, PartialEq, Eq, Ord, PartialOrd)]
 pub enum TargetSpecificBuildType {
   AllConfigs,
   Debug,
@@ -304,7 +352,7 @@ impl TargetSpecificBuildType {
   }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
 #[serde(deny_unknown_fields, untagged)]
 pub enum LinkSection {
   Uncategorized(Vec<String>),
@@ -317,6 +365,16 @@ pub enum LinkSection {
 pub type LanguageFeatureSection = LinkSection;
 
 impl LinkSection {
+  pub fn specifiers(&self) -> Vec<&String> {
+    return match self {
+      Self::Uncategorized(links) => links.iter().collect(),
+      Self::PublicPrivateCategorized { public, private } => public.iter()
+        .chain(private.iter())
+        .flatten()
+        .collect()
+    }
+  }
+
   pub fn add_exe_link(
     &mut self,
     container_name: &str,
@@ -336,7 +394,7 @@ impl LinkSection {
   }
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Clone, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct RawCompiledItem {
   pub requires_custom_main: Option<bool>, // Used for tests executables only
@@ -345,6 +403,8 @@ pub struct RawCompiledItem {
   pub windows_icon: Option<String>, 
   pub emscripten_html_shell: Option<String>,
   pub defines: Option<Vec<String>>,
+  #[serde(default)]
+  #[schemars(schema_with = "optional_target_build_config_map_schema")]
   pub build_config: Option<TargetBuildConfigMap>,
   pub link: Option<LinkSection>,
   // Language features are parsed and "inherited" the exact same way as links, so we
@@ -1,9 +1,10 @@
 use std::collections::{HashMap, BTreeSet, BTreeMap};
+use schemars::JsonSchema;
 use serde::{Serialize, Deserialize};
 
 use super::{raw_project_in::{RawCompiledItem, RawProject, BuildType}, PreBuildConfigIn, LanguageConfigMap};
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct RawSubproject {
   // If possible, should be the same as the project name
@@ -61,13 +62,14 @@ impl Into<RawProject> for RawSubproject {
       global_properties: None,
       output: self.output,
       predefined_dependencies: None,
-      gcmake_dependencies: None
+      gcmake_dependencies: None,
+      warnings: None
     }
   }
 }
 
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, JsonSchema, Debug)]
 #[serde(deny_unknown_fields)]
 pub struct RawTestProject {
   include_prefix: String,
@@ -0,0 +1,313 @@
+use std::{fmt, fs, ops::Range, path::{Path, PathBuf}};
+
+use colored::*;
+use regex::Regex;
+
+use crate::common::yaml_editing::{without_comment, YamlDocument};
+
+use super::{helpers::yaml_names_from_dir, path_manipulation::{cleaned_pathbuf, unix_style}};
+
+lazy_static! {
+  // serde_yaml appends the error location to its messages. Diagnostics show the location separately.
+  static ref SERDE_LOCATION_REGEX: Regex = Regex::new(r" at line \d+ column \d+").unwrap();
+}
+
+// Line and column are 1-based. Column and length are counted in characters.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SourceSpan {
+  pub line: usize,
+  pub column: usize,
+  pub length: usize
+}
+
+fn is_identifier_char(some_char: char) -> bool {
+  return some_char.is_alphanumeric() || some_char == '_' || some_char == '-';
+}
+
+// Finds the first occurrence of needle which isn't just part of a longer identifier.
+fn find_standalone(haystack: &str, needle: &str, search_from: usize) -> Option<usize> {
+  if needle.is_empty() {
+    return None;
+  }
+
+  let mut search_start: usize = search_from;
+
+  while let Some(relative_index) = haystack.get(search_start..).and_then(|rest| rest.find(needle)) {
+    let match_start: usize = search_start + relative_index;
+    let match_end: usize = match_start + needle.len();
+
+    let is_bounded: bool = !haystack[..match_start].chars().next_back().map_or(false, is_identifier_char)
+      && !haystack[match_end..].chars().next().map_or(false, is_identifier_char);
+
+    if is_bounded {
+      return Some(match_start);
+    }
+
+    search_start = match_start + needle.chars().next().unwrap().len_utf8();
+  }
+
+  return None;
+}
+
+fn span_from_byte_range(line_index: usize, line: &str, byte_range: Range<usize>) -> SourceSpan {
+  return SourceSpan {
+    line: line_index + 1,
+    column: line[..byte_range.start].chars().count() + 1,
+    length: line[byte_range].chars().count().max(1)
+  };
+}
+
+// Locates keys and values in a YAML document, so that errors found after deserialization can
+// still point to where the offending value was written. Keys can only be found in block
+// mappings, but values are searched for in every line nested under their key.
+pub struct YamlSpanMap {
+  document: YamlDocument
+}
+
+impl YamlSpanMap {
+  pub fn new(file_contents: &str) -> Self {
+    return Self {
+      document: YamlDocument::new(file_contents)
+    };
+  }
+
+  pub fn key_span(&self, key_path: &[&str]) -> Option<SourceSpan> {
+    let (key_line, key_range) = self.document.key_position(key_path)?;
+    return Some(span_from_byte_range(key_line, self.document.line(key_line)?, key_range));
+  }
+
+  // Finds the first place value is written inside the section for key_path. An empty key_path
+  // searches the whole document.
+  pub fn value_span(&self, key_path: &[&str], value: &str) -> Option<SourceSpan> {
+    let (searched_lines, first_line_offset) = if key_path.is_empty() {
+      (0..self.document.num_lines(), 0)
+    }
+    else {
+      let (_, key_range) = self.document.key_position(key_path)?;
+      // Skip past the key and its colon.
+      (self.document.key_block_lines(key_path)?, key_range.end + 1)
+    };
+
+    let first_line: usize = searched_lines.start;
+
+    for line_index in searched_lines {
+      let line: &str = self.document.line(line_index)?;
+      let search_from: usize = if line_index == first_line { first_line_offset } else { 0 };
+
+      if let Some(byte_index) = find_standalone(without_comment(line), value, search_from) {
+        return Some(span_from_byte_range(line_index, line, byte_index..byte_index + value.len()));
+      }
+    }
+
+    return None;
+  }
+
+  // line_number is 1-based, like in a SourceSpan.
+  pub fn line(&self, line_number: usize) -> Option<&str> {
+    return self.document.line(line_number.checked_sub(1)?);
+  }
+}
+
+// An error in a cmake_data.yaml file, rendered with the offending line and a caret underline.
+pub struct YamlDiagnostic {
+  pub message: String,
+  pub file_path: PathBuf,
+  pub span: Option<SourceSpan>,
+  source_line: Option<String>
+}
+
+impl YamlDiagnostic {
+  pub fn new(
+    message: impl Into<String>,
+    file_path: impl AsRef<Path>,
+    span: Option<SourceSpan>,
+    span_map: &YamlSpanMap
+  ) -> Self {
+    return Self {
+      message: message.into(),
+      file_path: file_path.as_ref().to_path_buf(),
+      source_line: span.and_then(|the_span| span_map.line(the_span.line)).map(String::from),
+      span
+    };
+  }
+
+  pub fn from_serde_error(
+    error: &serde_yaml::Error,
+    file_path: impl AsRef<Path>,
+    file_contents: &str
+  ) -> Self {
+    let span_map = YamlSpanMap::new(file_contents);
+
+    let span: Option<SourceSpan> = error.location().map(|location| {
+      // Underline the token the error points to. Keys are underlined without their colon.
+      let token_length: usize = span_map.line(location.line())
+        .map(|line| line.chars()
+          .skip(location.column() - 1)
+          .take_while(|some_char| !some_char.is_whitespace() && !",[]{}".contains(*some_char))
+          .collect::<String>()
+        )
+        .map_or(1, |token| token.trim_end_matches(':').chars().count().max(1));
+
+      SourceSpan {
+        line: location.line(),
+        column: location.column(),
+        length: token_length
+      }
+    });
+
+    return Self::new(
+      SERDE_LOCATION_REGEX.replace(&error.to_string(), "").to_string(),
+      file_path,
+      span,
+      &span_map
+    );
+  }
+}
+
+impl fmt::Display for YamlDiagnostic {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let display_path: String = unix_style(cleaned_pathbuf(&self.file_path));
+
+    writeln!(f, "{}", self.message)?;
+
+    let (span, source_line) = match (&self.span, &self.source_line) {
+      (Some(span), Some(source_line)) => (span, source_line),
+      _ => return write!(f, "  {} {}", "-->".blue(), display_path)
+    };
+
+    let line_number: String = span.line.to_string();
+    let gutter_padding: String = " ".repeat(line_number.len());
+
+    // Tabs are kept in the underline so it stays aligned with the source line.
+    let underline_padding: String = source_line.chars()
+      .take(span.column - 1)
+      .map(|some_char| if some_char == '\t' { '\t' } else { ' ' })
+      .collect();
+
+    writeln!(f, "{}{} {}:{}:{}", gutter_padding, "-->".blue(), display_path, span.line, span.column)?;
+    writeln!(f, "{} {}", gutter_padding, "|".blue())?;
+    writeln!(f, "{} {} {}", line_number.blue(), "|".blue(), source_line)?;
+    write!(f, "{} {} {}{}", gutter_padding, "|".blue(), underline_padding, "^".repeat(span.length).red())
+  }
+}
+
+// A project's cmake_data.yaml, read only once an error needs to point into it.
+pub struct YamlSourceFile {
+  pub file_path: PathBuf,
+  pub span_map: YamlSpanMap
+}
+
+impl YamlSourceFile {
+  pub fn find_in_project(project_root: &Path) -> Option<Self> {
+    for possible_cmake_data_file in yaml_names_from_dir(project_root) {
+      if let Ok(file_contents) = fs::read_to_string(&possible_cmake_data_file) {
+        return Some(Self {
+          file_path: possible_cmake_data_file,
+          span_map: YamlSpanMap::new(&file_contents)
+        });
+      }
+    }
+
+    return None;
+  }
+
+  // Points to value inside the key_path section, or to the section's key if no value is given
+  // or the value isn't found.
+  pub fn diagnostic_for(&self, message: impl Into<String>, key_path: &[&str], maybe_value: Option<&str>) -> YamlDiagnostic {
+    let span: Option<SourceSpan> = maybe_value
+      .and_then(|value| self.span_map.value_span(key_path, value))
+      .or_else(|| self.span_map.key_span(key_path));
+
+    return YamlDiagnostic::new(message, &self.file_path, span, &self.span_map);
+  }
+}
+
+// Adds a location in the project's cmake_data.yaml to an error message. The message is
+// returned as-is if the project's cmake_data.yaml can't be read.
+pub fn locate_in_project_yaml(
+  project_root: &Path,
+  message: String,
+  key_path: &[&str],
+  maybe_value: Option<&str>
+) -> String {
+  return match YamlSourceFile::find_in_project(project_root) {
+    Some(source_file) => source_file.diagnostic_for(message, key_path, maybe_value).to_string(),
+    None => message
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::project_info::final_project_data::ProjectLoadFailureReason;
+
+  // Colors are on or off depending on the terminal, so rendered diagnostics are compared
+  // without them.
+  fn without_ansi_codes(text: &str) -> String {
+    return Regex::new(r"\x1b\[[0-9;]*m").unwrap().replace_all(text, "").to_string();
+  }
+
+  const SAMPLE_YAML: &'static str = "\
+name: sample # the project name
+features:
+  extras:
+    enables: [ other-extras ]
+output:
+  sample:
+    output_type: CompiledLib
+    link:
+    - dep::some-lib
+    - dep::lib
+gcmake_dependencies:
+  dep:
+    features: [ extras, \"#not-a-comment\" ]
+";
+
+  #[test]
+  fn test_span_map_locates_nested_keys_and_values() {
+    let span_map = YamlSpanMap::new(SAMPLE_YAML);
+
+    assert_eq!(span_map.key_span(&["output", "sample", "link"]), Some(SourceSpan { line: 8, column: 5, length: 4 }));
+    assert_eq!(span_map.value_span(&["output", "sample", "link"], "dep::lib"), Some(SourceSpan { line: 10, column: 7, length: 8 }));
+    assert_eq!(span_map.value_span(&["gcmake_dependencies", "dep", "features"], "extras"), Some(SourceSpan { line: 13, column: 17, length: 6 }));
+    assert_eq!(span_map.value_span(&["output", "sample", "link"], "extras"), None);
+    assert_eq!(span_map.value_span(&[], "#not-a-comment"), Some(SourceSpan { line: 13, column: 26, length: 14 }));
+  }
+
+  #[test]
+  fn test_serde_errors_point_to_offending_key() {
+    let file_contents: &str = "name: sample\nversion: 1.0.0\nbad_key: true\n";
+    let error: serde_yaml::Error = serde_yaml::from_str::<NameAndVersion>(file_contents).unwrap_err();
+    let diagnostic = YamlDiagnostic::from_serde_error(&error, "cmake_data.yaml", file_contents);
+
+    assert_eq!(diagnostic.span, Some(SourceSpan { line: 3, column: 1, length: 7 }));
+    assert!(!diagnostic.message.contains("at line"));
+    assert!(without_ansi_codes(&diagnostic.to_string()).ends_with("--> cmake_data.yaml:3:1\n  |\n3 | bad_key: true\n  | ^^^^^^^"));
+  }
+
+  #[test]
+  fn test_load_failures_keep_diagnostic_location_when_given_context() {
+    let file_contents: &str = "name: sample\nversion: 1.0.0\nbad_key: true\n";
+    let error: serde_yaml::Error = serde_yaml::from_str::<NameAndVersion>(file_contents).unwrap_err();
+
+    let failure = ProjectLoadFailureReason::InvalidYaml(YamlDiagnostic::from_serde_error(&error, "subprojects/sample/cmake_data.yaml", file_contents))
+      .map_message(|err_message| format!("\t-> in subproject 'sample'\n{}", err_message));
+
+    match failure {
+      ProjectLoadFailureReason::InvalidYaml(diagnostic) => {
+        assert_eq!(diagnostic.file_path, PathBuf::from("subprojects/sample/cmake_data.yaml"));
+        assert_eq!(diagnostic.span, Some(SourceSpan { line: 3, column: 1, length: 7 }));
+        assert!(diagnostic.message.starts_with("\t-> in subproject 'sample'\nunknown field `bad_key`"));
+      },
+      _ => panic!("The diagnostic was flattened into a message")
+    }
+  }
+
+  #[derive(serde::Deserialize, Debug)]
+  #[serde(deny_unknown_fields)]
+  #[allow(dead_code)]
+  struct NameAndVersion {
+    name: String,
+    version: String
+  }
+}
@@ -0,0 +1,101 @@
+use std::{env, fs, path::{Path, PathBuf}, process};
+
+use crate::{program_actions::{DepConfigRepo, DepConfigRepoSource}, project_info::raw_data_in::dependencies::RawPredefinedDependencyMap};
+
+// A temporary directory for tests which load real project trees. Every test passes its own name,
+// so tests running in parallel never share a directory. The directory is removed on drop.
+pub struct TestProjectDir {
+  root: PathBuf
+}
+
+impl TestProjectDir {
+  pub fn new(test_name: &str) -> Self {
+    let root: PathBuf = env::temp_dir().join(format!("gcmake-{}-{}", test_name, process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+
+    return Self { root };
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.root
+  }
+
+  pub fn path_str(&self, relative_path: &str) -> String {
+    return self.root.join(relative_path).to_str().unwrap().to_string();
+  }
+
+  pub fn write_file(&self, relative_path: &str, contents: &str) {
+    let file_path: PathBuf = self.root.join(relative_path);
+    fs::create_dir_all(file_path.parent().unwrap()).unwrap();
+    fs::write(file_path, contents).unwrap();
+  }
+
+  // Loads the predefined dependency configs in relative_dir as if it were a cloned config repo.
+  pub fn dep_config_repo(&self, relative_dir: &str) -> RawPredefinedDependencyMap {
+    return RawPredefinedDependencyMap::new(&[DepConfigRepo {
+      name: String::from(relative_dir),
+      local_dir: self.root.join(relative_dir),
+      source: DepConfigRepoSource::LocalDir
+    }]).unwrap();
+  }
+
+  // Writes a root project with one executable named after the project. extra_sections is appended
+  // to its cmake_data.yaml as-is.
+  pub fn write_root_project(
+    &self,
+    relative_dir: &str,
+    project_name: &str,
+    exe_links: &[&str],
+    extra_sections: &str
+  ) {
+    let project_dir: PathBuf = Path::new(relative_dir).to_path_buf();
+
+    self.write_file(
+      project_dir.join("cmake_data.yaml").to_str().unwrap(),
+      &root_project_yaml(project_name, exe_links, extra_sections)
+    );
+    self.write_file(
+      project_dir.join("main.cpp").to_str().unwrap(),
+      "int main() { return 0; }\n"
+    );
+  }
+}
+
+impl Drop for TestProjectDir {
+  fn drop(&mut self) {
+    let _ = fs::remove_dir_all(&self.root);
+  }
+}
+
+pub fn root_project_yaml(project_name: &str, exe_links: &[&str], extra_sections: &str) -> String {
+  let link_section: String = if exe_links.is_empty()
+    { String::new() }
+    else {
+      exe_links.iter()
+        .map(|link_spec| format!("      - {}\n", link_spec))
+        .fold(String::from("    link:\n"), |section, link_line| section + &link_line)
+    };
+
+  return format!(
+    "name: {}\nvendor: some-vendor\ninclude_prefix: {}\ndescription: Project used in tests\nversion: 0.0.1\ndefault_build_type: Debug\nlanguages:\n  cpp:\n    min_standard: 17\nsupported_compilers:\n  - GCC\nbuild_configs:\n  Debug:\n    AllCompilers: {{}}\noutput:\n  {}-exe:\n    output_type: Executable\n    entry_file: main.cpp\n{}{}",
+    project_name,
+    project_name.to_uppercase().replace('-', "_"),
+    project_name,
+    link_section,
+    extra_sections
+  );
+}
+
+pub fn no_dep_configs() -> RawPredefinedDependencyMap {
+  return RawPredefinedDependencyMap::new(&[]).unwrap();
+}
+
+// A subdirectory dependency config with a single 'lib' target, downloaded using git.
+pub fn git_dep_config_yaml(dep_name: &str, repo_url: &str) -> String {
+  return format!(
+    "as_subdirectory:\n  namespace_config:\n    cmakelists_linking: \"{}::\"\n  download_info:\n    git_method:\n      repo_url: {}\n  target_configs:\n    lib: {{}}\n  can_cross_compile: true\n",
+    dep_name,
+    repo_url
+  );
+}